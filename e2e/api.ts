@@ -0,0 +1,95 @@
+import type { Page } from "@playwright/test";
+import { generateRandomString } from "./utils";
+
+// Defined by the bundled scripts of every page, adds the CSRF header
+declare function callApi(
+	url: string,
+	method: string,
+	body?: unknown,
+): Promise<Response>;
+
+export interface ApiResponse {
+	status: number;
+	body: string;
+}
+
+interface ApiRequest {
+	url: string;
+	method: string;
+	body?: unknown;
+}
+
+/** Sends a request from the page the same way the bundled scripts do */
+export function callPageApi(
+	page: Page,
+	url: string,
+	method: string,
+	body?: unknown,
+): Promise<ApiResponse> {
+	return page.evaluate(
+		async ({ url, method, body }: ApiRequest) => {
+			const res = await callApi(url, method, body);
+			return { status: res.status, body: await res.text() };
+		},
+		{ url, method, body },
+	);
+}
+
+/** Sends a request from the page with its cookies but without the CSRF header */
+export function callPageApiWithoutCsrfToken(
+	page: Page,
+	url: string,
+	method: string,
+	body?: unknown,
+): Promise<ApiResponse> {
+	return page.evaluate(
+		async ({ url, method, body }: ApiRequest) => {
+			const res = await fetch(url, {
+				method,
+				headers: { "Content-Type": "application/json" },
+				body: JSON.stringify(body),
+			});
+			return { status: res.status, body: await res.text() };
+		},
+		{ url, method, body },
+	);
+}
+
+export interface OpenVoting {
+	id: number;
+	candidateIds: number[];
+}
+
+/** Creates a voter group of the given tokens and returns its id */
+export async function createVoterGroup(
+	page: Page,
+	tokenIds: number[],
+): Promise<number> {
+	const group = await callPageApi(page, "/voter-groups", "POST", {
+		name: generateRandomString(),
+	});
+	const { id } = JSON.parse(group.body);
+	await callPageApi(page, `/voter-groups/${id}/tokens`, "PUT", { tokenIds });
+	return id;
+}
+
+/** Creates and opens a voting with two candidates for the given voter groups */
+export async function createOpenVoting(
+	page: Page,
+	eligibleGroups: number[],
+): Promise<OpenVoting> {
+	const created = await callPageApi(page, "/votings", "POST", {
+		name: generateRandomString(),
+		description: "",
+		hideVoteCounts: false,
+		numberOfWinners: 1,
+		candidates: [{ name: "Ehdokas A" }, { name: "Ehdokas B" }],
+		eligibleGroups,
+	});
+	const voting = JSON.parse(created.body);
+	await callPageApi(page, `/votings/${voting.id}`, "PATCH", { state: "Open" });
+	return {
+		id: voting.id,
+		candidateIds: voting.candidates.map(({ id }: { id: number }) => id),
+	};
+}
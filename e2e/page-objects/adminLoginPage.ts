@@ -3,15 +3,18 @@ import { AdminVotingsPage } from "./adminVotingsPage";
 
 // biome-ignore lint/style/noNonNullAssertion: Can't run tests without knowing admin password
 const ADMIN_PASSWORD = process.env.ADMIN_PASSWORD!;
+const ADMIN_USERNAME = process.env.ADMIN_USERNAME ?? "admin";
 
 export class AdminLoginPage {
-	private readonly tokenInput: Locator;
+	private readonly usernameInput: Locator;
+	private readonly passwordInput: Locator;
 	private readonly loginButton: Locator;
 
 	constructor(private readonly page: Page) {
-		this.tokenInput = this.page.getByRole("textbox", {
-			name: "Kirjautumiskoodi",
+		this.usernameInput = this.page.getByRole("textbox", {
+			name: "Käyttäjätunnus",
 		});
+		this.passwordInput = this.page.getByLabel("Salasana");
 		this.loginButton = this.page.getByRole("button", { name: "KIRJAUDU" });
 	}
 
@@ -20,9 +23,11 @@ export class AdminLoginPage {
 	}
 
 	public async login(
-		adminToken: string = ADMIN_PASSWORD,
+		username: string = ADMIN_USERNAME,
+		password: string = ADMIN_PASSWORD,
 	): Promise<AdminVotingsPage> {
-		await this.tokenInput.fill(adminToken);
+		await this.usernameInput.fill(username);
+		await this.passwordInput.fill(password);
 		await this.loginButton.click();
 		return new AdminVotingsPage(this.page);
 	}
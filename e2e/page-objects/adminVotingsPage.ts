@@ -8,7 +8,7 @@ export class AdminVotingsPage {
 	private readonly navBar: AdminNavBar;
 	private readonly createVotingBox: CreateVotingBox;
 
-	constructor(private readonly page: Page) {
+	constructor(public readonly page: Page) {
 		this.navBar = new AdminNavBar(this.page.getByRole("navigation"));
 		this.createVotingBox = new CreateVotingBox(this.page);
 	}
@@ -60,6 +60,11 @@ export class TokensPage {
 		return this.tokensTableBody.locator(`tr[data-token="${token}"]`);
 	}
 
+	public async getTokenId(token: string): Promise<number> {
+		const idCell = this.getTokenRow(token).getByRole("cell").nth(1);
+		return Number(await idCell.textContent());
+	}
+
 	public async activateToken(token: string) {
 		const tokenRow = this.getTokenRow(token);
 		await tokenRow.getByRole("button", { name: "Aktivoi" }).click();
@@ -5,7 +5,7 @@ export class VotingsPage {
 	private readonly navBar: NavBar;
 	private readonly loginStatusBox: Locator;
 
-	constructor(private readonly page: Page) {
+	constructor(public readonly page: Page) {
 		this.navBar = new NavBar(this.page.getByRole("navigation"));
 		this.loginStatusBox = this.page.getByTestId("login-status-box");
 	}
@@ -0,0 +1,31 @@
+import { expect } from "@playwright/test";
+import { callPageApi, callPageApiWithoutCsrfToken } from "../api";
+import { test } from "../fixtures";
+import { generateRandomString } from "../utils";
+
+test("Request without CSRF token is rejected", async ({ adminLoginPage }) => {
+	const adminVotingsPage = await adminLoginPage.login();
+	await adminVotingsPage.expectIsVisible();
+	const voting = {
+		name: generateRandomString(),
+		description: "",
+		hideVoteCounts: false,
+		numberOfWinners: 1,
+	};
+
+	const rejected = await callPageApiWithoutCsrfToken(
+		adminVotingsPage.page,
+		"/votings",
+		"POST",
+		voting,
+	);
+	expect(rejected.status).toBe(403);
+
+	const accepted = await callPageApi(
+		adminVotingsPage.page,
+		"/votings",
+		"POST",
+		voting,
+	);
+	expect(accepted.status).toBe(200);
+});
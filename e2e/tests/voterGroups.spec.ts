@@ -0,0 +1,68 @@
+import { expect } from "@playwright/test";
+import { callPageApi, createOpenVoting, createVoterGroup } from "../api";
+import { test } from "../fixtures";
+import { generateRandomString } from "../utils";
+
+test("Ineligible token cannot vote", async ({ adminLoginPage, loginPage }) => {
+	const adminVotingsPage = await adminLoginPage.login();
+	const adminTokensPage = await adminVotingsPage.goToTokens();
+	const [eligibleToken, ineligibleToken] =
+		await adminTokensPage.generateBulkTokens();
+	await adminTokensPage.activateToken(eligibleToken);
+	await adminTokensPage.activateToken(ineligibleToken);
+
+	const groupId = await createVoterGroup(adminTokensPage.page, [
+		await adminTokensPage.getTokenId(eligibleToken),
+	]);
+	const voting = await createOpenVoting(adminTokensPage.page, [groupId]);
+
+	const votingsPage = await loginPage.login({
+		alias: generateRandomString(),
+		token: ineligibleToken,
+	});
+	await votingsPage.expectIsVisible();
+
+	const vote = await callPageApi(votingsPage.page, "/votes/", "POST", {
+		candidates: [voting.candidateIds[0]],
+		voting_id: voting.id,
+	});
+	expect(vote.status).toBe(403);
+});
+
+test("Closing a voting waits only for eligible tokens", async ({
+	adminLoginPage,
+	loginPage,
+}) => {
+	const adminVotingsPage = await adminLoginPage.login();
+	const adminTokensPage = await adminVotingsPage.goToTokens();
+	const [eligibleToken, ineligibleToken] =
+		await adminTokensPage.generateBulkTokens();
+	await adminTokensPage.activateToken(eligibleToken);
+	await adminTokensPage.activateToken(ineligibleToken);
+
+	const groupId = await createVoterGroup(adminTokensPage.page, [
+		await adminTokensPage.getTokenId(eligibleToken),
+	]);
+	const voting = await createOpenVoting(adminTokensPage.page, [groupId]);
+
+	const votingsPage = await loginPage.login({
+		alias: generateRandomString(),
+		token: eligibleToken,
+	});
+	await votingsPage.expectIsVisible();
+
+	const vote = await callPageApi(votingsPage.page, "/votes/", "POST", {
+		candidates: [voting.candidateIds[0]],
+		voting_id: voting.id,
+	});
+	expect(vote.status).toBe(200);
+
+	// The ineligible token is active and has not voted
+	const close = await callPageApi(
+		adminTokensPage.page,
+		`/votings/${voting.id}`,
+		"PATCH",
+		{ state: "Closed" },
+	);
+	expect(close.status).toBe(200);
+});
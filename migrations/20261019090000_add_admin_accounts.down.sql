@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE admin_account;
+
+DROP TYPE admin_role;
@@ -0,0 +1,11 @@
+-- Add up migration script here
+
+CREATE TYPE admin_role AS ENUM ('superadmin', 'official', 'observer');
+
+CREATE TABLE admin_account (
+    id SERIAL PRIMARY KEY NOT NULL,
+    username text UNIQUE NOT NULL,
+    password_hash text NOT NULL,
+    role admin_role NOT NULL,
+    created_at timestamptz NOT NULL
+);
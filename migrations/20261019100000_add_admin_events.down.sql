@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+DROP TABLE admin_event;
+
+DROP FUNCTION reject_admin_event_modification;
+
+DROP TYPE admin_action;
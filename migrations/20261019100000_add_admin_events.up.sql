@@ -0,0 +1,34 @@
+-- Add up migration script here
+
+CREATE TYPE admin_action AS ENUM (
+    'create_voting',
+    'update_voting',
+    'close_voting',
+    'delete_voting',
+    'update_token',
+    'void_active_tokens',
+    'generate_tokens',
+    'delete_all_data'
+);
+
+--- admin_id is intentionally not a foreign key: events must outlive the accounts that created them
+CREATE TABLE admin_event (
+    id SERIAL PRIMARY KEY NOT NULL,
+    created_at timestamptz NOT NULL,
+    admin_id int NOT NULL,
+    admin_username text NOT NULL,
+    action admin_action NOT NULL,
+    target text,
+    before jsonb,
+    after jsonb
+);
+
+CREATE FUNCTION reject_admin_event_modification() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'admin_event is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER admin_event_append_only
+BEFORE UPDATE OR DELETE OR TRUNCATE ON admin_event
+FOR EACH STATEMENT EXECUTE FUNCTION reject_admin_event_modification();
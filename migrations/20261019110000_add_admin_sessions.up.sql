@@ -0,0 +1,11 @@
+-- Add up migration script here
+
+CREATE TABLE admin_session (
+    id uuid PRIMARY KEY NOT NULL,
+    admin_id int NOT NULL REFERENCES admin_account(id) ON DELETE CASCADE,
+    created_at timestamptz NOT NULL,
+    expires_at timestamptz NOT NULL,
+    revoked_at timestamptz
+);
+
+CREATE INDEX admin_session_admin_id_idx ON admin_session(admin_id);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE login_failure;
+DROP TYPE login_kind;
@@ -0,0 +1,14 @@
+-- Add up migration script here
+
+CREATE TYPE login_kind AS ENUM ('voter', 'admin');
+
+CREATE TABLE login_failure (
+    id SERIAL PRIMARY KEY NOT NULL,
+    created_at timestamptz NOT NULL,
+    kind login_kind NOT NULL,
+    ip text NOT NULL,
+    target text NOT NULL
+);
+
+CREATE INDEX login_failure_ip_idx ON login_failure(kind, ip, created_at);
+CREATE INDEX login_failure_target_idx ON login_failure(kind, target, created_at);
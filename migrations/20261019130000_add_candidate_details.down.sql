@@ -0,0 +1,46 @@
+-- Add down migration script here
+
+DROP TABLE candidate_image;
+
+ALTER TABLE vote ADD COLUMN candidate_name text;
+UPDATE vote SET candidate_name = c.name FROM candidate AS c WHERE c.id = vote.candidate_id;
+
+ALTER TABLE candidate_result_data ADD COLUMN name text;
+UPDATE candidate_result_data SET name = c.name FROM candidate AS c WHERE c.id = candidate_result_data.candidate_id;
+
+ALTER TABLE passing_candidate_result ADD COLUMN name text;
+UPDATE passing_candidate_result SET name = c.name FROM candidate AS c WHERE c.id = passing_candidate_result.candidate_id;
+
+ALTER TABLE voting_round_result ADD COLUMN dropped_candidate_name text;
+UPDATE voting_round_result SET dropped_candidate_name = c.name FROM candidate AS c WHERE c.id = voting_round_result.dropped_candidate_id;
+
+ALTER TABLE voting_round_result DROP CONSTRAINT fk_ensure_dropped_candidate_has_data;
+ALTER TABLE voting_round_result DROP COLUMN dropped_candidate_id;
+
+ALTER TABLE passing_candidate_result DROP COLUMN candidate_id;
+ALTER TABLE candidate_result_data DROP COLUMN candidate_id;
+ALTER TABLE vote DROP COLUMN candidate_id;
+
+ALTER TABLE candidate DROP CONSTRAINT candidate_voting_id_name_key;
+ALTER TABLE candidate DROP CONSTRAINT candidate_id_voting_id_key;
+ALTER TABLE candidate DROP CONSTRAINT candidate_pkey;
+ALTER TABLE candidate DROP COLUMN id;
+ALTER TABLE candidate DROP COLUMN bio;
+ALTER TABLE candidate DROP COLUMN display_order;
+ALTER TABLE candidate ADD PRIMARY KEY (voting_id, name);
+
+ALTER TABLE vote ALTER COLUMN candidate_name SET NOT NULL;
+ALTER TABLE vote ADD FOREIGN KEY (candidate_name, voting_id) REFERENCES candidate(name, voting_id) ON DELETE CASCADE;
+
+ALTER TABLE candidate_result_data ALTER COLUMN name SET NOT NULL;
+ALTER TABLE candidate_result_data ADD PRIMARY KEY (name, round, voting_id);
+ALTER TABLE candidate_result_data ADD FOREIGN KEY (voting_id, name) REFERENCES candidate(voting_id, name);
+
+ALTER TABLE passing_candidate_result ALTER COLUMN name SET NOT NULL;
+ALTER TABLE passing_candidate_result ADD PRIMARY KEY (voting_id, name, round);
+ALTER TABLE passing_candidate_result ADD FOREIGN KEY (voting_id, name, round) REFERENCES candidate_result_data(voting_id, name, round);
+
+ALTER TABLE voting_round_result
+ADD CONSTRAINT fk_ensure_dropped_candidate_has_data FOREIGN KEY (voting_id, dropped_candidate_name, round) REFERENCES candidate_result_data(voting_id, name, round);
+
+-- Postgres cannot drop a single enum value, so 'update_candidate_image' is left in admin_action
@@ -0,0 +1,78 @@
+-- Add up migration script here
+
+-- Candidates get a surrogate id so that they can be renamed without touching ballots or results
+ALTER TABLE candidate ADD COLUMN id SERIAL NOT NULL;
+ALTER TABLE candidate ADD COLUMN bio text;
+ALTER TABLE candidate ADD COLUMN display_order int NOT NULL DEFAULT 0;
+
+UPDATE candidate
+SET display_order = ordered.position
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY voting_id ORDER BY name) - 1 AS position
+    FROM candidate
+) AS ordered
+WHERE candidate.id = ordered.id;
+
+ALTER TABLE vote ADD COLUMN candidate_id int;
+UPDATE vote
+SET candidate_id = c.id
+FROM candidate AS c
+WHERE c.voting_id = vote.voting_id AND c.name = vote.candidate_name;
+
+ALTER TABLE candidate_result_data ADD COLUMN candidate_id int;
+UPDATE candidate_result_data
+SET candidate_id = c.id
+FROM candidate AS c
+WHERE c.voting_id = candidate_result_data.voting_id AND c.name = candidate_result_data.name;
+
+ALTER TABLE passing_candidate_result ADD COLUMN candidate_id int;
+UPDATE passing_candidate_result
+SET candidate_id = c.id
+FROM candidate AS c
+WHERE c.voting_id = passing_candidate_result.voting_id AND c.name = passing_candidate_result.name;
+
+ALTER TABLE voting_round_result ADD COLUMN dropped_candidate_id int;
+UPDATE voting_round_result
+SET dropped_candidate_id = c.id
+FROM candidate AS c
+WHERE c.voting_id = voting_round_result.voting_id AND c.name = voting_round_result.dropped_candidate_name;
+
+-- Drop everything that references candidates by name
+ALTER TABLE voting_round_result DROP CONSTRAINT fk_ensure_dropped_candidate_has_data;
+ALTER TABLE passing_candidate_result DROP CONSTRAINT passing_candidate_result_voting_id_name_round_fkey;
+ALTER TABLE passing_candidate_result DROP CONSTRAINT passing_candidate_result_pkey;
+ALTER TABLE candidate_result_data DROP CONSTRAINT candidate_result_data_voting_id_name_fkey;
+ALTER TABLE candidate_result_data DROP CONSTRAINT candidate_result_data_pkey;
+ALTER TABLE vote DROP CONSTRAINT vote_candidate_name_voting_id_fkey;
+ALTER TABLE candidate DROP CONSTRAINT candidate_pkey;
+
+ALTER TABLE candidate ADD PRIMARY KEY (id);
+ALTER TABLE candidate ADD CONSTRAINT candidate_id_voting_id_key UNIQUE (id, voting_id);
+-- Deferred so that candidates can swap names within one transaction
+ALTER TABLE candidate ADD CONSTRAINT candidate_voting_id_name_key UNIQUE (voting_id, name) DEFERRABLE INITIALLY DEFERRED;
+
+ALTER TABLE vote ALTER COLUMN candidate_id SET NOT NULL;
+ALTER TABLE vote DROP COLUMN candidate_name;
+ALTER TABLE vote ADD FOREIGN KEY (candidate_id, voting_id) REFERENCES candidate(id, voting_id) ON DELETE CASCADE;
+
+ALTER TABLE candidate_result_data ALTER COLUMN candidate_id SET NOT NULL;
+ALTER TABLE candidate_result_data DROP COLUMN name;
+ALTER TABLE candidate_result_data ADD PRIMARY KEY (candidate_id, round, voting_id);
+ALTER TABLE candidate_result_data ADD FOREIGN KEY (candidate_id, voting_id) REFERENCES candidate(id, voting_id);
+
+ALTER TABLE passing_candidate_result ALTER COLUMN candidate_id SET NOT NULL;
+ALTER TABLE passing_candidate_result DROP COLUMN name;
+ALTER TABLE passing_candidate_result ADD PRIMARY KEY (voting_id, candidate_id, round);
+ALTER TABLE passing_candidate_result ADD FOREIGN KEY (voting_id, candidate_id, round) REFERENCES candidate_result_data(voting_id, candidate_id, round);
+
+ALTER TABLE voting_round_result DROP COLUMN dropped_candidate_name;
+ALTER TABLE voting_round_result
+ADD CONSTRAINT fk_ensure_dropped_candidate_has_data FOREIGN KEY (voting_id, dropped_candidate_id, round) REFERENCES candidate_result_data(voting_id, candidate_id, round);
+
+CREATE TABLE candidate_image (
+    candidate_id int PRIMARY KEY NOT NULL REFERENCES candidate ON DELETE CASCADE,
+    content_type text NOT NULL,
+    data bytea NOT NULL
+);
+
+ALTER TYPE admin_action ADD VALUE 'update_candidate_image';
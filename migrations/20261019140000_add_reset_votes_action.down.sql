@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+-- Postgres cannot drop a single enum value, so 'reset_votes' is left in admin_action
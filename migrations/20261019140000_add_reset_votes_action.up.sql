@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TYPE admin_action ADD VALUE 'reset_votes';
@@ -0,0 +1,35 @@
+-- Add down migration script here
+
+ALTER TABLE voting_round_result DROP CONSTRAINT fk_ensure_dropped_candidate_has_data;
+ALTER TABLE passing_candidate_result DROP CONSTRAINT passing_candidate_result_voting_id_version_round_candidate_fkey;
+ALTER TABLE candidate_result_data DROP CONSTRAINT candidate_result_data_voting_id_version_round_fkey;
+ALTER TABLE voting_round_result DROP CONSTRAINT voting_round_result_voting_id_version_fkey;
+
+-- Only the latest result version of each voting is kept
+DELETE FROM passing_candidate_result AS p
+WHERE p.version < (SELECT MAX(version) FROM voting_result WHERE voting_id = p.voting_id);
+DELETE FROM candidate_result_data AS c
+WHERE c.version < (SELECT MAX(version) FROM voting_result WHERE voting_id = c.voting_id);
+DELETE FROM voting_round_result AS v
+WHERE v.version < (SELECT MAX(version) FROM voting_result WHERE voting_id = v.voting_id);
+
+ALTER TABLE passing_candidate_result DROP CONSTRAINT passing_candidate_result_pkey;
+ALTER TABLE candidate_result_data DROP CONSTRAINT candidate_result_data_pkey;
+ALTER TABLE voting_round_result DROP CONSTRAINT voting_round_result_pkey;
+
+ALTER TABLE voting_round_result DROP COLUMN version;
+ALTER TABLE candidate_result_data DROP COLUMN version;
+ALTER TABLE passing_candidate_result DROP COLUMN version;
+
+ALTER TABLE voting_round_result ADD PRIMARY KEY (voting_id, round);
+ALTER TABLE candidate_result_data ADD PRIMARY KEY (candidate_id, round, voting_id);
+ALTER TABLE passing_candidate_result ADD PRIMARY KEY (voting_id, candidate_id, round);
+
+ALTER TABLE candidate_result_data ADD FOREIGN KEY (voting_id, round) REFERENCES voting_round_result(voting_id, round);
+ALTER TABLE passing_candidate_result ADD FOREIGN KEY (voting_id, candidate_id, round) REFERENCES candidate_result_data(voting_id, candidate_id, round);
+ALTER TABLE voting_round_result
+ADD CONSTRAINT fk_ensure_dropped_candidate_has_data FOREIGN KEY (voting_id, dropped_candidate_id, round) REFERENCES candidate_result_data(voting_id, candidate_id, round);
+
+DROP TABLE voting_result;
+
+-- Postgres cannot drop a single enum value, so 'recount_voting' is left in admin_action
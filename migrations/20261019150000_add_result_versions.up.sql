@@ -0,0 +1,46 @@
+-- Add up migration script here
+
+-- Every count of a closed voting is stored as a new result version. The latest version is the
+-- current result, earlier ones are kept for the minutes.
+CREATE TABLE voting_result (
+    voting_id int NOT NULL REFERENCES voting(id),
+    version int NOT NULL,
+    created_at timestamptz NOT NULL,
+    number_of_winners int NOT NULL,
+    excluded_candidate_ids int[] NOT NULL DEFAULT '{}',
+    admin_username text,
+    PRIMARY KEY (voting_id, version)
+);
+
+INSERT INTO voting_result (voting_id, version, created_at, number_of_winners)
+SELECT v.id, 1, v.created_at, v.number_of_winners
+FROM voting AS v
+WHERE EXISTS (SELECT 1 FROM voting_round_result AS r WHERE r.voting_id = v.id);
+
+ALTER TABLE voting_round_result DROP CONSTRAINT fk_ensure_dropped_candidate_has_data;
+ALTER TABLE passing_candidate_result DROP CONSTRAINT passing_candidate_result_voting_id_candidate_id_round_fkey;
+ALTER TABLE candidate_result_data DROP CONSTRAINT candidate_result_data_voting_id_round_fkey;
+
+ALTER TABLE voting_round_result ADD COLUMN version int NOT NULL DEFAULT 1;
+ALTER TABLE candidate_result_data ADD COLUMN version int NOT NULL DEFAULT 1;
+ALTER TABLE passing_candidate_result ADD COLUMN version int NOT NULL DEFAULT 1;
+ALTER TABLE voting_round_result ALTER COLUMN version DROP DEFAULT;
+ALTER TABLE candidate_result_data ALTER COLUMN version DROP DEFAULT;
+ALTER TABLE passing_candidate_result ALTER COLUMN version DROP DEFAULT;
+
+ALTER TABLE voting_round_result DROP CONSTRAINT voting_round_result_pkey;
+ALTER TABLE voting_round_result ADD PRIMARY KEY (voting_id, version, round);
+ALTER TABLE voting_round_result ADD FOREIGN KEY (voting_id, version) REFERENCES voting_result(voting_id, version);
+
+ALTER TABLE candidate_result_data DROP CONSTRAINT candidate_result_data_pkey;
+ALTER TABLE candidate_result_data ADD PRIMARY KEY (voting_id, version, round, candidate_id);
+ALTER TABLE candidate_result_data ADD FOREIGN KEY (voting_id, version, round) REFERENCES voting_round_result(voting_id, version, round);
+
+ALTER TABLE passing_candidate_result DROP CONSTRAINT passing_candidate_result_pkey;
+ALTER TABLE passing_candidate_result ADD PRIMARY KEY (voting_id, version, round, candidate_id);
+ALTER TABLE passing_candidate_result ADD FOREIGN KEY (voting_id, version, round, candidate_id) REFERENCES candidate_result_data(voting_id, version, round, candidate_id);
+
+ALTER TABLE voting_round_result
+ADD CONSTRAINT fk_ensure_dropped_candidate_has_data FOREIGN KEY (voting_id, version, round, dropped_candidate_id) REFERENCES candidate_result_data(voting_id, version, round, candidate_id);
+
+ALTER TYPE admin_action ADD VALUE 'recount_voting';
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE candidate DROP COLUMN withdrawn_at;
+
+-- Postgres cannot drop a single enum value, so 'withdraw_candidate' is left in admin_action
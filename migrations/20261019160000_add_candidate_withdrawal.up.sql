@@ -0,0 +1,6 @@
+-- Add up migration script here
+
+-- A withdrawn candidate stays on cast ballots but is left out of the count
+ALTER TABLE candidate ADD COLUMN withdrawn_at timestamptz;
+
+ALTER TYPE admin_action ADD VALUE 'withdraw_candidate';
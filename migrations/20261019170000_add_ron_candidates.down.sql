@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX candidate_one_ron_per_voting;
+ALTER TABLE candidate DROP COLUMN is_ron;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+-- The re-open nominations (RON) pseudo-candidate. A seat won by RON stays unfilled.
+ALTER TABLE candidate ADD COLUMN is_ron boolean NOT NULL DEFAULT false;
+CREATE UNIQUE INDEX candidate_one_ron_per_voting ON candidate (voting_id) WHERE is_ron;
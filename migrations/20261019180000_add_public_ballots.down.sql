@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE ballot_voter;
+ALTER TABLE voting DROP COLUMN is_public_ballot;
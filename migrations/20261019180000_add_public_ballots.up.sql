@@ -0,0 +1,14 @@
+-- Add up migration script here
+
+-- Public (roll-call) votings store the alias of the voter with each ballot. Ballots of secret
+-- votings never get a row here. A ballot without candidates has no rows in vote, so the alias
+-- is kept in its own table instead of a column of vote.
+ALTER TABLE voting ADD COLUMN is_public_ballot boolean NOT NULL DEFAULT false;
+
+CREATE TABLE ballot_voter (
+    voting_id int REFERENCES voting ON DELETE CASCADE NOT NULL,
+    ballot_id uuid NOT NULL,
+    voter_alias text NOT NULL,
+    PRIMARY KEY (voting_id, ballot_id),
+    UNIQUE (voting_id, voter_alias)
+);
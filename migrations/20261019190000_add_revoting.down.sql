@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE ballot_pseudonym;
+ALTER TABLE voting DROP COLUMN allow_revote;
@@ -0,0 +1,14 @@
+-- Add up migration script here
+
+-- Votings that let voters replace their ballot until the voting closes.
+ALTER TABLE voting ADD COLUMN allow_revote boolean NOT NULL DEFAULT false;
+
+-- Links the latest ballot of a voter to a keyed pseudonym of the voter token. The pseudonym
+-- is computed with the server key, so the table alone does not tell which token cast which
+-- ballot. The rows are deleted when the voting closes.
+CREATE TABLE ballot_pseudonym (
+    voting_id int REFERENCES voting ON DELETE CASCADE NOT NULL,
+    pseudonym text NOT NULL,
+    ballot_id uuid NOT NULL,
+    PRIMARY KEY (voting_id, pseudonym)
+);
@@ -0,0 +1,13 @@
+-- Add up migration script here
+
+-- Weight moved from an elected or dropped candidate to the next preference of its ballots at
+-- the end of a round. Exhausted ballots have no receiving candidate.
+CREATE TABLE vote_transfer (
+    voting_id int NOT NULL,
+    version int NOT NULL,
+    round int NOT NULL,
+    from_candidate_id int NOT NULL,
+    to_candidate_id int,
+    weight double precision NOT NULL,
+    FOREIGN KEY (voting_id, version, round) REFERENCES voting_round_result(voting_id, version, round)
+);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE voting_round_result DROP COLUMN lost_weight;
+ALTER TABLE voting_round_result DROP COLUMN exhausted_weight;
@@ -0,0 +1,16 @@
+-- Add up migration script here
+
+-- Weight that left the count at the end of a round without reaching another candidate:
+-- ballots without a further preference and weight lost e.g. to rounding of transfer values.
+ALTER TABLE voting_round_result ADD COLUMN exhausted_weight double precision NOT NULL DEFAULT 0;
+ALTER TABLE voting_round_result ADD COLUMN lost_weight double precision NOT NULL DEFAULT 0;
+
+UPDATE voting_round_result AS r
+SET exhausted_weight = t.weight
+FROM (
+    SELECT voting_id, version, round, SUM(weight) AS weight
+    FROM vote_transfer
+    WHERE to_candidate_id IS NULL
+    GROUP BY voting_id, version, round
+) AS t
+WHERE r.voting_id = t.voting_id AND r.version = t.version AND r.round = t.round;
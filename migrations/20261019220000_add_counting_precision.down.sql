@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE voting_result DROP COLUMN decimal_places;
+ALTER TABLE voting DROP COLUMN decimal_places;
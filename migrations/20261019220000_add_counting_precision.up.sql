@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+-- Votings can be counted in fixed-point decimals where every transfer value is truncated to
+-- the given number of decimal places. NULL counts in floating point. Each result version
+-- stores the precision it was counted with.
+ALTER TABLE voting ADD COLUMN decimal_places int CHECK (decimal_places BETWEEN 0 AND 9);
+ALTER TABLE voting_result ADD COLUMN decimal_places int;
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE voting DROP COLUMN revealed_rounds;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+-- Number of counting rounds of the current result shown on the projector view. Officials step
+-- through the rounds one at a time after the voting has closed.
+ALTER TABLE voting ADD COLUMN revealed_rounds int NOT NULL DEFAULT 0;
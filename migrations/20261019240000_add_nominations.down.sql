@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE nomination;
+DROP TYPE nomination_state;
+
+-- Postgres cannot drop a single enum value, so 'nominating' is left in voting_state and
+-- 'review_nomination' in admin_action
+UPDATE voting SET state = 'draft' WHERE state = 'nominating';
@@ -0,0 +1,21 @@
+-- Add up migration script here
+
+-- Between draft and open, logged-in voters can nominate candidates. Approved nominees are added
+-- to the candidates when the voting opens.
+ALTER TYPE voting_state ADD VALUE 'nominating' BEFORE 'open';
+
+CREATE TYPE nomination_state AS ENUM ('pending', 'approved', 'rejected');
+
+CREATE TABLE nomination (
+    id SERIAL PRIMARY KEY NOT NULL,
+    voting_id int NOT NULL REFERENCES voting ON DELETE CASCADE,
+    name text NOT NULL,
+    bio text,
+    nominated_by text NOT NULL,
+    state nomination_state NOT NULL DEFAULT 'pending',
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX nomination_voting_id_index ON nomination (voting_id);
+
+ALTER TYPE admin_action ADD VALUE 'review_nomination';
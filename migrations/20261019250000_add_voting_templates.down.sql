@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE voting_template;
+
+-- Postgres cannot drop a single enum value, so the template and agenda actions are left in
+-- admin_action
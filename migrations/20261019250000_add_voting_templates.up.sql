@@ -0,0 +1,19 @@
+-- Add up migration script here
+
+-- Reusable settings for votings that recur at every meeting, such as the board election
+CREATE TABLE voting_template (
+    id SERIAL PRIMARY KEY NOT NULL,
+    name text NOT NULL UNIQUE,
+    description text NOT NULL DEFAULT '',
+    hide_vote_counts boolean NOT NULL DEFAULT false,
+    is_public_ballot boolean NOT NULL DEFAULT false,
+    allow_revote boolean NOT NULL DEFAULT false,
+    number_of_winners int NOT NULL CHECK (number_of_winners > 0),
+    decimal_places int CHECK (decimal_places BETWEEN 0 AND 9),
+    include_ron boolean NOT NULL DEFAULT false,
+    created_at timestamptz NOT NULL
+);
+
+ALTER TYPE admin_action ADD VALUE 'create_voting_template';
+ALTER TYPE admin_action ADD VALUE 'delete_voting_template';
+ALTER TYPE admin_action ADD VALUE 'import_agenda';
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE voting DROP COLUMN agenda_group;
+ALTER TABLE voting DROP COLUMN agenda_position;
+
+-- Postgres cannot drop a single enum value, so 'reorder_votings' is left in admin_action
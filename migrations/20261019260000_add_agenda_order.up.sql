@@ -0,0 +1,13 @@
+-- Add up migration script here
+
+-- Votings are listed in the order of the meeting agenda instead of by state only. The group is
+-- an optional section of the agenda, such as "Elections".
+ALTER TABLE voting ADD COLUMN agenda_position int NOT NULL DEFAULT 0;
+ALTER TABLE voting ADD COLUMN agenda_group text;
+
+UPDATE voting
+SET agenda_position = ordered.position
+FROM (SELECT id, ROW_NUMBER() OVER (ORDER BY created_at, id) AS position FROM voting) AS ordered
+WHERE voting.id = ordered.id;
+
+ALTER TYPE admin_action ADD VALUE 'reorder_votings';
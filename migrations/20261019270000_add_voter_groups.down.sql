@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP FUNCTION token_is_eligible;
+DROP TABLE voting_eligible_group;
+DROP TABLE token_voter_group;
+DROP TABLE voter_group;
+
+-- Postgres cannot drop a single enum value, so the voter group actions are left in admin_action
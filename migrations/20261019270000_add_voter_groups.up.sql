@@ -0,0 +1,42 @@
+-- Add up migration script here
+
+-- Voter groups restrict votings to some of the tokens, for example to full members. A voting
+-- without eligible groups is open to every activated token.
+CREATE TABLE voter_group (
+    id SERIAL PRIMARY KEY NOT NULL,
+    name text NOT NULL UNIQUE,
+    created_at timestamptz NOT NULL
+);
+
+CREATE TABLE token_voter_group (
+    token_id int NOT NULL REFERENCES token ON DELETE CASCADE,
+    voter_group_id int NOT NULL REFERENCES voter_group ON DELETE CASCADE,
+    PRIMARY KEY (token_id, voter_group_id)
+);
+
+-- Groups that are still used by a voting cannot be deleted, so that a restricted voting never
+-- becomes open to everyone by accident
+CREATE TABLE voting_eligible_group (
+    voting_id int NOT NULL REFERENCES voting ON DELETE CASCADE,
+    voter_group_id int NOT NULL REFERENCES voter_group,
+    PRIMARY KEY (voting_id, voter_group_id)
+);
+
+CREATE INDEX voting_eligible_group_voter_group_id_index ON voting_eligible_group (voter_group_id);
+
+-- Whether the token may vote in the voting. The state of the token is checked separately.
+CREATE FUNCTION token_is_eligible(p_voting_id int, p_token_id int) RETURNS boolean
+LANGUAGE sql STABLE AS $$
+    SELECT
+        NOT EXISTS (SELECT 1 FROM voting_eligible_group WHERE voting_id = p_voting_id)
+        OR EXISTS (
+            SELECT 1
+            FROM voting_eligible_group AS e
+                INNER JOIN token_voter_group AS g ON g.voter_group_id = e.voter_group_id
+            WHERE e.voting_id = p_voting_id AND g.token_id = p_token_id
+        )
+$$;
+
+ALTER TYPE admin_action ADD VALUE 'create_voter_group';
+ALTER TYPE admin_action ADD VALUE 'delete_voter_group';
+ALTER TYPE admin_action ADD VALUE 'update_voter_group_tokens';
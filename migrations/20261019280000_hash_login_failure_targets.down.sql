@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+-- The hashed voter targets no longer match any token
+DELETE FROM login_failure WHERE kind = 'voter';
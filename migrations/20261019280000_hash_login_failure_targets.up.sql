@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+-- Attempted voter tokens are now stored as a keyed hash. The earlier failures held the tokens
+-- in plain text and cannot be hashed here without the server key, so they are removed.
+DELETE FROM login_failure WHERE kind = 'voter';
@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+-- Postgres cannot drop a single enum value, so 'reveal_round' and 'hide_round' are left in admin_action
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TYPE admin_action ADD VALUE 'reveal_round';
+ALTER TYPE admin_action ADD VALUE 'hide_round';
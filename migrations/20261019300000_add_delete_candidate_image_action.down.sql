@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+-- Postgres cannot drop a single enum value, so 'delete_candidate_image' is left in admin_action
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TYPE admin_action ADD VALUE 'delete_candidate_image';
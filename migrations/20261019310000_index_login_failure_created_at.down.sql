@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX login_failure_created_at_idx;
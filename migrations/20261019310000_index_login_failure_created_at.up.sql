@@ -0,0 +1,2 @@
+-- Add up migration script here
+CREATE INDEX login_failure_created_at_idx ON login_failure(created_at);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+-- Postgres cannot drop a single enum value, so 'create_admin', 'update_admin', 'delete_admin' and
+-- 'revoke_admin_sessions' are left in admin_action
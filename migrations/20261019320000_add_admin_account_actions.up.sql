@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TYPE admin_action ADD VALUE 'create_admin';
+ALTER TYPE admin_action ADD VALUE 'update_admin';
+ALTER TYPE admin_action ADD VALUE 'delete_admin';
+ALTER TYPE admin_action ADD VALUE 'revoke_admin_sessions';
@@ -0,0 +1,6 @@
+-- Add down migration script here
+UPDATE candidate
+SET
+    name = 'RON (avaa ehdollepano uudelleen)',
+    bio = 'Jos RON valitaan, paikka jää täyttämättä ja ehdollepano avataan uudelleen.'
+WHERE is_ron;
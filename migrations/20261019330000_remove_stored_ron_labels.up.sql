@@ -0,0 +1,4 @@
+-- Add up migration script here
+
+-- The label and introduction of RON are rendered in the language of the viewer
+UPDATE candidate SET name = '', bio = NULL WHERE is_ron;
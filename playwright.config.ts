@@ -27,6 +27,8 @@ export default defineConfig({
 		baseURL: `http://127.0.0.1:${port}`,
 		/* Collect trace when retrying the failed test. See https://playwright.dev/docs/trace-viewer */
 		trace: "on-first-retry",
+		/* The tests look for the Finnish texts of the UI */
+		locale: "fi-FI",
 	},
 
 	/* Configure projects for major browsers */
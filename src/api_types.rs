@@ -5,6 +5,8 @@ use axum::{
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
 
+use crate::i18n::Locale;
+
 pub type ApiResult<T> = core::result::Result<T, ApiError>;
 
 #[derive(Serialize, Debug)]
@@ -13,7 +15,9 @@ pub enum AuthFailedError {
     InvalidToken,
     TokenUnactivated,
     TokenVoided,
-    WrongAdminToken,
+    WrongAdminCredentials,
+    InsufficientPermissions,
+    TooManyAttempts,
 }
 
 #[derive(Serialize, Debug)]
@@ -22,6 +26,12 @@ pub enum InvalidAliasError {
     BadAlias,
 }
 
+#[derive(Serialize, Debug)]
+pub enum InvalidAdminAccountError {
+    UsernameAlreadyInUse,
+    LastSuperadmin,
+}
+
 #[serde_as]
 #[derive(Serialize, Debug)]
 pub enum ApiError {
@@ -30,6 +40,7 @@ pub enum ApiError {
     VotingNotFound,
     VotingAlreadyClosed,
     VotingNotOpen,
+    VotingNotClosed,
     NotAllActiveTokensHaveVoted,
     InvalidInput,
     AlreadyVoted,
@@ -39,38 +50,107 @@ pub enum ApiError {
     TemplatingError(#[serde_as(as = "DisplayFromStr")] askama::Error),
     VotingAlgorithmError(&'static str),
     InvalidAlias(InvalidAliasError),
+    InvalidAdminAccount(InvalidAdminAccountError),
+    InvalidCsrfToken,
+    VoteResetNotConfirmed,
+    NominationsNotOpen,
+    NominationNotFound,
+    /// The imported agenda could not be read, with the reason
+    InvalidAgenda(String),
+    VotingTemplateNotFound,
+    /// The token is not in any of the eligible groups of the voting
+    NotEligible,
+    VoterGroupNotFound,
+    /// The voter group is still eligible in a voting
+    VoterGroupInUse,
+    CandidateImageNotFound,
+    /// Fewer candidates than seats would be left in the voting
+    TooFewCandidates,
+    /// The migrations have not been run yet
+    DatabaseNotReady,
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        println!("{:?}", self);
-        match self {
+        let (status, message) = match self {
             ApiError::InvalidAlias(InvalidAliasError::AliasAlreadyInUse) => {
-                (StatusCode::BAD_REQUEST, "Alias already in use").into_response()
+                (StatusCode::BAD_REQUEST, "error.alias_already_in_use")
             }
             ApiError::InvalidAlias(InvalidAliasError::BadAlias) => {
-                (StatusCode::BAD_REQUEST, "Bad alias").into_response()
+                (StatusCode::BAD_REQUEST, "error.bad_alias")
             }
             ApiError::AuthFailed(AuthFailedError::MissingToken) => {
-                (StatusCode::UNAUTHORIZED, "Token missing").into_response()
+                (StatusCode::UNAUTHORIZED, "error.token_missing")
             }
             ApiError::AuthFailed(AuthFailedError::InvalidToken) => {
-                (StatusCode::UNAUTHORIZED, "Token invalid").into_response()
+                (StatusCode::UNAUTHORIZED, "error.token_invalid")
             }
             ApiError::AuthFailed(AuthFailedError::TokenUnactivated) => {
-                (StatusCode::UNAUTHORIZED, "Token unactivated").into_response()
+                (StatusCode::UNAUTHORIZED, "error.token_unactivated")
             }
             ApiError::AuthFailed(AuthFailedError::TokenVoided) => {
-                (StatusCode::UNAUTHORIZED, "Token voided").into_response()
+                (StatusCode::UNAUTHORIZED, "error.token_voided")
+            }
+            ApiError::AuthFailed(AuthFailedError::WrongAdminCredentials) => {
+                (StatusCode::UNAUTHORIZED, "error.wrong_admin_credentials")
+            }
+            ApiError::AuthFailed(AuthFailedError::InsufficientPermissions) => {
+                (StatusCode::FORBIDDEN, "error.insufficient_permissions")
+            }
+            ApiError::AuthFailed(AuthFailedError::TooManyAttempts) => {
+                (StatusCode::TOO_MANY_REQUESTS, "error.too_many_attempts")
+            }
+            ApiError::InvalidAdminAccount(InvalidAdminAccountError::UsernameAlreadyInUse) => {
+                (StatusCode::BAD_REQUEST, "error.username_already_in_use")
             }
-            ApiError::AuthFailed(AuthFailedError::WrongAdminToken) => {
-                (StatusCode::UNAUTHORIZED, "Wrong admin token").into_response()
+            ApiError::InvalidAdminAccount(InvalidAdminAccountError::LastSuperadmin) => {
+                (StatusCode::BAD_REQUEST, "error.last_superadmin")
             }
-            ApiError::VotingNotFound => {
-                (StatusCode::BAD_REQUEST, "Voting is not open").into_response()
+            ApiError::InvalidCsrfToken => (StatusCode::FORBIDDEN, "error.invalid_csrf_token"),
+            ApiError::InvalidInput => (StatusCode::BAD_REQUEST, "error.invalid_input"),
+            ApiError::VotingNotOpen => (StatusCode::BAD_REQUEST, "error.voting_not_open"),
+            ApiError::VotingNotClosed => (StatusCode::BAD_REQUEST, "error.voting_not_closed"),
+            ApiError::VotingAlreadyClosed => {
+                (StatusCode::BAD_REQUEST, "error.voting_already_closed")
             }
-            _ => (StatusCode::INTERNAL_SERVER_ERROR, "Unhandled client error").into_response(),
+            ApiError::VoteResetNotConfirmed => {
+                (StatusCode::CONFLICT, "error.vote_reset_not_confirmed")
+            }
+            ApiError::VotingNotFound => (StatusCode::BAD_REQUEST, "error.voting_not_found"),
+            ApiError::NominationsNotOpen => (StatusCode::BAD_REQUEST, "error.nominations_not_open"),
+            ApiError::NominationNotFound => (StatusCode::BAD_REQUEST, "error.nomination_not_found"),
+            ApiError::VotingTemplateNotFound => {
+                (StatusCode::BAD_REQUEST, "error.voting_template_not_found")
+            }
+            ApiError::NotEligible => (StatusCode::FORBIDDEN, "error.not_eligible"),
+            ApiError::VoterGroupNotFound => {
+                (StatusCode::BAD_REQUEST, "error.voter_group_not_found")
+            }
+            ApiError::VoterGroupInUse => (StatusCode::CONFLICT, "error.voter_group_in_use"),
+            ApiError::CandidateImageNotFound => {
+                (StatusCode::NOT_FOUND, "error.candidate_image_not_found")
+            }
+            ApiError::TooFewCandidates => (StatusCode::BAD_REQUEST, "error.too_few_candidates"),
+            ApiError::InvalidAgenda(_) => (StatusCode::BAD_REQUEST, "error.invalid_agenda"),
+            ApiError::DatabaseNotReady => {
+                (StatusCode::SERVICE_UNAVAILABLE, "error.database_not_ready")
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "error.unhandled"),
+        };
+
+        // The Debug output of a database error may contain column values, such as tokens
+        match &self {
+            ApiError::DatabaseError(err) => tracing::error!(error = %err, "database error"),
+            _ if status.is_server_error() => tracing::error!(error = ?self, "request failed"),
+            _ => tracing::debug!(error = ?self, "request rejected"),
         }
+
+        let message = match &self {
+            ApiError::InvalidAgenda(reason) => Locale::current().t1(message, reason),
+            _ => Locale::current().t(message).to_string(),
+        };
+
+        (status, message).into_response()
     }
 }
 
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use envconfig::Envconfig;
 use validator::Validate;
 
@@ -14,7 +16,73 @@ pub struct Config {
     #[validate(length(min = 1, max = 1024))]
     pub hmac_key: String,
 
+    /// Username of the superadmin that is created when no admin accounts exist
+    #[envconfig(from = "ADMIN_USERNAME", default = "admin")]
+    #[validate(length(min = 1, max = 64))]
+    pub admin_username: String,
+
+    /// Password of the superadmin that is created when no admin accounts exist
     #[envconfig(from = "ADMIN_PASSWORD")]
     #[validate(length(min = 1, max = 1024))]
     pub admin_password: String,
+
+    /// Use the last `X-Forwarded-For` entry as the client address. Only enable this behind a
+    /// reverse proxy that sets the header.
+    #[envconfig(from = "BEHIND_PROXY", default = "false")]
+    pub behind_proxy: bool,
+
+    /// Failed logins from one address before backoff starts
+    #[envconfig(from = "LOGIN_MAX_FAILURES_PER_IP", default = "20")]
+    pub login_max_failures_per_ip: i64,
+
+    /// Failed logins against one token or admin username before backoff starts
+    #[envconfig(from = "LOGIN_MAX_FAILURES_PER_TARGET", default = "5")]
+    pub login_max_failures_per_target: i64,
+
+    /// Lockout after the first failure over the limit, doubled for every further failure
+    #[envconfig(from = "LOGIN_BACKOFF_BASE_SECONDS", default = "30")]
+    pub login_backoff_base_seconds: i64,
+
+    /// Upper bound for a single lockout
+    #[envconfig(from = "LOGIN_LOCKOUT_MAX_SECONDS", default = "3600")]
+    pub login_lockout_max_seconds: i64,
+
+    /// How long a failed login counts towards the limits
+    #[envconfig(from = "LOGIN_FAILURE_WINDOW_MINUTES", default = "60")]
+    pub login_failure_window_minutes: i64,
+
+    /// Log filter in the `RUST_LOG` syntax, e.g. `info` or `info,vaalikoppi=debug`
+    #[envconfig(from = "LOG_LEVEL", default = "info")]
+    pub log_level: String,
+
+    /// Serves `/metrics` without authentication on this port too. On the main port it is only
+    /// available to admins.
+    #[envconfig(from = "METRICS_PORT")]
+    pub metrics_port: Option<u16>,
+
+    /// How long open requests may take to finish on shutdown
+    #[envconfig(from = "SHUTDOWN_TIMEOUT_SECONDS", default = "30")]
+    pub shutdown_timeout_seconds: u64,
+
+    /// `text` for humans or `json` for log collectors
+    #[envconfig(from = "LOG_FORMAT", default = "text")]
+    pub log_format: LogFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("unknown log format {s}")),
+        }
+    }
 }
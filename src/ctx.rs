@@ -1,23 +1,39 @@
-use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use std::net::SocketAddr;
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
 
 use crate::{
     api_types::{ApiError, ApiResult},
-    models::LoginState,
+    http::AppState,
+    i18n::Locale,
+    models::{AdminAccount, LoginState},
 };
 
 #[derive(Clone, Debug)]
 pub struct Ctx {
     login_state: LoginState,
+    locale: Locale,
 }
 
 impl Ctx {
-    pub fn new(login_state: LoginState) -> Self {
-        Self { login_state }
+    pub fn new(login_state: LoginState, locale: Locale) -> Self {
+        Self {
+            login_state,
+            locale,
+        }
     }
 
     pub fn login_state(&self) -> LoginState {
         self.login_state.clone()
     }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
 }
 
 #[async_trait]
@@ -27,7 +43,48 @@ impl<S: Send + Sync> FromRequestParts<S> for Ctx {
         parts
             .extensions
             .get::<Ctx>()
-            .map(|voting| voting.clone())
+            .cloned()
+            .ok_or(ApiError::InternalServerError)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AdminAccount {
+    type Rejection = ApiError;
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> ApiResult<Self> {
+        match parts.extensions.get::<Ctx>().map(Ctx::login_state) {
+            Some(LoginState::Admin(admin)) => Ok(admin),
+            _ => Err(ApiError::TokenNotFound),
+        }
+    }
+}
+
+/// Address of the client, used for login throttling.
+pub struct ClientIp(pub String);
+
+#[async_trait]
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = ApiError;
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> ApiResult<Self> {
+        // The last entry is the one appended by our own proxy, the rest are client controlled
+        let forwarded_for = state
+            .config
+            .behind_proxy
+            .then(|| parts.headers.get("x-forwarded-for"))
+            .flatten()
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.rsplit(',').next())
+            .map(|ip| ip.trim().to_string())
+            .filter(|ip| !ip.is_empty());
+
+        forwarded_for
+            .or_else(|| {
+                parts
+                    .extensions
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(address)| address.ip().to_string())
+            })
+            .map(ClientIp)
             .ok_or(ApiError::InternalServerError)
     }
 }
@@ -0,0 +1,19 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::models::VotingId;
+
+/// Keyed pseudonym that links a voter token to its ballot in one voting. Without the server
+/// key the pseudonym cannot be matched to a token, so the stored link does not reveal who cast
+/// which ballot to someone with only a copy of the database.
+pub fn ballot_pseudonym(hmac_key: &str, token: &str, voting_id: VotingId) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(hmac_key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("ballot-pseudonym:{voting_id}:{token}").as_bytes());
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
@@ -1,9 +1,16 @@
-use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    iter::Sum,
+    ops::{Add, AddAssign, Sub},
+};
 
 use crate::{
     api_types::{ApiError, ApiResult},
+    metrics::metrics,
     models::{
-        CandidateId, CandidateResultData, PassingCandidateResult, VotingResult, VotingRoundResult,
+        CandidateId, CandidateResultData, NonTransferableWeight, PassingCandidateResult,
+        VoteTransfer, VotingResult, VotingRoundResult, MAX_DECIMAL_PLACES,
     },
 };
 use float_cmp::approx_eq;
@@ -11,25 +18,208 @@ use rand::seq::IteratorRandom;
 
 type Vote = Vec<CandidateId>;
 
-struct WeightedVote<'a> {
-    weight: f64,
+/// Arithmetic of the vote weights. A count is done either in floating point or in fixed-point
+/// decimals, where every transfer value is truncated to the chosen number of decimal places.
+trait Arithmetic {
+    type Weight: Copy
+        + Default
+        + PartialEq
+        + PartialOrd
+        + Add<Output = Self::Weight>
+        + Sub<Output = Self::Weight>
+        + AddAssign
+        + Sum;
+
+    fn one(&self) -> Self::Weight;
+    fn quota(&self, valid_vote_count: usize, number_of_winners: usize) -> Self::Weight;
+    /// Share of `surplus` carried by a ballot of `weight` out of a pile of `total`.
+    fn transfer_value(
+        &self,
+        weight: Self::Weight,
+        surplus: Self::Weight,
+        total: Self::Weight,
+    ) -> Self::Weight;
+    fn cmp(&self, a: &Self::Weight, b: &Self::Weight) -> Ordering;
+    fn is_tie(&self, a: Self::Weight, b: Self::Weight) -> bool;
+    fn to_f64(&self, weight: Self::Weight) -> f64;
+    fn decimal_places(&self) -> Option<i32>;
+}
+
+struct FloatArithmetic;
+
+impl Arithmetic for FloatArithmetic {
+    type Weight = f64;
+
+    fn one(&self) -> f64 {
+        1.0
+    }
+
+    fn quota(&self, valid_vote_count: usize, number_of_winners: usize) -> f64 {
+        valid_vote_count as f64 / (number_of_winners as f64 + 1.0) + 1.0
+    }
+
+    fn transfer_value(&self, weight: f64, surplus: f64, total: f64) -> f64 {
+        (weight / total) * surplus
+    }
+
+    fn cmp(&self, a: &f64, b: &f64) -> Ordering {
+        a.total_cmp(b)
+    }
+
+    fn is_tie(&self, a: f64, b: f64) -> bool {
+        approx_eq!(f64, a, b, epsilon = 0.000001)
+    }
+
+    fn to_f64(&self, weight: f64) -> f64 {
+        weight
+    }
+
+    fn decimal_places(&self) -> Option<i32> {
+        None
+    }
+}
+
+/// Weights are integers in units of `1 / scale`, so sums and comparisons are exact and
+/// division truncates.
+struct DecimalArithmetic {
+    scale: i128,
+}
+
+impl DecimalArithmetic {
+    fn new(decimal_places: i32) -> ApiResult<Self> {
+        if !(0..=MAX_DECIMAL_PLACES).contains(&decimal_places) {
+            return Err(ApiError::VotingAlgorithmError(
+                "Invalid number of decimal places for a fixed-point count",
+            ));
+        }
+
+        Ok(DecimalArithmetic {
+            scale: 10_i128.pow(decimal_places.unsigned_abs()),
+        })
+    }
+}
+
+impl Arithmetic for DecimalArithmetic {
+    type Weight = i128;
+
+    fn one(&self) -> i128 {
+        self.scale
+    }
+
+    fn quota(&self, valid_vote_count: usize, number_of_winners: usize) -> i128 {
+        valid_vote_count as i128 * self.scale / (number_of_winners as i128 + 1) + self.scale
+    }
+
+    fn transfer_value(&self, weight: i128, surplus: i128, total: i128) -> i128 {
+        weight * surplus / total
+    }
+
+    fn cmp(&self, a: &i128, b: &i128) -> Ordering {
+        a.cmp(b)
+    }
+
+    fn is_tie(&self, a: i128, b: i128) -> bool {
+        a == b
+    }
+
+    fn to_f64(&self, weight: i128) -> f64 {
+        weight as f64 / self.scale as f64
+    }
+
+    fn decimal_places(&self) -> Option<i32> {
+        Some(self.scale.ilog10() as i32)
+    }
+}
+
+struct WeightedVote<'a, W> {
+    weight: W,
     vote: &'a Vote,
 }
 
-type VoteMap<'a> = HashMap<CandidateId, Vec<WeightedVote<'a>>>;
+type VoteMap<'a, W> = HashMap<CandidateId, Vec<WeightedVote<'a, W>>>;
+
+/// Transferred weight keyed by the giving and the receiving candidate. `None` is exhausted.
+type TransferMap<W> = BTreeMap<(CandidateId, Option<CandidateId>), W>;
+
+fn collect_transfers<A: Arithmetic>(
+    arithmetic: &A,
+    transfer_map: TransferMap<A::Weight>,
+) -> Vec<VoteTransfer> {
+    transfer_map
+        .into_iter()
+        .filter(|(_, weight)| *weight > A::Weight::default())
+        .map(
+            |((from_candidate_id, to_candidate_id), weight)| VoteTransfer {
+                from_candidate_id,
+                to_candidate_id,
+                weight: arithmetic.to_f64(weight),
+            },
+        )
+        .collect()
+}
+
+/// Splits the weight that left the count without reaching another candidate into exhausted
+/// ballots and weight lost on the way. `departed` is the weight the elected or dropped
+/// candidates gave away.
+fn non_transferable_weight<A: Arithmetic>(
+    arithmetic: &A,
+    transfer_map: &TransferMap<A::Weight>,
+    departed: A::Weight,
+) -> NonTransferableWeight {
+    let exhausted = transfer_map
+        .iter()
+        .filter(|((_, to), _)| to.is_none())
+        .map(|(_, weight)| *weight)
+        .sum();
+    let transferred = transfer_map.values().copied().sum::<A::Weight>();
+
+    NonTransferableWeight {
+        exhausted: arithmetic.to_f64(exhausted),
+        lost: arithmetic.to_f64(departed - transferred),
+    }
+}
 
-fn get_current_vote_counts_of_candidates<'a>(
-    vote_map: &'a VoteMap,
-) -> impl Iterator<Item = (&'a CandidateId, f64)> {
+fn get_current_vote_counts_of_candidates<'a, W: Copy + Sum>(
+    vote_map: &'a VoteMap<W>,
+) -> impl Iterator<Item = (&'a CandidateId, W)> {
     vote_map
         .iter()
         .map(|(id, votes)| (id, votes.iter().map(|v| v.weight).sum()))
 }
 
+/// Counts the votes with single transferable vote. With `decimal_places` the count is done in
+/// fixed-point decimals and every transfer value is truncated to that many decimals, otherwise
+/// in floating point.
 pub fn calculate_stv_result(
     candidates: Vec<CandidateId>,
     votes: Vec<Vote>,
     number_of_winners: usize,
+    decimal_places: Option<i32>,
+) -> ApiResult<VotingResult> {
+    let _timer = metrics()
+        .stv_count_duration
+        .with_label_values(&[match decimal_places {
+            Some(_) => "decimal",
+            None => "float",
+        }])
+        .start_timer();
+
+    match decimal_places {
+        Some(decimal_places) => count(
+            &DecimalArithmetic::new(decimal_places)?,
+            candidates,
+            votes,
+            number_of_winners,
+        ),
+        None => count(&FloatArithmetic, candidates, votes, number_of_winners),
+    }
+}
+
+fn count<A: Arithmetic>(
+    arithmetic: &A,
+    candidates: Vec<CandidateId>,
+    votes: Vec<Vote>,
+    number_of_winners: usize,
 ) -> ApiResult<VotingResult> {
     // TODO sanitize inputs
     let mut round_results: Vec<VotingRoundResult> = vec![];
@@ -40,9 +230,9 @@ pub fn calculate_stv_result(
     let valid_votes: Vec<&Vec<CandidateId>> =
         votes.iter().filter(|vote| !vote.is_empty()).collect();
     let valid_vote_count = valid_votes.len();
-    let quota = valid_vote_count as f64 / (number_of_winners as f64 + 1.0) + 1.0;
+    let quota = arithmetic.quota(valid_vote_count, number_of_winners);
 
-    let mut vote_map: VoteMap = VoteMap::new();
+    let mut vote_map: VoteMap<A::Weight> = VoteMap::new();
 
     // Insert empty list of votes for each candidate
     candidates.iter().for_each(|c| {
@@ -54,7 +244,7 @@ pub fn calculate_stv_result(
         if let Some(id) = ballot.first() {
             let weighted_votes_of_candidate = vote_map.entry(id.to_owned()).or_default();
             weighted_votes_of_candidate.push(WeightedVote {
-                weight: 1.0,
+                weight: arithmetic.one(),
                 vote: ballot,
             });
         }
@@ -71,13 +261,13 @@ pub fn calculate_stv_result(
 
         let elected_candidates = get_current_vote_counts_of_candidates(&vote_map)
             .filter(|(_, votes)| (*votes >= quota) || accept_all_candidates)
-            .map(|(id, _)| id.clone())
+            .map(|(id, _)| *id)
             .collect::<HashSet<_>>();
 
         let round_result: VotingRoundResult = if !elected_candidates.is_empty() {
-            transfer_surplus_votes(&mut vote_map, &elected_candidates, quota, round)
+            transfer_surplus_votes(arithmetic, &mut vote_map, &elected_candidates, quota, round)
         } else {
-            drop_one_candidate(&mut vote_map, round)
+            drop_one_candidate(arithmetic, &mut vote_map, round)
         }?;
 
         round_results.push(round_result);
@@ -85,7 +275,7 @@ pub fn calculate_stv_result(
         round += 1;
 
         if winner_count == number_of_winners || vote_map.is_empty() {
-            println!("FINISH VOTING");
+            tracing::debug!(round, winner_count, "counting finished");
             voting_is_finished = true;
         }
     }
@@ -93,33 +283,58 @@ pub fn calculate_stv_result(
     let winners = round_results
         .iter()
         .flat_map(|res| res.candidate_results.iter().filter(|c| c.is_selected))
-        .map(|c| c.data.name.clone())
+        .map(|c| c.data.candidate_id)
         .collect::<Vec<_>>();
 
     Ok(VotingResult {
         round_results,
         winners,
+        decimal_places: arithmetic.decimal_places(),
     })
 }
 
-fn drop_one_candidate<'a>(vote_map: &'a mut VoteMap, round: usize) -> ApiResult<VotingRoundResult> {
-    println!("DROP ONE CANDIDATE");
+/// Removes the excluded candidates from the count as if they had never been on the ballot.
+/// Preferences for an excluded candidate are skipped, so those ballots count for the next
+/// preference from the first round on.
+pub fn exclude_candidates(
+    candidates: Vec<CandidateId>,
+    votes: Vec<Vote>,
+    excluded: &[CandidateId],
+) -> (Vec<CandidateId>, Vec<Vote>) {
+    let candidates = candidates
+        .into_iter()
+        .filter(|c| !excluded.contains(c))
+        .collect();
+    let votes = votes
+        .into_iter()
+        .map(|vote| vote.into_iter().filter(|c| !excluded.contains(c)).collect())
+        .collect();
+
+    (candidates, votes)
+}
+
+fn drop_one_candidate<A: Arithmetic>(
+    arithmetic: &A,
+    vote_map: &mut VoteMap<A::Weight>,
+    round: usize,
+) -> ApiResult<VotingRoundResult> {
+    tracing::debug!(round, "dropping one candidate");
     let mut vote_counts = get_current_vote_counts_of_candidates(vote_map)
         .map(|(c, v)| (c.to_owned(), v))
         .collect::<Vec<_>>();
 
-    vote_counts.sort_by(|(_, old), (_, new)| new.total_cmp(old));
+    vote_counts.sort_by(|(_, old), (_, new)| arithmetic.cmp(new, old));
     let &min_number_of_votes = vote_counts
         .iter()
         .map(|(_, votes)| votes)
-        .min_by(|old, new| old.total_cmp(new))
+        .min_by(|old, new| arithmetic.cmp(old, new))
         .ok_or(ApiError::VotingAlgorithmError(
             "Expected to find at least one entry in vote_counts, found none",
         ))?;
 
     let candidates_with_votes_equal_to_minimum_value = vote_counts
         .iter()
-        .filter(|(_, votes)| approx_eq!(f64, min_number_of_votes, *votes, epsilon = 0.000001))
+        .filter(|(_, votes)| arithmetic.is_tie(min_number_of_votes, *votes))
         .collect::<Vec<_>>();
 
     // If there are multiple candidates with equal votes, choose one at random
@@ -139,8 +354,13 @@ fn drop_one_candidate<'a>(vote_map: &'a mut VoteMap, round: usize) -> ApiResult<
             ))?;
 
     // Transfer votes
+    let departed = votes_of_dropped_candidate.iter().map(|v| v.weight).sum();
+    let mut transfer_map = TransferMap::new();
     for vote in votes_of_dropped_candidate {
         let secondary_preference = find_secondary_preference(vote_map, vote.vote);
+        *transfer_map
+            .entry((candidate_to_be_dropped.0, secondary_preference.copied()))
+            .or_default() += vote.weight;
         if let Some(secondary_preference) = secondary_preference {
             vote_map.get_mut(secondary_preference).unwrap().push(vote);
         }
@@ -151,20 +371,20 @@ fn drop_one_candidate<'a>(vote_map: &'a mut VoteMap, round: usize) -> ApiResult<
         .filter(|(c, _)| *c != candidate_to_be_dropped.0)
         .map(|(c, v)| PassingCandidateResult {
             data: CandidateResultData {
-                name: c.clone(),
-                vote_count: v.clone(),
+                candidate_id: *c,
+                vote_count: arithmetic.to_f64(*v),
                 is_draw: candidates_with_votes_equal_to_minimum_value.len() > 1
                     && candidates_with_votes_equal_to_minimum_value
                         .to_owned()
-                        .contains(&&(c.clone(), *v)),
+                        .contains(&&(*c, *v)),
             },
             is_selected: false,
         })
         .collect::<Vec<_>>();
 
     let dropped_candidate = Some(CandidateResultData {
-        name: candidate_to_be_dropped.0.to_owned(),
-        vote_count: candidate_to_be_dropped.1,
+        candidate_id: candidate_to_be_dropped.0.to_owned(),
+        vote_count: arithmetic.to_f64(candidate_to_be_dropped.1),
         is_draw: candidates_with_votes_equal_to_minimum_value.len() > 1
             && candidates_with_votes_equal_to_minimum_value.contains(&candidate_to_be_dropped),
     });
@@ -173,48 +393,68 @@ fn drop_one_candidate<'a>(vote_map: &'a mut VoteMap, round: usize) -> ApiResult<
         round: round.try_into().expect("Could not fit rounds into i32!"),
         candidate_results,
         dropped_candidate,
+        non_transferable: non_transferable_weight(arithmetic, &transfer_map, departed),
+        transfers: collect_transfers(arithmetic, transfer_map),
     })
 }
 
-fn transfer_surplus_votes(
-    vote_map: &mut VoteMap,
+fn transfer_surplus_votes<A: Arithmetic>(
+    arithmetic: &A,
+    vote_map: &mut VoteMap<A::Weight>,
     elected_candidates: &HashSet<CandidateId>,
-    quota: f64,
+    quota: A::Weight,
     round: usize,
 ) -> ApiResult<VotingRoundResult> {
-    println!("TRANSFER SURPLUS VOTES");
+    tracing::debug!(round, "transferring surplus votes");
     let mut vote_counts = get_current_vote_counts_of_candidates(vote_map)
         .map(|(c, v)| (c.to_owned(), v))
         .collect::<Vec<_>>();
-    vote_counts.sort_by(|(_, old), (_, new)| new.total_cmp(old));
+    vote_counts.sort_by(|(_, old), (_, new)| arithmetic.cmp(new, old));
 
+    let zero = A::Weight::default();
+    let mut transfer_map = TransferMap::new();
+    let mut departed = zero;
     vote_counts
         .iter()
         .filter(|(c, _)| elected_candidates.contains(c))
-        .map(|(c, v)| {
-            let surplus = (v - quota).max(0.0); // Limit min value to 0 to prevent negative values from floating point issues
+        .try_for_each(|(c, _)| {
             let votes_to_be_transferred = vote_map.remove(c).ok_or(
                 ApiError::VotingAlgorithmError("Could not find elected candidate in voting_map"),
             )?;
 
+            // The elected candidate keeps a quota of the votes it has at this point, including
+            // the ones received from the candidates elected before it on this round. Limit min
+            // value to 0 to prevent negative values from floating point issues.
+            let pile = votes_to_be_transferred
+                .iter()
+                .map(|v| v.weight)
+                .sum::<A::Weight>();
+            let surplus = if pile > quota { pile - quota } else { zero };
+            if surplus == zero {
+                return Ok(());
+            }
+            departed += surplus;
+
             votes_to_be_transferred.into_iter().for_each(|mut vote| {
-                vote.weight = (vote.weight / v) * surplus;
+                vote.weight = arithmetic.transfer_value(vote.weight, surplus, pile);
 
                 let secondary_preference_votes = find_secondary_preference(vote_map, vote.vote);
+                *transfer_map
+                    .entry((*c, secondary_preference_votes.copied()))
+                    .or_default() += vote.weight;
                 if let Some(secondary_preference) = secondary_preference_votes {
                     vote_map.get_mut(secondary_preference).unwrap().push(vote);
                 }
             });
-            Ok(())
-        })
-        .collect::<ApiResult<_>>()?;
+            Ok::<(), ApiError>(())
+        })?;
 
     let candidate_results = vote_counts
         .iter()
         .map(|(c, v)| PassingCandidateResult {
             data: CandidateResultData {
-                name: (*c).to_owned(),
-                vote_count: *v,
+                candidate_id: (*c).to_owned(),
+                vote_count: arithmetic.to_f64(*v),
                 is_draw: false,
             },
             is_selected: elected_candidates.contains(c),
@@ -225,11 +465,13 @@ fn transfer_surplus_votes(
         round: round.try_into().expect("Could not fit rounds into i32!"),
         candidate_results,
         dropped_candidate: None,
+        non_transferable: non_transferable_weight(arithmetic, &transfer_map, departed),
+        transfers: collect_transfers(arithmetic, transfer_map),
     })
 }
 
-fn find_secondary_preference<'a>(
-    vote_map: &HashMap<String, Vec<WeightedVote<'a>>>,
+fn find_secondary_preference<'a, W>(
+    vote_map: &VoteMap<'a, W>,
     vote: &'a Vote,
 ) -> Option<&'a CandidateId> {
     vote.iter().find(|c| vote_map.contains_key(*c))
@@ -243,7 +485,7 @@ fn find_secondary_preference<'a>(
         .into_iter()
         .map(|(c_id, votes)| PassingCandidateResult {
             data: CandidateResultData {
-                name: c_id.clone(),
+                candidate_id: c_id.clone(),
                 vote_count: *votes,
             },
             is_selected: true,
@@ -254,7 +496,7 @@ fn find_secondary_preference<'a>(
         .iter()
         .map(|(id, votes)| PassingCandidateResult {
             data: CandidateResultData {
-                name: id.clone(),
+                candidate_id: id.clone(),
                 vote_count: *votes,
             },
             is_selected: false,
@@ -266,62 +508,66 @@ fn find_secondary_preference<'a>(
 
 #[cfg(test)]
 mod tests {
+    use float_cmp::approx_eq;
     use pretty_assertions::assert_eq;
 
     use crate::{
-        helpers::calculate_stv_result,
+        helpers::{calculate_stv_result, exclude_candidates},
         models::{
-            CandidateId, CandidateResultData, PassingCandidateResult, VotingResult,
-            VotingRoundResult,
+            CandidateId, CandidateResultData, NonTransferableWeight, PassingCandidateResult,
+            VoteTransfer, VotingResult, VotingRoundResult,
         },
     };
 
     // TODO sanitize inputs
     /* #[tokio::test(flavor = "multi_thread")]
        async fn test_duplicate_candidate_throws() {
-           let candidates = vec!["a".to_string(), "a".to_string()];
+           let candidates = vec![1, 1];
            let votes: Vec<Vec<CandidateId>> = vec![];
-           let result = calculate_stv_result(candidates, votes, 1);
+           let result = calculate_stv_result(candidates, votes, 1, None);
            assert!(result.is_err())
        }
 
        #[tokio::test(flavor = "multi_thread")]
        async fn test_duplicate_vote_throws() {
-           let candidates = vec!["a".to_string(), "b".to_string()];
-           let votes: Vec<Vec<CandidateId>> = vec![vec!["a".to_string(), "a".to_string()]];
-           let result = calculate_stv_result(candidates, votes, 1);
+           let candidates = vec![1, 2];
+           let votes: Vec<Vec<CandidateId>> = vec![vec![1, 1]];
+           let result = calculate_stv_result(candidates, votes, 1, None);
            assert!(result.is_err())
        }
 
        #[tokio::test(flavor = "multi_thread")]
        async fn test_invalid_candidate_throws() {
-           let candidates = vec!["a".to_string(), "b".to_string()];
-           let votes: Vec<Vec<CandidateId>> = vec![vec!["c".to_string()]];
-           let result = calculate_stv_result(candidates, votes, 1);
+           let candidates = vec![1, 2];
+           let votes: Vec<Vec<CandidateId>> = vec![vec![3]];
+           let result = calculate_stv_result(candidates, votes, 1, None);
            assert!(result.is_err())
        }
     */
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_single_candidate_is_selected_with_no_votes() {
-        let candidates = vec!["a".to_string()];
+        let candidates = vec![1];
         let votes: Vec<Vec<CandidateId>> = vec![];
-        let result = calculate_stv_result(candidates, votes, 1);
+        let result = calculate_stv_result(candidates, votes, 1, None);
 
         let expected_result = VotingResult {
             round_results: vec![VotingRoundResult {
                 round: 1,
                 candidate_results: vec![PassingCandidateResult {
                     data: CandidateResultData {
-                        name: "a".to_string(),
+                        candidate_id: 1,
                         vote_count: 0.0,
                         is_draw: false,
                     },
                     is_selected: true,
                 }],
                 dropped_candidate: None,
+                transfers: vec![],
+                non_transferable: NonTransferableWeight::default(),
             }],
-            winners: vec!["a".to_string()],
+            winners: vec![1],
+            decimal_places: None,
         };
 
         match result {
@@ -332,24 +578,27 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_single_candidate_is_selected_with_one_vote() {
-        let candidates = vec!["a".to_string()];
-        let votes: Vec<Vec<CandidateId>> = vec![vec!["a".to_string()]];
-        let result = calculate_stv_result(candidates, votes, 1);
+        let candidates = vec![1];
+        let votes: Vec<Vec<CandidateId>> = vec![vec![1]];
+        let result = calculate_stv_result(candidates, votes, 1, None);
 
         let expected_result = VotingResult {
             round_results: vec![VotingRoundResult {
                 round: 1,
                 candidate_results: vec![PassingCandidateResult {
                     data: CandidateResultData {
-                        name: "a".to_string(),
+                        candidate_id: 1,
                         vote_count: 1.0,
                         is_draw: false,
                     },
                     is_selected: true,
                 }],
                 dropped_candidate: None,
+                transfers: vec![],
+                non_transferable: NonTransferableWeight::default(),
             }],
-            winners: vec!["a".to_string()],
+            winners: vec![1],
+            decimal_places: None,
         };
 
         match result {
@@ -360,9 +609,9 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_two_candidates_one_vote() {
-        let candidates = vec!["a".to_string(), "b".to_string()];
-        let votes: Vec<Vec<CandidateId>> = vec![vec!["a".to_string()]];
-        let result = calculate_stv_result(candidates, votes, 1);
+        let candidates = vec![1, 2];
+        let votes: Vec<Vec<CandidateId>> = vec![vec![1]];
+        let result = calculate_stv_result(candidates, votes, 1, None);
 
         let expected_result = VotingResult {
             round_results: vec![
@@ -370,32 +619,37 @@ mod tests {
                     round: 1,
                     candidate_results: vec![PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "a".to_string(),
+                            candidate_id: 1,
                             vote_count: 1.0,
                             is_draw: false,
                         },
                         is_selected: false,
                     }],
                     dropped_candidate: Some(CandidateResultData {
-                        name: "b".to_string(),
+                        candidate_id: 2,
                         vote_count: 0.0,
                         is_draw: false,
                     }),
+                    transfers: vec![],
+                    non_transferable: NonTransferableWeight::default(),
                 },
                 VotingRoundResult {
                     round: 2,
                     candidate_results: vec![PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "a".to_string(),
+                            candidate_id: 1,
                             vote_count: 1.0,
                             is_draw: false,
                         },
                         is_selected: true,
                     }],
                     dropped_candidate: None,
+                    transfers: vec![],
+                    non_transferable: NonTransferableWeight::default(),
                 },
             ],
-            winners: vec!["a".to_string()],
+            winners: vec![1],
+            decimal_places: None,
         };
 
         match result {
@@ -406,9 +660,9 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_two_candidates_two_spots_one_vote() {
-        let candidates = vec!["a".to_string(), "b".to_string()];
-        let votes: Vec<Vec<CandidateId>> = vec![vec!["a".to_string()]];
-        let result = calculate_stv_result(candidates, votes, 2);
+        let candidates = vec![1, 2];
+        let votes: Vec<Vec<CandidateId>> = vec![vec![1]];
+        let result = calculate_stv_result(candidates, votes, 2, None);
 
         let expected_result = VotingResult {
             round_results: vec![VotingRoundResult {
@@ -416,7 +670,7 @@ mod tests {
                 candidate_results: vec![
                     PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "a".to_string(),
+                            candidate_id: 1,
                             vote_count: 1.0,
                             is_draw: false,
                         },
@@ -424,7 +678,7 @@ mod tests {
                     },
                     PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "b".to_string(),
+                            candidate_id: 2,
                             vote_count: 0.0,
                             is_draw: false,
                         },
@@ -432,8 +686,11 @@ mod tests {
                     },
                 ],
                 dropped_candidate: None,
+                transfers: vec![],
+                non_transferable: NonTransferableWeight::default(),
             }],
-            winners: vec!["a".to_string(), "b".to_string()],
+            winners: vec![1, 2],
+            decimal_places: None,
         };
 
         match result {
@@ -444,16 +701,16 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_surplus_votes_no_next_candidate_and_double_transfer() {
-        let candidates = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let candidates = vec![1, 2, 3];
         let votes: Vec<Vec<CandidateId>> = vec![
-            vec!["a".to_string(), "c".to_string(), "b".to_string()],
-            vec!["a".to_string(), "b".to_string(), "c".to_string()],
-            vec!["a".to_string(), "b".to_string(), "c".to_string()],
-            vec!["a".to_string()],
-            vec!["b".to_string()],
+            vec![1, 3, 2],
+            vec![1, 2, 3],
+            vec![1, 2, 3],
+            vec![1],
+            vec![2],
         ];
         let quota = (5.0 / (2.0 + 1.0)) + 1.0;
-        let result = calculate_stv_result(candidates, votes, 2);
+        let result = calculate_stv_result(candidates, votes, 2, None);
 
         let expected_result = VotingResult {
             round_results: vec![
@@ -462,7 +719,7 @@ mod tests {
                     candidate_results: vec![
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "a".to_string(),
+                                candidate_id: 1,
                                 vote_count: 4.0,
                                 is_draw: false,
                             },
@@ -470,7 +727,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "b".to_string(),
+                                candidate_id: 2,
                                 vote_count: 1.0,
                                 is_draw: false,
                             },
@@ -478,7 +735,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "c".to_string(),
+                                candidate_id: 3,
                                 vote_count: 0.0,
                                 is_draw: false,
                             },
@@ -486,37 +743,67 @@ mod tests {
                         },
                     ],
                     dropped_candidate: None,
+                    transfers: vec![
+                        VoteTransfer {
+                            from_candidate_id: 1,
+                            to_candidate_id: None,
+                            weight: (4.0 - quota) * (1.0 / 4.0),
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 1,
+                            to_candidate_id: Some(2),
+                            weight: (4.0 - quota) * (2.0 / 4.0),
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 1,
+                            to_candidate_id: Some(3),
+                            weight: (4.0 - quota) * (1.0 / 4.0),
+                        },
+                    ],
+                    non_transferable: NonTransferableWeight {
+                        exhausted: (4.0 - quota) * (1.0 / 4.0),
+                        lost: 0.0,
+                    },
                 },
                 VotingRoundResult {
                     round: 2,
                     candidate_results: vec![PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "b".to_string(),
+                            candidate_id: 2,
                             vote_count: 1.0 + (4.0 - quota) * (2.0 / 4.0),
                             is_draw: false,
                         },
                         is_selected: false,
                     }],
                     dropped_candidate: Some(CandidateResultData {
-                        name: "c".to_string(),
+                        candidate_id: 3,
                         vote_count: (4.0 - quota) * (1.0 / 4.0),
                         is_draw: false,
                     }),
+                    transfers: vec![VoteTransfer {
+                        from_candidate_id: 3,
+                        to_candidate_id: Some(2),
+                        weight: (4.0 - quota) * (1.0 / 4.0),
+                    }],
+                    non_transferable: NonTransferableWeight::default(),
                 },
                 VotingRoundResult {
                     round: 3,
                     candidate_results: vec![PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "b".to_string(),
+                            candidate_id: 2,
                             vote_count: 1.0 + (4.0 - quota) * ((2.0 / 4.0) + (1.0 / 4.0)),
                             is_draw: false,
                         },
                         is_selected: true,
                     }],
                     dropped_candidate: None,
+                    transfers: vec![],
+                    non_transferable: NonTransferableWeight::default(),
                 },
             ],
-            winners: vec!["a".to_string(), "b".to_string()],
+            winners: vec![1, 2],
+            decimal_places: None,
         };
 
         match result {
@@ -527,37 +814,30 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_candidates_over_quota_are_elected() {
-        let candidates = vec![
-            "a".to_string(),
-            "b".to_string(),
-            "c".to_string(),
-            "d".to_string(),
-        ];
+        let candidates = vec![1, 2, 3, 4];
 
-        let a_b_votes: Vec<Vec<String>> = std::iter::repeat(vec!["a".to_string(), "b".to_string()])
-            .take(15)
-            .collect();
-        let b_a_votes: Vec<Vec<String>> = std::iter::repeat(vec!["b".to_string(), "c".to_string()])
-            .take(11)
-            .collect();
-        let b_d_votes: Vec<Vec<String>> = std::iter::repeat(vec!["b".to_string(), "d".to_string()])
-            .take(1)
-            .collect();
-        let a_c_votes = std::iter::repeat(vec!["a".to_string(), "c".to_string()])
-            .take(1)
-            .collect();
-        let c_votes = std::iter::repeat(vec!["c".to_string()]).take(1).collect();
+        let a_b_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![1, 2], 15).collect();
+        let b_a_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![2, 3], 11).collect();
+        let b_d_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![2, 4], 1).collect();
+        let a_c_votes = std::iter::repeat_n(vec![1, 3], 1).collect();
+        let c_votes = std::iter::repeat_n(vec![3], 1).collect();
 
         let votes = [a_b_votes, b_a_votes, b_d_votes, a_c_votes, c_votes].concat();
-        let _quota = (votes.len() as f64 / (2.0 + 1.0)) + 1.0;
-        let result = calculate_stv_result(candidates, votes, 2);
+        let quota = (votes.len() as f64 / (2.0 + 1.0)) + 1.0;
+        let result = calculate_stv_result(candidates, votes, 2, None);
+
+        // Candidate 2 is elected on the same round and passes on the surplus of the 17 votes
+        // it holds after receiving from candidate 1
+        let received_by_2 = (16.0 - quota) * (15.0 / 16.0);
+        let pile_of_2 = 12.0 + received_by_2;
+        let surplus_of_2 = pile_of_2 - quota;
 
         let expected_first_round = VotingRoundResult {
             round: 1,
             candidate_results: vec![
                 PassingCandidateResult {
                     data: CandidateResultData {
-                        name: "a".to_string(),
+                        candidate_id: 1,
                         vote_count: 16.0,
 
                         is_draw: false,
@@ -566,7 +846,7 @@ mod tests {
                 },
                 PassingCandidateResult {
                     data: CandidateResultData {
-                        name: "b".to_string(),
+                        candidate_id: 2,
                         vote_count: 12.0,
                         is_draw: false,
                     },
@@ -574,7 +854,7 @@ mod tests {
                 },
                 PassingCandidateResult {
                     data: CandidateResultData {
-                        name: "c".to_string(),
+                        candidate_id: 3,
                         vote_count: 1.0,
                         is_draw: false,
                     },
@@ -582,7 +862,7 @@ mod tests {
                 },
                 PassingCandidateResult {
                     data: CandidateResultData {
-                        name: "d".to_string(),
+                        candidate_id: 4,
                         vote_count: 0.0,
                         is_draw: false,
                     },
@@ -590,6 +870,37 @@ mod tests {
                 },
             ],
             dropped_candidate: None,
+            transfers: vec![
+                VoteTransfer {
+                    from_candidate_id: 1,
+                    to_candidate_id: Some(2),
+                    weight: (16.0 - quota) * (15.0 / 16.0),
+                },
+                VoteTransfer {
+                    from_candidate_id: 1,
+                    to_candidate_id: Some(3),
+                    weight: (16.0 - quota) * (1.0 / 16.0),
+                },
+                VoteTransfer {
+                    from_candidate_id: 2,
+                    to_candidate_id: None,
+                    weight: surplus_of_2 * (received_by_2 / pile_of_2),
+                },
+                VoteTransfer {
+                    from_candidate_id: 2,
+                    to_candidate_id: Some(3),
+                    weight: surplus_of_2 * (11.0 / pile_of_2),
+                },
+                VoteTransfer {
+                    from_candidate_id: 2,
+                    to_candidate_id: Some(4),
+                    weight: surplus_of_2 * (1.0 / pile_of_2),
+                },
+            ],
+            non_transferable: NonTransferableWeight {
+                exhausted: surplus_of_2 * (received_by_2 / pile_of_2),
+                lost: 0.0,
+            },
         };
 
         match result {
@@ -603,23 +914,19 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_gregory_transfer_proportions() {
-        let candidates = vec!["a".to_string(), "b".to_string(), "c".to_string()];
-
-        let a_b_votes: Vec<Vec<String>> = std::iter::repeat(vec!["a".to_string(), "b".to_string()])
-            .take(10)
-            .collect();
-        let a_c_votes: Vec<Vec<String>> = std::iter::repeat(vec!["a".to_string(), "c".to_string()])
-            .take(9)
-            .collect();
-        let a_votes: Vec<Vec<String>> = std::iter::repeat(vec!["a".to_string()]).take(8).collect();
-        let b_votes: Vec<Vec<String>> = std::iter::repeat(vec!["b".to_string()]).take(10).collect();
-        let c_votes: Vec<Vec<String>> = std::iter::repeat(vec!["c".to_string()]).take(9).collect();
+        let candidates = vec![1, 2, 3];
+
+        let a_b_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![1, 2], 10).collect();
+        let a_c_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![1, 3], 9).collect();
+        let a_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![1], 8).collect();
+        let b_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![2], 10).collect();
+        let c_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![3], 9).collect();
 
         let votes: Vec<Vec<CandidateId>> =
             [a_b_votes, a_c_votes, a_votes, b_votes, c_votes].concat();
 
         let quota = (votes.len() as f64 / (2.0 + 1.0)) + 1.0; // 24
-        let result = calculate_stv_result(candidates, votes, 2);
+        let result = calculate_stv_result(candidates, votes, 2, None);
 
         let expected_result = VotingResult {
             round_results: vec![
@@ -628,7 +935,7 @@ mod tests {
                     candidate_results: vec![
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "a".to_string(),
+                                candidate_id: 1,
                                 vote_count: 27.0,
                                 is_draw: false,
                             },
@@ -636,7 +943,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "b".to_string(),
+                                candidate_id: 2,
                                 vote_count: 10.0,
                                 is_draw: false,
                             },
@@ -644,7 +951,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "c".to_string(),
+                                candidate_id: 3,
                                 vote_count: 9.0,
                                 is_draw: false,
                             },
@@ -652,37 +959,70 @@ mod tests {
                         },
                     ],
                     dropped_candidate: None,
+                    transfers: vec![
+                        VoteTransfer {
+                            from_candidate_id: 1,
+                            to_candidate_id: None,
+                            weight: (27.0 - quota) * (8.0 / 27.0),
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 1,
+                            to_candidate_id: Some(2),
+                            weight: (27.0 - quota) * (10.0 / 27.0),
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 1,
+                            to_candidate_id: Some(3),
+                            weight: (27.0 - quota) * (9.0 / 27.0),
+                        },
+                    ],
+                    non_transferable: NonTransferableWeight {
+                        exhausted: (27.0 - quota) * (8.0 / 27.0),
+                        lost: 0.0,
+                    },
                 },
                 VotingRoundResult {
                     round: 2,
                     candidate_results: vec![PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "b".to_string(),
+                            candidate_id: 2,
                             vote_count: 10.0 + (27.0 - quota) * (10.0 / 27.0),
                             is_draw: false,
                         },
                         is_selected: false,
                     }],
                     dropped_candidate: Some(CandidateResultData {
-                        name: "c".to_string(),
+                        candidate_id: 3,
                         vote_count: 9.0 + (27.0 - quota) * (9.0 / 27.0),
                         is_draw: false,
                     }),
+                    transfers: vec![VoteTransfer {
+                        from_candidate_id: 3,
+                        to_candidate_id: None,
+                        weight: 9.0 + (27.0 - quota) * (9.0 / 27.0),
+                    }],
+                    non_transferable: NonTransferableWeight {
+                        exhausted: 9.0 + (27.0 - quota) * (9.0 / 27.0),
+                        lost: 0.0,
+                    },
                 },
                 VotingRoundResult {
                     round: 3,
                     candidate_results: vec![PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "b".to_string(),
+                            candidate_id: 2,
                             vote_count: 10.0 + (27.0 - quota) * (10.0 / 27.0),
                             is_draw: false,
                         },
                         is_selected: true,
                     }],
                     dropped_candidate: None,
+                    transfers: vec![],
+                    non_transferable: NonTransferableWeight::default(),
                 },
             ],
-            winners: vec!["a".to_string(), "b".to_string()],
+            winners: vec![1, 2],
+            decimal_places: None,
         };
 
         match result {
@@ -693,36 +1033,26 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_vote_transfer_chain() {
-        let candidates = vec![
-            "a".to_string(),
-            "b".to_string(),
-            "c".to_string(),
-            "d".to_string(),
-        ];
+        let candidates = vec![1, 2, 3, 4];
 
         let votes: Vec<Vec<CandidateId>> = vec![
-            vec!["a".to_string()],
-            vec!["a".to_string()],
-            vec!["a".to_string()],
-            vec!["a".to_string()],
-            vec!["a".to_string()],
-            vec!["a".to_string()],
-            vec!["b".to_string()],
-            vec!["b".to_string()],
-            vec!["b".to_string()],
-            vec!["b".to_string()],
-            vec!["c".to_string()],
-            vec!["c".to_string()],
-            vec![
-                "d".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "a".to_string(),
-            ],
+            vec![1],
+            vec![1],
+            vec![1],
+            vec![1],
+            vec![1],
+            vec![1],
+            vec![2],
+            vec![2],
+            vec![2],
+            vec![2],
+            vec![3],
+            vec![3],
+            vec![4, 3, 2, 1],
         ];
 
         let _quota = (votes.len() as f64 / (1.0 + 1.0)) + 1.0; // 8.5
-        let result = calculate_stv_result(candidates, votes, 1);
+        let result = calculate_stv_result(candidates, votes, 1, None);
 
         let expected_result = VotingResult {
             round_results: vec![
@@ -731,7 +1061,7 @@ mod tests {
                     candidate_results: vec![
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "a".to_string(),
+                                candidate_id: 1,
                                 vote_count: 6.0,
                                 is_draw: false,
                             },
@@ -739,7 +1069,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "b".to_string(),
+                                candidate_id: 2,
                                 vote_count: 4.0,
                                 is_draw: false,
                             },
@@ -747,7 +1077,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "c".to_string(),
+                                candidate_id: 3,
                                 vote_count: 2.0,
                                 is_draw: false,
                             },
@@ -755,17 +1085,23 @@ mod tests {
                         },
                     ],
                     dropped_candidate: Some(CandidateResultData {
-                        name: "d".to_string(),
+                        candidate_id: 4,
                         vote_count: 1.0,
                         is_draw: false,
                     }),
+                    transfers: vec![VoteTransfer {
+                        from_candidate_id: 4,
+                        to_candidate_id: Some(3),
+                        weight: 1.0,
+                    }],
+                    non_transferable: NonTransferableWeight::default(),
                 },
                 VotingRoundResult {
                     round: 2,
                     candidate_results: vec![
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "a".to_string(),
+                                candidate_id: 1,
                                 vote_count: 6.0,
                                 is_draw: false,
                             },
@@ -773,7 +1109,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "b".to_string(),
+                                candidate_id: 2,
                                 vote_count: 4.0,
                                 is_draw: false,
                             },
@@ -781,41 +1117,76 @@ mod tests {
                         },
                     ],
                     dropped_candidate: Some(CandidateResultData {
-                        name: "c".to_string(),
+                        candidate_id: 3,
                         vote_count: 3.0,
                         is_draw: false,
                     }),
+                    transfers: vec![
+                        VoteTransfer {
+                            from_candidate_id: 3,
+                            to_candidate_id: None,
+                            weight: 2.0,
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 3,
+                            to_candidate_id: Some(2),
+                            weight: 1.0,
+                        },
+                    ],
+                    non_transferable: NonTransferableWeight {
+                        exhausted: 2.0,
+                        lost: 0.0,
+                    },
                 },
                 VotingRoundResult {
                     round: 3,
                     candidate_results: vec![PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "a".to_string(),
+                            candidate_id: 1,
                             vote_count: 6.0,
                             is_draw: false,
                         },
                         is_selected: false,
                     }],
                     dropped_candidate: Some(CandidateResultData {
-                        name: "b".to_string(),
+                        candidate_id: 2,
                         vote_count: 5.0,
                         is_draw: false,
                     }),
+                    transfers: vec![
+                        VoteTransfer {
+                            from_candidate_id: 2,
+                            to_candidate_id: None,
+                            weight: 4.0,
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 2,
+                            to_candidate_id: Some(1),
+                            weight: 1.0,
+                        },
+                    ],
+                    non_transferable: NonTransferableWeight {
+                        exhausted: 4.0,
+                        lost: 0.0,
+                    },
                 },
                 VotingRoundResult {
                     round: 4,
                     candidate_results: vec![PassingCandidateResult {
                         data: CandidateResultData {
-                            name: "a".to_string(),
+                            candidate_id: 1,
                             vote_count: 7.0,
                             is_draw: false,
                         },
                         is_selected: true,
                     }],
                     dropped_candidate: None,
+                    transfers: vec![],
+                    non_transferable: NonTransferableWeight::default(),
                 },
             ],
-            winners: vec!["a".to_string()],
+            winners: vec![1],
+            decimal_places: None,
         };
 
         match result {
@@ -826,519 +1197,94 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_prodeko_chairman_2024() {
-        let candidates = vec![
-            "a".to_string(),
-            "b".to_string(),
-            "c".to_string(),
-            "d".to_string(),
-            "e".to_string(),
-        ];
+        let candidates = vec![1, 2, 3, 4, 5];
 
         let votes: Vec<Vec<CandidateId>> = vec![
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-            ],
-            vec!["b".to_string(), "d".to_string()],
-            vec!["c".to_string(), "a".to_string(), "b".to_string()],
-            vec![
-                "c".to_string(),
-                "a".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-                "d".to_string(),
-            ],
-            vec![
-                "d".to_string(),
-                "a".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-            ],
-            vec![
-                "d".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "e".to_string(),
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "e".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "c".to_string(),
-                "b".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec!["b".to_string()],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-                "d".to_string(),
-            ],
-            vec![
-                "c".to_string(),
-                "b".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-                "c".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "c".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "e".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-            ],
-            vec![
-                "d".to_string(),
-                "a".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "d".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec!["c".to_string()],
-            vec![
-                "e".to_string(),
-                "a".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-            ],
-            vec![
-                "e".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "a".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec!["b".to_string()],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "c".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "a".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "d".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "c".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-            ],
-            vec![
-                "c".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-            ],
-            vec![
-                "c".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec!["c".to_string()],
-            vec![
-                "e".to_string(),
-                "d".to_string(),
-                "a".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "a".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "d".to_string(),
-                "c".to_string(),
-                "a".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec!["d".to_string(), "b".to_string(), "c".to_string()],
-            vec![
-                "a".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-                "d".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec!["b".to_string(), "a".to_string(), "d".to_string()],
-            vec![
-                "e".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-            ],
-            vec!["a".to_string()],
-            vec!["b".to_string(), "c".to_string(), "a".to_string()],
-            vec![
-                "d".to_string(),
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-            ],
-            vec![
-                "e".to_string(),
-                "b".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-            ],
-            vec![
-                "c".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "d".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-                "a".to_string(),
-            ],
-            vec![
-                "d".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-                "c".to_string(),
-                "a".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-                "a".to_string(),
-            ],
-            vec![
-                "d".to_string(),
-                "c".to_string(),
-                "a".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "e".to_string(),
-                "b".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "c".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "d".to_string(),
-                "a".to_string(),
-                "e".to_string(),
-                "c".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "d".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec!["e".to_string(), "c".to_string(), "a".to_string()],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-                "d".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "a".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "b".to_string(),
-                "e".to_string(),
-            ],
-            vec![
-                "b".to_string(),
-                "d".to_string(),
-                "c".to_string(),
-                "e".to_string(),
-                "a".to_string(),
-            ],
+            vec![1, 2, 3, 4, 5],
+            vec![2, 3, 4, 1, 5],
+            vec![2, 4],
+            vec![3, 1, 2],
+            vec![3, 1, 2, 5, 4],
+            vec![4, 1, 3, 2, 5],
+            vec![3, 2, 5, 1, 4],
+            vec![4, 3, 2, 1, 5],
+            vec![1, 4, 2, 3, 5],
+            vec![5, 1, 2, 3, 4],
+            vec![1, 5, 2, 3, 4],
+            vec![1, 2, 4, 3, 5],
+            vec![1, 4, 3, 2, 5],
+            vec![1, 4, 2, 3, 5],
+            vec![3, 2, 1, 4, 5],
+            vec![2],
+            vec![1, 2, 3, 5, 4],
+            vec![3, 2, 1, 4, 5],
+            vec![1, 4, 2, 5, 3],
+            vec![1, 2, 3, 5],
+            vec![2, 3, 1, 4, 5],
+            vec![1, 5, 2, 4, 3],
+            vec![4, 1, 3, 2, 5],
+            vec![4, 1, 5, 3, 2],
+            vec![1, 2, 3, 4, 5],
+            vec![2, 1, 4, 3, 5],
+            vec![3],
+            vec![5, 1, 2, 4, 3],
+            vec![5, 2, 3, 4, 1],
+            vec![1, 4, 3, 2, 5],
+            vec![2],
+            vec![1, 2, 3, 4, 5],
+            vec![3, 4, 2, 1, 5],
+            vec![2, 1, 3, 4, 5],
+            vec![4, 2, 3, 1, 5],
+            vec![3, 1, 5, 4, 2],
+            vec![3, 1, 5, 4, 2],
+            vec![3, 1, 4, 2, 5],
+            vec![3],
+            vec![5, 4, 1, 3, 2],
+            vec![1, 2, 4, 3, 5],
+            vec![1, 2, 4, 3, 5],
+            vec![2, 1, 3, 4, 5],
+            vec![4, 3, 1, 2, 5],
+            vec![4, 2, 3],
+            vec![1, 3, 5, 4, 2],
+            vec![1, 2, 3, 5, 4],
+            vec![1, 2, 3, 4, 5],
+            vec![2, 1, 4],
+            vec![5, 1, 4, 3, 2],
+            vec![1],
+            vec![2, 3, 1],
+            vec![4, 1, 2, 3, 5],
+            vec![1, 2, 5, 4, 3],
+            vec![5, 2, 1, 4, 3],
+            vec![3, 2, 4, 1, 5],
+            vec![4, 3, 2, 5, 1],
+            vec![4, 2, 5, 3, 1],
+            vec![1, 4, 3, 2, 5],
+            vec![2, 4, 3, 5, 1],
+            vec![4, 3, 1, 2, 5],
+            vec![1, 4, 3, 2, 5],
+            vec![1, 2, 3, 4, 5],
+            vec![5, 2, 1, 4, 3],
+            vec![1, 3, 5, 4, 2],
+            vec![1, 2, 4, 3, 5],
+            vec![3, 2, 5, 1, 4],
+            vec![1, 3, 4, 2, 5],
+            vec![1, 3, 2, 4, 5],
+            vec![2, 4, 3, 1, 5],
+            vec![1, 2, 4, 3, 5],
+            vec![1, 3, 2, 4, 5],
+            vec![2, 4, 1, 5, 3],
+            vec![1, 4, 2, 3, 5],
+            vec![2, 1, 4, 3, 5],
+            vec![5, 3, 1],
+            vec![1, 2, 3, 5, 4],
+            vec![1, 3, 2, 4, 5],
+            vec![1, 2, 4, 3, 5],
+            vec![1, 4, 3, 2, 5],
+            vec![2, 4, 3, 5, 1],
         ];
 
-        let _quota = (votes.len() as f64 / (1.0 + 1.0)) + 1.0; // 41.5
-        let result = calculate_stv_result(candidates, votes, 1);
+        let quota = (votes.len() as f64 / (1.0 + 1.0)) + 1.0; // 41.5
+        let result = calculate_stv_result(candidates, votes, 1, None);
 
         let expected_result = VotingResult {
             round_results: vec![
@@ -1347,7 +1293,7 @@ mod tests {
                     candidate_results: vec![
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "a".to_string(),
+                                candidate_id: 1,
                                 vote_count: 34.0,
                                 is_draw: false,
                             },
@@ -1355,7 +1301,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "b".to_string(),
+                                candidate_id: 2,
                                 vote_count: 15.0,
                                 is_draw: false,
                             },
@@ -1363,7 +1309,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "c".to_string(),
+                                candidate_id: 3,
                                 vote_count: 13.0,
                                 is_draw: false,
                             },
@@ -1371,7 +1317,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "d".to_string(),
+                                candidate_id: 4,
                                 vote_count: 11.0,
                                 is_draw: false,
                             },
@@ -1379,17 +1325,40 @@ mod tests {
                         },
                     ],
                     dropped_candidate: Some(CandidateResultData {
-                        name: "e".to_string(),
+                        candidate_id: 5,
                         vote_count: 8.0,
                         is_draw: false,
                     }),
+                    transfers: vec![
+                        VoteTransfer {
+                            from_candidate_id: 5,
+                            to_candidate_id: Some(1),
+                            weight: 3.0,
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 5,
+                            to_candidate_id: Some(2),
+                            weight: 3.0,
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 5,
+                            to_candidate_id: Some(3),
+                            weight: 1.0,
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 5,
+                            to_candidate_id: Some(4),
+                            weight: 1.0,
+                        },
+                    ],
+                    non_transferable: NonTransferableWeight::default(),
                 },
                 VotingRoundResult {
                     round: 2,
                     candidate_results: vec![
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "a".to_string(),
+                                candidate_id: 1,
                                 vote_count: 37.0,
                                 is_draw: false,
                             },
@@ -1397,7 +1366,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "b".to_string(),
+                                candidate_id: 2,
                                 vote_count: 18.0,
                                 is_draw: false,
                             },
@@ -1405,7 +1374,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "c".to_string(),
+                                candidate_id: 3,
                                 vote_count: 14.0,
                                 is_draw: false,
                             },
@@ -1413,17 +1382,35 @@ mod tests {
                         },
                     ],
                     dropped_candidate: Some(CandidateResultData {
-                        name: "d".to_string(),
+                        candidate_id: 4,
                         vote_count: 12.0,
                         is_draw: false,
                     }),
+                    transfers: vec![
+                        VoteTransfer {
+                            from_candidate_id: 4,
+                            to_candidate_id: Some(1),
+                            weight: 5.0,
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 4,
+                            to_candidate_id: Some(2),
+                            weight: 3.0,
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 4,
+                            to_candidate_id: Some(3),
+                            weight: 4.0,
+                        },
+                    ],
+                    non_transferable: NonTransferableWeight::default(),
                 },
                 VotingRoundResult {
                     round: 3,
                     candidate_results: vec![
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "a".to_string(),
+                                candidate_id: 1,
                                 vote_count: 42.0,
                                 is_draw: false,
                             },
@@ -1431,7 +1418,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "b".to_string(),
+                                candidate_id: 2,
                                 vote_count: 21.0,
                                 is_draw: false,
                             },
@@ -1439,7 +1426,7 @@ mod tests {
                         },
                         PassingCandidateResult {
                             data: CandidateResultData {
-                                name: "c".to_string(),
+                                candidate_id: 3,
                                 vote_count: 18.0,
                                 is_draw: false,
                             },
@@ -1447,9 +1434,31 @@ mod tests {
                         },
                     ],
                     dropped_candidate: None,
+                    transfers: vec![
+                        VoteTransfer {
+                            from_candidate_id: 1,
+                            to_candidate_id: None,
+                            weight: (42.0 - quota) * (1.0 / 42.0),
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 1,
+                            to_candidate_id: Some(2),
+                            weight: (42.0 - quota) * (25.0 / 42.0),
+                        },
+                        VoteTransfer {
+                            from_candidate_id: 1,
+                            to_candidate_id: Some(3),
+                            weight: (42.0 - quota) * (16.0 / 42.0),
+                        },
+                    ],
+                    non_transferable: NonTransferableWeight {
+                        exhausted: (42.0 - quota) * (1.0 / 42.0),
+                        lost: 0.0,
+                    },
                 },
             ],
-            winners: vec!["a".to_string()],
+            winners: vec![1],
+            decimal_places: None,
         };
 
         match result {
@@ -1457,4 +1466,195 @@ mod tests {
             Err(e) => panic!("{:?}", e),
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_decimal_count_truncates_transfer_values() {
+        let candidates = vec![1, 2, 3];
+        let a_b_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![1, 2], 7).collect();
+        let c_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![3], 2).collect();
+        let votes = [a_b_votes, c_votes, vec![vec![2]]].concat();
+
+        // Quota 10 / 3 + 1 = 4.33333 and surplus 7 - 4.33333 = 2.66667. Each of the 7 ballots
+        // carries 2.66667 / 7 = 0.380952... truncated to 0.38095.
+        match calculate_stv_result(candidates, votes, 2, Some(5)) {
+            Ok(res) => {
+                assert_eq!(res.winners, vec![1, 2]);
+                assert_eq!(
+                    res.round_results[0].transfers,
+                    vec![VoteTransfer {
+                        from_candidate_id: 1,
+                        to_candidate_id: Some(2),
+                        weight: 2.66665,
+                    }]
+                );
+                assert_eq!(
+                    res.round_results[0].non_transferable,
+                    NonTransferableWeight {
+                        exhausted: 0.0,
+                        lost: 0.00002,
+                    }
+                );
+                assert_eq!(
+                    res.round_results[1].candidate_results[0].data,
+                    CandidateResultData {
+                        candidate_id: 2,
+                        vote_count: 3.66665,
+                        is_draw: false,
+                    }
+                );
+            }
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_surplus_received_on_the_same_round_is_passed_on() {
+        let candidates = vec![1, 2, 3];
+        let a_b_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![1, 2], 8).collect();
+        let b_c_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![2, 3], 7).collect();
+        let votes = [a_b_votes, b_c_votes, vec![vec![3]]].concat();
+
+        // Quota 16 / 3 + 1 = 6.33333, so candidates 1 and 2 are both elected on the first round.
+        // Candidate 2 holds 7 + 1.66667 votes after receiving the surplus of candidate 1. The
+        // transfer values used to be scaled by the 7 votes it had at the start of the round,
+        // which recorded most of its surplus of 2.33333 as lost.
+        match calculate_stv_result(candidates, votes, 2, None) {
+            Ok(res) => {
+                let quota = 16.0 / 3.0 + 1.0;
+                let surplus_of_2 = 7.0 + (8.0 - quota) - quota;
+                let round = &res.round_results[0];
+                let transferred_by_2 = round
+                    .transfers
+                    .iter()
+                    .filter(|t| t.from_candidate_id == 2)
+                    .map(|t| t.weight)
+                    .sum::<f64>();
+                assert!(approx_eq!(
+                    f64,
+                    transferred_by_2,
+                    surplus_of_2,
+                    epsilon = 0.000001
+                ));
+                assert!(!round.non_transferable.has_lost());
+            }
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_decimal_count_with_zero_vote_candidate_accepted() {
+        match calculate_stv_result(vec![1, 2], vec![vec![1, 2]], 2, Some(5)) {
+            Ok(res) => {
+                assert_eq!(res.winners.len(), 2);
+                assert!(res.round_results[0].transfers.is_empty());
+            }
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_float_count_with_zero_vote_candidate_accepted() {
+        match calculate_stv_result(vec![1, 2], vec![vec![1, 2]], 2, None) {
+            Ok(res) => {
+                let non_transferable = &res.round_results[0].non_transferable;
+                assert_eq!(res.winners.len(), 2);
+                assert!(!non_transferable.exhausted.is_nan() && !non_transferable.lost.is_nan());
+                assert!(!non_transferable.has_exhausted());
+                assert!(!non_transferable.has_lost());
+            }
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_decimal_count_loses_only_truncation_when_two_elected() {
+        let candidates = vec![1, 2, 3, 4];
+        let a_b_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![1, 2], 15).collect();
+        let b_c_votes: Vec<Vec<CandidateId>> = std::iter::repeat_n(vec![2, 3], 11).collect();
+        let votes = [a_b_votes, b_c_votes, vec![vec![2, 4], vec![1, 3], vec![3]]].concat();
+
+        // Quota 29 / 3 + 1 = 10.66666. Candidate 1 passes 16 x 0.33333 of its surplus 5.33334
+        // and candidate 2 the surplus 6.33329 of the 16.99995 votes it then holds: 12 x 0.37254
+        // and 15 x 0.12418. Only the truncated decimals are lost.
+        match calculate_stv_result(candidates, votes, 2, Some(5)) {
+            Ok(res) => {
+                assert_eq!(
+                    res.round_results[0].non_transferable,
+                    NonTransferableWeight {
+                        exhausted: 1.8627,
+                        lost: 0.00017,
+                    }
+                );
+                assert_eq!(
+                    res.round_results[0]
+                        .transfers
+                        .iter()
+                        .filter(|t| t.to_candidate_id == Some(3))
+                        .map(|t| t.weight)
+                        .collect::<Vec<_>>(),
+                    vec![0.33333, 4.09794]
+                );
+            }
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_excluded_candidate_votes_transfer_to_next_preference() {
+        let candidates = vec![1, 2, 3];
+        let votes: Vec<Vec<CandidateId>> = vec![vec![1, 2], vec![1, 3], vec![2], vec![1]];
+        let (candidates, votes) = exclude_candidates(candidates, votes, &[1]);
+
+        assert_eq!(candidates, vec![2, 3]);
+        assert_eq!(votes, vec![vec![2], vec![3], vec![2], vec![]]);
+
+        match calculate_stv_result(candidates, votes, 1, None) {
+            Ok(res) => assert_eq!(res.winners, vec![2]),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_recount_has_same_outcome() {
+        let candidates = vec![1, 2, 3];
+        let votes: Vec<Vec<CandidateId>> = vec![
+            vec![1, 2],
+            vec![1, 3],
+            vec![2, 3],
+            vec![2, 1],
+            vec![3, 2],
+            vec![1],
+        ];
+
+        let stored = calculate_stv_result(candidates.clone(), votes.clone(), 2, None).unwrap();
+        let recounted = calculate_stv_result(candidates.clone(), votes.clone(), 2, None).unwrap();
+        assert!(!stored.has_draws());
+        assert!(stored.has_same_outcome(&recounted));
+
+        let fewer_winners = calculate_stv_result(candidates, votes, 1, None).unwrap();
+        assert!(!stored.has_same_outcome(&fewer_winners));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_seat_won_by_ron_stays_unfilled() {
+        let ron = 3;
+        let candidates = vec![1, 2, ron];
+        let votes: Vec<Vec<CandidateId>> = vec![
+            vec![1],
+            vec![ron, 2],
+            vec![ron],
+            vec![ron],
+            vec![1, 2],
+            vec![1],
+        ];
+
+        match calculate_stv_result(candidates, votes, 2, None) {
+            Ok(res) => {
+                assert_eq!(res.winners.len(), 2);
+                assert_eq!(res.elected(Some(ron)), vec![1]);
+                assert_eq!(res.unfilled_seats(2, Some(ron)), 1);
+            }
+            Err(e) => panic!("{:?}", e),
+        }
+    }
 }
@@ -0,0 +1,31 @@
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+
+use crate::{api_types::ApiResult, config::Config, helpers::hash_password, models::AdminRole};
+
+/// Creates a superadmin from the configured credentials if the database has no admin accounts.
+pub async fn create_initial_admin(db: &Pool<Postgres>, config: &Config) -> ApiResult<()> {
+    let admin_count = sqlx::query!("SELECT count(*) AS \"count!\" FROM admin_account")
+        .fetch_one(db)
+        .await?
+        .count;
+
+    if admin_count > 0 {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        "
+        INSERT INTO admin_account (username, password_hash, role, created_at)
+        VALUES ($1, $2, $3, $4)
+        ",
+        config.admin_username,
+        hash_password(config.admin_password.clone()).await?,
+        AdminRole::Superadmin as AdminRole,
+        Utc::now(),
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
+use std::time::Duration;
+
 use sqlx::{postgres::PgPoolOptions, Error, Pool, Postgres};
 
-pub async fn create_pg_pool(db_url: &str, max_connections: u32) -> Result<Pool<Postgres>, Error> {
+/// Connects lazily, so that the server starts and reports itself unready while the database is
+/// unreachable.
+pub fn create_pg_pool(db_url: &str, max_connections: u32) -> Result<Pool<Postgres>, Error> {
     PgPoolOptions::new()
         .max_connections(max_connections)
-        .connect(db_url)
-        .await
+        .acquire_timeout(Duration::from_secs(5))
+        .connect_lazy(db_url)
 }
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use sqlx::{Executor, Postgres};
+
+use crate::{
+    api_types::ApiResult,
+    models::{Candidate, VotingId},
+};
+
+/// Candidates of one voting in display order, with RON last.
+pub async fn get_candidates<'e, E>(executor: E, voting_id: VotingId) -> ApiResult<Vec<Candidate>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as!(
+        Candidate,
+        "
+        SELECT
+            c.id,
+            c.name,
+            c.bio,
+            c.display_order,
+            c.withdrawn_at,
+            c.is_ron,
+            EXISTS (SELECT 1 FROM candidate_image AS i WHERE i.candidate_id = c.id) AS \"has_image!\"
+        FROM candidate AS c
+        WHERE c.voting_id = $1
+        ORDER BY c.is_ron, c.display_order, c.id
+        ",
+        voting_id
+    )
+    .fetch_all(executor)
+    .await
+    .map_err(|e| e.into())
+}
+
+/// Candidates of every voting in display order, keyed by voting.
+pub async fn get_candidates_of_all_votings<'e, E>(
+    executor: E,
+) -> ApiResult<HashMap<VotingId, Vec<Candidate>>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let rows = sqlx::query!(
+        "
+        SELECT
+            c.voting_id,
+            c.id,
+            c.name,
+            c.bio,
+            c.display_order,
+            c.withdrawn_at,
+            c.is_ron,
+            EXISTS (SELECT 1 FROM candidate_image AS i WHERE i.candidate_id = c.id) AS \"has_image!\"
+        FROM candidate AS c
+        ORDER BY c.voting_id, c.is_ron, c.display_order, c.id
+        "
+    )
+    .fetch_all(executor)
+    .await?;
+
+    let mut candidates: HashMap<VotingId, Vec<Candidate>> = HashMap::new();
+    rows.into_iter().for_each(|row| {
+        candidates
+            .entry(row.voting_id)
+            .or_default()
+            .push(Candidate {
+                id: row.id,
+                name: row.name,
+                bio: row.bio,
+                display_order: row.display_order,
+                has_image: row.has_image,
+                withdrawn_at: row.withdrawn_at,
+                is_ron: row.is_ron,
+            })
+    });
+
+    Ok(candidates)
+}
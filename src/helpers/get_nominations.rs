@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use sqlx::{Executor, Postgres};
+
+use crate::{
+    api_types::ApiResult,
+    models::{Nomination, NominationState, VotingId},
+};
+
+/// Nominations of every voting whose nominations are open, keyed by voting, in the order they
+/// were made. With `approved_only` the pending and rejected nominations are left out, which is
+/// what voters see.
+pub async fn get_nominations_of_nominating_votings<'e, E>(
+    executor: E,
+    approved_only: bool,
+) -> ApiResult<HashMap<VotingId, Vec<Nomination>>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let rows = sqlx::query_as!(
+        Nomination,
+        "
+        SELECT
+            n.id,
+            n.voting_id,
+            n.name,
+            n.bio,
+            n.nominated_by,
+            n.state AS \"state: NominationState\",
+            n.created_at
+        FROM nomination AS n
+            INNER JOIN voting AS v ON v.id = n.voting_id AND v.state = 'nominating'
+        WHERE NOT $1 OR n.state = 'approved'
+        ORDER BY n.voting_id, n.created_at, n.id
+        ",
+        approved_only
+    )
+    .fetch_all(executor)
+    .await?;
+
+    let mut nominations: HashMap<VotingId, Vec<Nomination>> = HashMap::new();
+    rows.into_iter()
+        .for_each(|n| nominations.entry(n.voting_id).or_default().push(n));
+
+    Ok(nominations)
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use sqlx::{Executor, Postgres};
+
+use crate::{
+    api_types::ApiResult,
+    models::{PublicBallot, VotingId},
+};
+
+/// The named ballots of one public voting ordered by voter alias. Ballots are listed only once
+/// the voting is closed so that the roll-call cannot steer the votes still to be cast.
+pub async fn get_public_ballots<'e, E>(
+    executor: E,
+    voting_id: VotingId,
+) -> ApiResult<Vec<PublicBallot>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    Ok(
+        get_public_ballots_of_closed_votings(executor, Some(voting_id))
+            .await?
+            .remove(&voting_id)
+            .unwrap_or_default(),
+    )
+}
+
+/// The named ballots of every closed public voting, or of only one of them, keyed by voting.
+pub async fn get_public_ballots_of_closed_votings<'e, E>(
+    executor: E,
+    voting_id: Option<VotingId>,
+) -> ApiResult<HashMap<VotingId, Vec<PublicBallot>>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let rows = sqlx::query!(
+        "
+        SELECT
+            b.voting_id,
+            b.voter_alias,
+            COALESCE(
+                ARRAY_AGG(v.candidate_id ORDER BY v.rank) FILTER (WHERE v.candidate_id IS NOT NULL),
+                '{}'
+            ) AS \"candidates!\"
+        FROM ballot_voter AS b
+            INNER JOIN voting AS vt
+                ON vt.id = b.voting_id AND vt.state = 'closed' AND vt.is_public_ballot
+            LEFT JOIN vote AS v
+                ON v.voting_id = b.voting_id AND v.id = b.ballot_id
+        WHERE $1::int IS NULL OR b.voting_id = $1
+        GROUP BY b.voting_id, b.voter_alias
+        ORDER BY b.voting_id, b.voter_alias
+        ",
+        voting_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    let mut ballots: HashMap<VotingId, Vec<PublicBallot>> = HashMap::new();
+    rows.into_iter().for_each(|row| {
+        ballots
+            .entry(row.voting_id)
+            .or_default()
+            .push(PublicBallot {
+                voter_alias: row.voter_alias,
+                candidates: row.candidates,
+            })
+    });
+
+    Ok(ballots)
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use sqlx::{Executor, Postgres};
+
+use crate::{
+    api_types::ApiResult,
+    models::{VoterGroup, VoterGroupTokens, VotingId},
+};
+
+/// All voter groups in alphabetical order with the numbers of their tokens.
+pub async fn get_voter_groups<'e, E>(executor: E) -> ApiResult<Vec<VoterGroupTokens>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let groups = sqlx::query_as!(
+        VoterGroupTokens,
+        "
+        SELECT
+            g.id,
+            g.name,
+            ARRAY(
+                SELECT t.token_id FROM token_voter_group AS t
+                WHERE t.voter_group_id = g.id
+                ORDER BY t.token_id
+            ) AS \"token_ids!\"
+        FROM voter_group AS g
+        ORDER BY g.name
+        "
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(groups)
+}
+
+/// Eligible groups of one voting, see [`crate::models::Voting::eligible_groups`].
+pub async fn get_eligible_groups<'e, E>(
+    executor: E,
+    voting_id: VotingId,
+) -> ApiResult<Vec<VoterGroup>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let groups = sqlx::query_as!(
+        VoterGroup,
+        "
+        SELECT g.id, g.name
+        FROM voting_eligible_group AS e INNER JOIN voter_group AS g ON g.id = e.voter_group_id
+        WHERE e.voting_id = $1
+        ORDER BY g.name
+        ",
+        voting_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(groups)
+}
+
+/// Eligible groups of every voting that has any, keyed by voting.
+pub async fn get_eligible_groups_of_all_votings<'e, E>(
+    executor: E,
+) -> ApiResult<HashMap<VotingId, Vec<VoterGroup>>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let rows = sqlx::query!(
+        "
+        SELECT e.voting_id, g.id, g.name
+        FROM voting_eligible_group AS e INNER JOIN voter_group AS g ON g.id = e.voter_group_id
+        ORDER BY e.voting_id, g.name
+        "
+    )
+    .fetch_all(executor)
+    .await?;
+
+    let mut groups: HashMap<VotingId, Vec<VoterGroup>> = HashMap::new();
+    rows.into_iter().for_each(|row| {
+        groups.entry(row.voting_id).or_default().push(VoterGroup {
+            id: row.id,
+            name: row.name,
+        })
+    });
+
+    Ok(groups)
+}
@@ -0,0 +1,55 @@
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    api_types::ApiResult,
+    helpers::{get_candidates, get_eligible_groups},
+    models::{Voting, VotingId, VotingStateWithoutResults},
+};
+
+/// A voting with its candidates, without the results.
+pub async fn get_voting(db: &Pool<Postgres>, id: VotingId) -> ApiResult<Option<Voting>> {
+    let voting = sqlx::query!(
+        "
+        SELECT
+            id,
+            name,
+            description,
+            state AS \"state: VotingStateWithoutResults\",
+            created_at,
+            hide_vote_counts,
+            is_public_ballot,
+            allow_revote,
+            number_of_winners,
+            decimal_places,
+            agenda_position,
+            agenda_group
+        FROM voting
+        WHERE id = $1
+        ",
+        id
+    )
+    .fetch_optional(db)
+    .await?;
+
+    let voting = match voting {
+        Some(row) => Some(Voting {
+            id: row.id,
+            name: row.name,
+            description: row.description,
+            state: row.state.into(),
+            created_at: row.created_at,
+            hide_vote_counts: row.hide_vote_counts,
+            is_public_ballot: row.is_public_ballot,
+            allow_revote: row.allow_revote,
+            number_of_winners: row.number_of_winners,
+            decimal_places: row.decimal_places,
+            agenda_position: row.agenda_position,
+            agenda_group: row.agenda_group,
+            eligible_groups: get_eligible_groups(db, row.id).await?,
+            candidates: get_candidates(db, row.id).await?,
+        }),
+        None => None,
+    };
+
+    Ok(voting)
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    api_types::ApiResult,
+    models::{
+        CandidateResultData, NonTransferableWeight, PassingCandidateResult, ResultVersion,
+        VoteTransfer, VotingId, VotingResult, VotingResultVersion, VotingRoundResult,
+    },
+};
+
+/// All result versions of a voting, newest first.
+pub async fn get_voting_results(
+    db: &Pool<Postgres>,
+    voting_id: VotingId,
+) -> ApiResult<Vec<VotingResultVersion>> {
+    let versions = sqlx::query!(
+        "
+        SELECT version, created_at, number_of_winners, excluded_candidate_ids, admin_username, decimal_places
+        FROM voting_result
+        WHERE voting_id = $1
+        ORDER BY version DESC
+        ",
+        voting_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    // Candidates that are not passing on a round are the ones dropped on it
+    let candidate_rows = sqlx::query!(
+        "
+        SELECT
+            c.version,
+            c.round,
+            c.candidate_id,
+            c.vote_count,
+            c.is_draw,
+            p.is_selected AS \"is_selected?\"
+        FROM candidate_result_data AS c
+            LEFT JOIN passing_candidate_result AS p
+                ON p.voting_id = c.voting_id
+                AND p.version = c.version
+                AND p.round = c.round
+                AND p.candidate_id = c.candidate_id
+        WHERE c.voting_id = $1
+        ORDER BY c.version, c.round, c.vote_count DESC
+        ",
+        voting_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let round_rows = sqlx::query!(
+        "
+        SELECT version, round, exhausted_weight, lost_weight
+        FROM voting_round_result
+        WHERE voting_id = $1
+        ",
+        voting_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let transfer_rows = sqlx::query!(
+        "
+        SELECT version, round, from_candidate_id, to_candidate_id, weight
+        FROM vote_transfer
+        WHERE voting_id = $1
+        ORDER BY version, round, weight DESC
+        ",
+        voting_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut rounds: BTreeMap<(ResultVersion, i32), VotingRoundResult> = BTreeMap::new();
+    candidate_rows.into_iter().for_each(|row| {
+        let round = rounds
+            .entry((row.version, row.round))
+            .or_insert_with(|| VotingRoundResult {
+                round: row.round,
+                candidate_results: vec![],
+                dropped_candidate: None,
+                transfers: vec![],
+                non_transferable: NonTransferableWeight::default(),
+            });
+        let data = CandidateResultData {
+            candidate_id: row.candidate_id,
+            vote_count: row.vote_count,
+            is_draw: row.is_draw,
+        };
+
+        match row.is_selected {
+            Some(is_selected) => round
+                .candidate_results
+                .push(PassingCandidateResult { data, is_selected }),
+            None => round.dropped_candidate = Some(data),
+        }
+    });
+
+    round_rows.into_iter().for_each(|row| {
+        if let Some(round) = rounds.get_mut(&(row.version, row.round)) {
+            round.non_transferable = NonTransferableWeight {
+                exhausted: row.exhausted_weight,
+                lost: row.lost_weight,
+            };
+        }
+    });
+
+    transfer_rows.into_iter().for_each(|row| {
+        if let Some(round) = rounds.get_mut(&(row.version, row.round)) {
+            round.transfers.push(VoteTransfer {
+                from_candidate_id: row.from_candidate_id,
+                to_candidate_id: row.to_candidate_id,
+                weight: row.weight,
+            });
+        }
+    });
+
+    Ok(versions
+        .into_iter()
+        .map(|v| {
+            let round_results = rounds
+                .range((v.version, i32::MIN)..=(v.version, i32::MAX))
+                .map(|(_, round)| round.clone())
+                .collect::<Vec<_>>();
+            let winners = round_results
+                .iter()
+                .flat_map(|r| r.candidate_results.iter().filter(|c| c.is_selected))
+                .map(|c| c.data.candidate_id)
+                .collect();
+
+            VotingResultVersion {
+                version: v.version,
+                created_at: v.created_at,
+                number_of_winners: v.number_of_winners,
+                excluded_candidate_ids: v.excluded_candidate_ids,
+                admin_username: v.admin_username,
+                result: VotingResult {
+                    round_results,
+                    winners,
+                    decimal_places: v.decimal_places,
+                },
+            }
+        })
+        .collect())
+}
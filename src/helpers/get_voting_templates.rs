@@ -0,0 +1,32 @@
+use sqlx::{Executor, Postgres};
+
+use crate::{api_types::ApiResult, models::VotingTemplate};
+
+/// All voting templates in alphabetical order.
+pub async fn get_voting_templates<'e, E>(executor: E) -> ApiResult<Vec<VotingTemplate>>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let templates = sqlx::query_as!(
+        VotingTemplate,
+        "
+        SELECT
+            id,
+            name,
+            description,
+            hide_vote_counts,
+            is_public_ballot,
+            allow_revote,
+            number_of_winners,
+            decimal_places,
+            include_ron,
+            created_at
+        FROM voting_template
+        ORDER BY name
+        "
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(templates)
+}
@@ -0,0 +1,22 @@
+use tracing_subscriber::{filter::ParseError, EnvFilter};
+
+use crate::config::{Config, LogFormat};
+
+/// Sets up the global log subscriber. Logs go to stdout as text or as one JSON object per line.
+pub fn init_tracing(config: &Config) -> Result<(), ParseError> {
+    let filter = EnvFilter::try_new(&config.log_level)?;
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+
+    match config.log_format {
+        LogFormat::Text => builder.init(),
+        // The fields of the request span are flattened into each line
+        LogFormat::Json => builder
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .init(),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::{Executor, Postgres};
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    models::{AdminAccount, AdminAction},
+};
+
+/// Appends an entry to the admin event log.
+///
+/// When both `before` and `after` are given, only the top-level fields that differ are stored.
+/// Pass the transaction of the logged action as the executor so that the event is only
+/// recorded if the action is committed.
+pub async fn log_admin_event<'e, E, B, A>(
+    executor: E,
+    admin: &AdminAccount,
+    action: AdminAction,
+    target: Option<String>,
+    before: Option<&B>,
+    after: Option<&A>,
+) -> ApiResult<()>
+where
+    E: Executor<'e, Database = Postgres>,
+    B: Serialize,
+    A: Serialize,
+{
+    let before = before.map(to_value).transpose()?;
+    let after = after.map(to_value).transpose()?;
+
+    let (before, after) = match (before, after) {
+        (Some(Value::Object(before)), Some(Value::Object(after))) => {
+            let (before, after) = diff_objects(before, after);
+            (Some(Value::Object(before)), Some(Value::Object(after)))
+        }
+        other => other,
+    };
+
+    sqlx::query!(
+        "
+        INSERT INTO admin_event (created_at, admin_id, admin_username, action, target, before, after)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ",
+        Utc::now(),
+        admin.id,
+        admin.username,
+        action as AdminAction,
+        target,
+        before,
+        after,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+fn to_value<T: Serialize>(value: &T) -> ApiResult<Value> {
+    serde_json::to_value(value).map_err(|_| ApiError::InternalServerError)
+}
+
+fn diff_objects(
+    mut before: Map<String, Value>,
+    mut after: Map<String, Value>,
+) -> (Map<String, Value>, Map<String, Value>) {
+    let unchanged_keys = before
+        .iter()
+        .filter(|(key, value)| after.get(*key) == Some(value))
+        .map(|(key, _)| key.clone())
+        .collect::<Vec<_>>();
+
+    unchanged_keys.iter().for_each(|key| {
+        before.remove(key);
+        after.remove(key);
+    });
+
+    (before, after)
+}
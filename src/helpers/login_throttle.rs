@@ -0,0 +1,261 @@
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    api_types::{ApiError, ApiResult, AuthFailedError},
+    config::Config,
+    metrics::metrics,
+    models::LoginKind,
+};
+
+/// Rejects the login attempt while the client address or the attempted target is locked out.
+/// This runs before the credentials are checked, so a locked target cannot be guessed from
+/// other addresses and a correct password does not get through a lockout either. Attempts
+/// rejected here are not recorded, so they do not extend the lockout.
+pub async fn ensure_login_allowed(
+    db: &Pool<Postgres>,
+    config: &Config,
+    kind: LoginKind,
+    ip: &str,
+    target: &str,
+) -> ApiResult<()> {
+    let now = Utc::now();
+    let since = failure_window_start(config, now);
+
+    let ip_failures = sqlx::query!(
+        "
+        SELECT count(*) AS \"failures!\", max(created_at) AS last_failure_at
+        FROM login_failure
+        WHERE kind = $1 AND ip = $2 AND created_at > $3
+        ",
+        kind as LoginKind,
+        ip,
+        since,
+    )
+    .fetch_one(db)
+    .await?;
+
+    let target_failures = sqlx::query!(
+        "
+        SELECT count(*) AS \"failures!\", max(created_at) AS last_failure_at
+        FROM login_failure
+        WHERE kind = $1 AND target = $2 AND created_at > $3
+        ",
+        kind as LoginKind,
+        login_failure_target(config, kind, target),
+        since,
+    )
+    .fetch_one(db)
+    .await?;
+
+    let ip_locked_until = ip_failures.last_failure_at.and_then(|last_failure_at| {
+        locked_until(
+            config,
+            ip_failures.failures,
+            last_failure_at,
+            config.login_max_failures_per_ip,
+        )
+    });
+    let target_locked_until = target_failures.last_failure_at.and_then(|last_failure_at| {
+        locked_until(
+            config,
+            target_failures.failures,
+            last_failure_at,
+            config.login_max_failures_per_target,
+        )
+    });
+
+    if ip_locked_until
+        .into_iter()
+        .chain(target_locked_until)
+        .any(|locked_until| locked_until > now)
+    {
+        return Err(ApiError::AuthFailed(AuthFailedError::TooManyAttempts));
+    }
+
+    Ok(())
+}
+
+/// Records a login attempt with wrong credentials. Failures that have fallen out of the
+/// failure window no longer count towards any lockout, so they are deleted at the same time.
+pub async fn record_login_failure(
+    db: &Pool<Postgres>,
+    config: &Config,
+    kind: LoginKind,
+    ip: &str,
+    target: &str,
+) -> ApiResult<()> {
+    let kind_label = match kind {
+        LoginKind::Voter => "voter",
+        LoginKind::Admin => "admin",
+    };
+    metrics()
+        .login_failures
+        .with_label_values(&[kind_label])
+        .inc();
+
+    let now = Utc::now();
+
+    sqlx::query!(
+        "DELETE FROM login_failure WHERE created_at <= $1",
+        failure_window_start(config, now),
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query!(
+        "
+        INSERT INTO login_failure (created_at, kind, ip, target)
+        VALUES ($1, $2, $3, $4)
+        ",
+        now,
+        kind as LoginKind,
+        ip,
+        login_failure_target(config, kind, target),
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Forgets the failures against a target after a successful login. Failures from the client
+/// address are kept so that one valid credential cannot be used to reset the address limit.
+pub async fn clear_login_failures(
+    db: &Pool<Postgres>,
+    config: &Config,
+    kind: LoginKind,
+    target: &str,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "DELETE FROM login_failure WHERE kind = $1 AND target = $2",
+        kind as LoginKind,
+        login_failure_target(config, kind, target),
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Voter tokens are stored as a keyed hash, so the failure log does not hold attempted codes
+/// that are one typo away from a working one. Usernames are stored as such.
+fn login_failure_target(config: &Config, kind: LoginKind, target: &str) -> String {
+    match kind {
+        LoginKind::Admin => target.to_string(),
+        LoginKind::Voter => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(config.hmac_key.as_bytes())
+                .expect("HMAC accepts keys of any length");
+            mac.update(format!("login-failure:{target}").as_bytes());
+
+            mac.finalize()
+                .into_bytes()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect()
+        }
+    }
+}
+
+pub fn failure_window_start(config: &Config, now: DateTime<Utc>) -> DateTime<Utc> {
+    now - Duration::minutes(config.login_failure_window_minutes)
+}
+
+/// Exponential backoff: the first failure over the limit locks for the base duration and
+/// every further failure doubles it, up to the configured maximum.
+pub fn locked_until(
+    config: &Config,
+    failures: i64,
+    last_failure_at: DateTime<Utc>,
+    max_failures: i64,
+) -> Option<DateTime<Utc>> {
+    if failures < max_failures {
+        return None;
+    }
+
+    let exponent = (failures - max_failures).min(32) as u32;
+    let lockout_seconds = config
+        .login_backoff_base_seconds
+        .saturating_mul(2_i64.saturating_pow(exponent))
+        .min(config.login_lockout_max_seconds);
+
+    Some(last_failure_at + Duration::seconds(lockout_seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+    use sqlx::{Pool, Postgres};
+
+    use super::*;
+    use crate::{
+        helpers::create_initial_admin,
+        http::test_client::{test_config, TestClient, ADMIN_PASSWORD, ADMIN_USERNAME},
+    };
+
+    #[test]
+    fn test_backoff_starts_at_the_failure_limit() {
+        let config = test_config();
+        let now = Utc::now();
+        let lockout = |failures| locked_until(&config, failures, now, 5).map(|t| t - now);
+
+        assert_eq!(lockout(4), None);
+        assert_eq!(lockout(5), Some(Duration::seconds(30)));
+        assert_eq!(lockout(6), Some(Duration::seconds(60)));
+        assert_eq!(lockout(7), Some(Duration::seconds(120)));
+    }
+
+    #[test]
+    fn test_lockout_is_capped() {
+        let config = test_config();
+        let now = Utc::now();
+
+        assert_eq!(
+            locked_until(&config, 1000, now, 5),
+            Some(now + Duration::seconds(3600))
+        );
+    }
+
+    async fn admin_login(client: &mut TestClient, username: &str, password: &str) -> StatusCode {
+        client
+            .send(
+                Method::POST,
+                "/login",
+                Some(json!({ "username": username, "password": password })),
+            )
+            .await
+            .0
+    }
+
+    #[sqlx::test]
+    async fn test_locked_target_rejects_the_correct_password(db: Pool<Postgres>) {
+        let mut client = TestClient::new(db);
+        create_initial_admin(&client.state.db, &client.state.config)
+            .await
+            .unwrap();
+
+        for _ in 0..5 {
+            let status = admin_login(&mut client, ADMIN_USERNAME, "wrong").await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+
+        let status = admin_login(&mut client, ADMIN_USERNAME, ADMIN_PASSWORD).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[sqlx::test]
+    async fn test_address_is_locked_after_failures_against_many_targets(db: Pool<Postgres>) {
+        let mut client = TestClient::new(db);
+
+        for i in 0..20 {
+            let status = admin_login(&mut client, &format!("user{i}"), "wrong").await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+
+        let status = admin_login(&mut client, "another", "wrong").await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+}
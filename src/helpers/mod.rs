@@ -1,4 +1,41 @@
+mod ballot_pseudonym;
 mod calculate_stv_result;
+mod create_initial_admin;
 mod create_pg_pool;
-pub use calculate_stv_result::calculate_stv_result;
+mod get_candidates;
+mod get_nominations;
+mod get_public_ballots;
+mod get_voter_groups;
+mod get_voting;
+mod get_voting_results;
+mod get_voting_templates;
+mod init_tracing;
+mod log_admin_event;
+mod login_throttle;
+mod password;
+mod prepare_database;
+mod store_voting_result;
+mod vote_transfer_chart;
+pub use ballot_pseudonym::ballot_pseudonym;
+pub use calculate_stv_result::{calculate_stv_result, exclude_candidates};
+pub use create_initial_admin::create_initial_admin;
 pub use create_pg_pool::create_pg_pool;
+pub use get_candidates::{get_candidates, get_candidates_of_all_votings};
+pub use get_nominations::get_nominations_of_nominating_votings;
+pub use get_public_ballots::{get_public_ballots, get_public_ballots_of_closed_votings};
+pub use get_voter_groups::{
+    get_eligible_groups, get_eligible_groups_of_all_votings, get_voter_groups,
+};
+pub use get_voting::get_voting;
+pub use get_voting_results::get_voting_results;
+pub use get_voting_templates::get_voting_templates;
+pub use init_tracing::init_tracing;
+pub use log_admin_event::log_admin_event;
+pub use login_throttle::{
+    clear_login_failures, ensure_login_allowed, failure_window_start, locked_until,
+    record_login_failure,
+};
+pub use password::{dummy_password_hash, hash_password, verify_password};
+pub use prepare_database::{pending_migrations, prepare_database};
+pub use store_voting_result::store_voting_result;
+pub use vote_transfer_chart::{vote_transfer_chart, VoteTransferChart};
@@ -0,0 +1,50 @@
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use rand::rngs::OsRng;
+use std::sync::OnceLock;
+
+use crate::api_types::{ApiError, ApiResult};
+
+/// Argon2 is deliberately slow, so hashing runs on the blocking thread pool instead of holding
+/// up the request handlers that share the async worker threads.
+pub async fn hash_password(password: String) -> ApiResult<String> {
+    tokio::task::spawn_blocking(move || hash_password_blocking(&password))
+        .await
+        .map_err(|_| ApiError::InternalServerError)?
+}
+
+/// Verifies on the blocking thread pool, see [`hash_password`].
+pub async fn verify_password(password: String, password_hash: String) -> ApiResult<bool> {
+    tokio::task::spawn_blocking(move || verify_password_blocking(&password, &password_hash))
+        .await
+        .map_err(|_| ApiError::InternalServerError)
+}
+
+fn hash_password_blocking(password: &str) -> ApiResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|_| ApiError::InternalServerError)
+}
+
+fn verify_password_blocking(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// Hash to verify against when the username does not exist, so that a login takes as long
+/// whether or not the username is taken.
+pub fn dummy_password_hash() -> &'static str {
+    static DUMMY_PASSWORD_HASH: OnceLock<String> = OnceLock::new();
+    DUMMY_PASSWORD_HASH.get_or_init(|| {
+        hash_password_blocking("dummy password").expect("Hashing the dummy password failed")
+    })
+}
@@ -0,0 +1,66 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use sqlx::{migrate::Migrator, Pool, Postgres};
+
+use crate::{config::Config, helpers::create_initial_admin};
+
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+const UNDEFINED_TABLE: &str = "42P01";
+
+/// Runs the migrations and creates the initial admin, retrying until the database is reachable.
+/// Sets `ready` once done.
+pub async fn prepare_database(db: Pool<Postgres>, config: Arc<Config>, ready: Arc<AtomicBool>) {
+    loop {
+        let result = match MIGRATOR.run(&db).await {
+            Ok(()) => create_initial_admin(&db, &config)
+                .await
+                .map_err(|e| format!("{e:?}")),
+            Err(e) => Err(e.to_string()),
+        };
+
+        match result {
+            Ok(()) => {
+                ready.store(true, Ordering::Release);
+                tracing::info!("database is ready");
+                return;
+            }
+            Err(error) => {
+                tracing::error!(%error, "preparing the database failed, retrying");
+                tokio::time::sleep(RETRY_INTERVAL).await;
+            }
+        }
+    }
+}
+
+/// Versions of the migrations that have not been applied successfully.
+pub async fn pending_migrations(db: &Pool<Postgres>) -> Result<Vec<i64>, sqlx::Error> {
+    let applied = sqlx::query_scalar!("SELECT version FROM _sqlx_migrations WHERE success")
+        .fetch_all(db)
+        .await
+        .or_else(|e| match e {
+            // The migrations table is created by the first migration run
+            sqlx::Error::Database(err) if err.code().as_deref() == Some(UNDEFINED_TABLE) => {
+                Ok(vec![])
+            }
+            e => Err(e),
+        })?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| m.version)
+        .filter(|version| !applied.contains(version))
+        .collect())
+}
@@ -0,0 +1,136 @@
+use chrono::Utc;
+use sqlx::{Postgres, QueryBuilder, Transaction};
+
+use crate::{
+    api_types::ApiResult,
+    models::{AdminAccount, CandidateId, ResultVersion, VotingId, VotingResult},
+};
+
+/// Stores a count of a voting as its next result version and returns the version number.
+pub async fn store_voting_result(
+    tx: &mut Transaction<'_, Postgres>,
+    voting_id: VotingId,
+    number_of_winners: i32,
+    excluded_candidate_ids: &[CandidateId],
+    admin: &AdminAccount,
+    result: &VotingResult,
+) -> ApiResult<ResultVersion> {
+    let version = sqlx::query!(
+        "
+        INSERT INTO voting_result (voting_id, version, created_at, number_of_winners, excluded_candidate_ids, admin_username, decimal_places)
+        SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3, $4, $5, $6
+        FROM voting_result
+        WHERE voting_id = $1
+        RETURNING version
+        ",
+        voting_id,
+        Utc::now(),
+        number_of_winners,
+        excluded_candidate_ids,
+        admin.username,
+        result.decimal_places,
+    )
+    .fetch_one(&mut **tx)
+    .await?
+    .version;
+
+    let round_results = &result.round_results;
+
+    let mut winning_candidates = vec![];
+    let mut passing_candidates = vec![];
+    let mut dropped_candidates = vec![];
+
+    round_results.iter().for_each(|r| {
+        r.candidate_results.iter().for_each(|c| {
+            if c.is_selected {
+                winning_candidates.push((&c.data, r.round));
+            } else {
+                passing_candidates.push((&c.data, r.round));
+            }
+        });
+
+        r.dropped_candidate.iter().for_each(|c| {
+            dropped_candidates.push((c, r.round));
+        });
+    });
+
+    QueryBuilder::new(
+        "INSERT INTO voting_round_result (voting_id, version, round, exhausted_weight, lost_weight)",
+    )
+    .push_values(round_results, |mut b, res| {
+        b.push_bind(voting_id)
+            .push_bind(version)
+            .push_bind(res.round)
+            .push_bind(res.non_transferable.exhausted)
+            .push_bind(res.non_transferable.lost);
+    })
+    .build()
+    .execute(&mut **tx)
+    .await?;
+
+    let all_candidate_data = [
+        winning_candidates.clone(),
+        passing_candidates.clone(),
+        dropped_candidates,
+    ]
+    .concat();
+
+    QueryBuilder::new(
+        "INSERT INTO candidate_result_data (candidate_id, round, voting_id, version, vote_count, is_draw)",
+    )
+    .push_values(all_candidate_data, |mut b, (result, round)| {
+        b.push_bind(result.candidate_id)
+            .push_bind(round)
+            .push_bind(voting_id)
+            .push_bind(version)
+            .push_bind(result.vote_count)
+            .push_bind(result.is_draw);
+    })
+    .build()
+    .execute(&mut **tx)
+    .await?;
+
+    for (candidates, is_selected) in [(winning_candidates, true), (passing_candidates, false)] {
+        if candidates.is_empty() {
+            continue;
+        }
+
+        QueryBuilder::new(
+            "INSERT INTO passing_candidate_result (candidate_id, round, voting_id, version, is_selected)",
+        )
+        .push_values(candidates, |mut b, (result, round)| {
+            b.push_bind(result.candidate_id)
+                .push_bind(round)
+                .push_bind(voting_id)
+                .push_bind(version)
+                .push_bind(is_selected);
+        })
+        .build()
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    let transfers = round_results
+        .iter()
+        .flat_map(|r| r.transfers.iter().map(move |t| (t, r.round)))
+        .collect::<Vec<_>>();
+
+    if !transfers.is_empty() {
+        QueryBuilder::new(
+            "INSERT INTO vote_transfer (voting_id, version, round, from_candidate_id, to_candidate_id, weight)",
+        )
+        .push_values(transfers, |mut b, (transfer, round)| {
+            b.push_bind(voting_id)
+                .push_bind(version)
+                .push_bind(round)
+                .push_bind(transfer.from_candidate_id)
+                .push_bind(transfer.to_candidate_id)
+                .push_bind(transfer.weight);
+        })
+        .build()
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(version)
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use crate::{
+    i18n::Locale,
+    models::{CandidateId, Voting, VotingResult},
+};
+
+const MARGIN: f64 = 24.0;
+const COLUMN_LABEL_HEIGHT: f64 = 24.0;
+const COLUMN_SPACING: f64 = 180.0;
+const LABEL_WIDTH: f64 = 140.0;
+const NODE_WIDTH: f64 = 12.0;
+const NODE_GAP: f64 = 16.0;
+const PLOT_HEIGHT: f64 = 320.0;
+
+/// A candidate of a column, `None` collects the exhausted ballots.
+type NodeKey = Option<CandidateId>;
+
+/// Layout of a flow chart of the vote transfers of a count, rendered as an SVG on the results
+/// page. Every round is a column, the height of a node is the vote count of the candidate on
+/// that round and the links show how the votes moved to the next round.
+pub struct VoteTransferChart {
+    pub width: f64,
+    pub height: f64,
+    pub columns: Vec<ChartColumn>,
+    pub nodes: Vec<ChartNode>,
+    pub links: Vec<ChartLink>,
+}
+
+pub struct ChartColumn {
+    pub x: f64,
+    pub y: f64,
+    pub label: String,
+}
+
+pub struct ChartNode {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub label: String,
+    pub title: String,
+    pub class: &'static str,
+}
+
+pub struct ChartLink {
+    pub path: String,
+    pub width: f64,
+    pub title: String,
+}
+
+struct Column {
+    label: String,
+    nodes: Vec<(NodeKey, f64, &'static str)>,
+}
+
+/// Lays out the transfer chart of a count. Returns `None` when no votes moved between rounds.
+pub fn vote_transfer_chart(
+    voting: &Voting,
+    result: &VotingResult,
+    locale: Locale,
+) -> Option<VoteTransferChart> {
+    let rounds = &result.round_results;
+    let mut columns = vec![];
+    let mut exhausted = 0.0;
+
+    for round in rounds {
+        let mut nodes = round
+            .candidate_results
+            .iter()
+            .map(|c| {
+                let class = if c.is_selected {
+                    "elected"
+                } else {
+                    "continuing"
+                };
+                (Some(c.data.candidate_id), c.data.vote_count, class)
+            })
+            .collect::<Vec<_>>();
+        if let Some(dropped) = &round.dropped_candidate {
+            nodes.push((Some(dropped.candidate_id), dropped.vote_count, "dropped"));
+        }
+        if exhausted > 0.0 {
+            nodes.push((None, exhausted, "exhausted"));
+        }
+
+        columns.push(Column {
+            label: locale.t1("chart.round", round.round),
+            nodes,
+        });
+        exhausted += round
+            .transfers
+            .iter()
+            .filter(|t| t.to_candidate_id.is_none())
+            .map(|t| t.weight)
+            .sum::<f64>();
+    }
+
+    // Votes transferred on the last round have no next round to go to, so they get a column
+    // of their own
+    if let Some(last) = rounds.last().filter(|r| !r.transfers.is_empty()) {
+        let mut nodes = last
+            .candidate_results
+            .iter()
+            .filter(|c| !c.is_selected)
+            .map(|c| {
+                let received = last
+                    .transfers
+                    .iter()
+                    .filter(|t| t.to_candidate_id == Some(c.data.candidate_id))
+                    .map(|t| t.weight)
+                    .sum::<f64>();
+                (
+                    Some(c.data.candidate_id),
+                    c.data.vote_count + received,
+                    "continuing",
+                )
+            })
+            .collect::<Vec<_>>();
+        if exhausted > 0.0 {
+            nodes.push((None, exhausted, "exhausted"));
+        }
+
+        columns.push(Column {
+            label: locale.t("chart.final").to_string(),
+            nodes,
+        });
+    }
+
+    // Source column, source node, target node and weight of every link
+    let mut flows: Vec<(usize, usize, usize, f64)> = vec![];
+    for (i, round) in rounds.iter().enumerate() {
+        let Some(next) = columns.get(i + 1) else {
+            break;
+        };
+        let position = |key: NodeKey| next.nodes.iter().position(|(k, _, _)| *k == key);
+
+        columns[i]
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, weight, class))| {
+                *weight > 0.0 && matches!(*class, "continuing" | "exhausted")
+            })
+            .for_each(|(source, (key, weight, _))| {
+                if let Some(target) = position(*key) {
+                    flows.push((i, source, target, *weight));
+                }
+            });
+
+        round.transfers.iter().for_each(|t| {
+            let source = columns[i]
+                .nodes
+                .iter()
+                .position(|(k, _, _)| *k == Some(t.from_candidate_id));
+            if let Some((source, target)) = source.zip(position(t.to_candidate_id)) {
+                flows.push((i, source, target, t.weight));
+            }
+        });
+    }
+
+    if flows.is_empty() {
+        return None;
+    }
+
+    let max_node_count = columns.iter().map(|c| c.nodes.len()).max().unwrap_or(0);
+    let max_total = columns
+        .iter()
+        .map(|c| c.nodes.iter().map(|(_, value, _)| value).sum::<f64>())
+        .fold(0.0, f64::max);
+    if max_total <= 0.0 {
+        return None;
+    }
+    let scale = (PLOT_HEIGHT - NODE_GAP * (max_node_count as f64 - 1.0)) / max_total;
+
+    let name = |key: &NodeKey| match key {
+        Some(id) => voting.candidate_name(id, &locale),
+        None => locale.t("chart.exhausted").to_string(),
+    };
+
+    // Top edge of every node, indexed by column and node
+    let mut node_tops: Vec<Vec<f64>> = vec![];
+    let mut nodes = vec![];
+    let mut chart_columns = vec![];
+    for (i, column) in columns.iter().enumerate() {
+        let x = MARGIN + i as f64 * COLUMN_SPACING;
+        let mut y = MARGIN + COLUMN_LABEL_HEIGHT;
+        let mut tops = vec![];
+
+        chart_columns.push(ChartColumn {
+            x: px(x),
+            y: px(MARGIN),
+            label: column.label.clone(),
+        });
+
+        column.nodes.iter().for_each(|(key, value, class)| {
+            let height = (value * scale).max(1.0);
+            nodes.push(ChartNode {
+                x: px(x),
+                y: px(y),
+                width: NODE_WIDTH,
+                height: px(height),
+                label: name(key),
+                title: format!("{}: {:.4}", name(key), value),
+                class,
+            });
+            tops.push(y);
+            y += height + NODE_GAP;
+        });
+        node_tops.push(tops);
+    }
+
+    flows.sort_by_key(|(column, source, target, _)| (*column, *source, *target));
+
+    let mut outgoing: HashMap<(usize, usize), f64> = HashMap::new();
+    let mut incoming: HashMap<(usize, usize), f64> = HashMap::new();
+    let links = flows
+        .into_iter()
+        .map(|(i, source, target, weight)| {
+            let width = weight * scale;
+            let out_offset = outgoing.entry((i, source)).or_default();
+            let y0 = node_tops[i][source] + *out_offset + width / 2.0;
+            *out_offset += width;
+            let in_offset = incoming.entry((i + 1, target)).or_default();
+            let y1 = node_tops[i + 1][target] + *in_offset + width / 2.0;
+            *in_offset += width;
+
+            let x0 = MARGIN + i as f64 * COLUMN_SPACING + NODE_WIDTH;
+            let x1 = x0 + COLUMN_SPACING - NODE_WIDTH;
+            let middle = (x0 + x1) / 2.0;
+
+            let from = &columns[i].nodes[source].0;
+            let to = &columns[i + 1].nodes[target].0;
+            let title = if from == to {
+                format!("{}: {:.4}", name(from), weight)
+            } else {
+                format!("{} → {}: {:.4}", name(from), name(to), weight)
+            };
+
+            ChartLink {
+                path: format!(
+                    "M{:.1},{:.1} C{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}",
+                    x0, y0, middle, y0, middle, y1, x1, y1
+                ),
+                width: px(width.max(0.5)),
+                title,
+            }
+        })
+        .collect();
+
+    let height = node_tops
+        .iter()
+        .zip(&columns)
+        .filter_map(|(tops, column)| {
+            let (_, value, _) = column.nodes.last()?;
+            Some(tops.last()? + (value * scale).max(1.0))
+        })
+        .fold(0.0, f64::max)
+        + MARGIN;
+
+    Some(VoteTransferChart {
+        width: px(2.0 * MARGIN
+            + (columns.len() as f64 - 1.0) * COLUMN_SPACING
+            + NODE_WIDTH
+            + LABEL_WIDTH),
+        height: px(height),
+        columns: chart_columns,
+        nodes,
+        links,
+    })
+}
+
+/// Rounds a coordinate to a tenth of a pixel to keep the markup short.
+fn px(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
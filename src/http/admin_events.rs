@@ -0,0 +1,111 @@
+use askama::Template;
+use axum::{
+    debug_handler,
+    extract::State,
+    http::header,
+    middleware::from_fn,
+    response::{Html, IntoResponse},
+    routing::get,
+    Router,
+};
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    ctx::Ctx,
+    http::AppState,
+    i18n::Locale,
+    middleware::require_is_admin::require_is_admin,
+    models::{AdminAction, AdminEvent, LoginState},
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_admin_events_page))
+        .route("/export", get(export_admin_events))
+        .route_layer(from_fn(require_is_admin))
+}
+
+#[derive(Template)]
+#[template(path = "pages/admin-events.html")]
+struct AdminEventsTemplate {
+    login_state: LoginState,
+    locale: Locale,
+    events: Vec<AdminEvent>,
+}
+
+async fn get_admin_events(state: &AppState) -> ApiResult<Vec<AdminEvent>> {
+    sqlx::query_as!(
+        AdminEvent,
+        "
+        SELECT
+            id,
+            created_at,
+            admin_id,
+            admin_username,
+            action AS \"action: AdminAction\",
+            target,
+            before,
+            after
+        FROM admin_event
+        ORDER BY id DESC
+        "
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| e.into())
+}
+
+#[debug_handler]
+async fn get_admin_events_page(ctx: Ctx, state: State<AppState>) -> ApiResult<Html<String>> {
+    let events = get_admin_events(&state).await?;
+
+    AdminEventsTemplate {
+        login_state: ctx.login_state(),
+        locale: ctx.locale(),
+        events,
+    }
+    .render()
+    .map(Html)
+    .map_err(ApiError::TemplatingError)
+}
+
+#[debug_handler]
+async fn export_admin_events(state: State<AppState>) -> ApiResult<impl IntoResponse> {
+    let events = get_admin_events(&state).await?;
+
+    let header_row = "id,created_at,admin_id,admin_username,action,target,before,after\n";
+    let rows = events.iter().map(|e| {
+        let fields = [
+            e.id.to_string(),
+            e.created_at.to_rfc3339(),
+            e.admin_id.to_string(),
+            e.admin_username.clone(),
+            e.action.to_string(),
+            e.target.clone().unwrap_or_default(),
+            e.before.as_ref().map(|v| v.to_string()).unwrap_or_default(),
+            e.after.as_ref().map(|v| v.to_string()).unwrap_or_default(),
+        ];
+
+        fields
+            .iter()
+            .map(|f| format!("\"{}\"", f.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(",")
+            + "\n"
+    });
+
+    let csv = std::iter::once(header_row.to_string())
+        .chain(rows)
+        .collect::<String>();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"admin-events.csv\"",
+            ),
+        ],
+        csv,
+    ))
+}
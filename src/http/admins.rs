@@ -0,0 +1,311 @@
+use askama::Template;
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    middleware::from_fn,
+    response::Html,
+    routing::{delete, get, patch},
+    Json, Router,
+};
+use chrono::Utc;
+use serde_json::json;
+use sqlx::{error::ErrorKind, Postgres, Transaction};
+use validator::Validate;
+
+use crate::{
+    api_types::{ApiError, ApiResult, InvalidAdminAccountError},
+    ctx::Ctx,
+    helpers::{hash_password, log_admin_event},
+    http::AppState,
+    i18n::Locale,
+    middleware::require_is_superadmin::require_is_superadmin,
+    models::{
+        AdminAccount, AdminAccountCreate, AdminAccountUpdate, AdminAction, AdminId, AdminRole,
+        LoginState,
+    },
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_admins_page).post(post_admin))
+        .route("/:id", patch(patch_admin).delete(delete_admin))
+        .route("/sessions", delete(revoke_all_admin_sessions))
+        .route_layer(from_fn(require_is_superadmin))
+}
+
+#[derive(Template)]
+#[template(path = "pages/admin-accounts.html")]
+struct AdminAccountsTemplate {
+    login_state: LoginState,
+    locale: Locale,
+    admins: Vec<AdminAccount>,
+}
+
+#[debug_handler]
+async fn get_admins_page(ctx: Ctx, state: State<AppState>) -> ApiResult<Html<String>> {
+    let admins = sqlx::query_as!(
+        AdminAccount,
+        "
+        SELECT
+            id,
+            username,
+            role AS \"role: AdminRole\"
+        FROM admin_account
+        ORDER BY id
+        "
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    AdminAccountsTemplate {
+        login_state: ctx.login_state(),
+        locale: ctx.locale(),
+        admins,
+    }
+    .render()
+    .map(Html)
+    .map_err(ApiError::TemplatingError)
+}
+
+#[debug_handler]
+async fn post_admin(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Json(admin_create): Json<AdminAccountCreate>,
+) -> ApiResult<Json<AdminAccount>> {
+    admin_create
+        .validate()
+        .map_err(|_| ApiError::InvalidInput)?;
+
+    let password_hash = hash_password(admin_create.password).await?;
+
+    let mut tx = state.db.begin().await?;
+
+    let created = sqlx::query_as!(
+        AdminAccount,
+        "
+        INSERT INTO admin_account (username, password_hash, role, created_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING
+            id,
+            username,
+            role AS \"role: AdminRole\"
+        ",
+        admin_create.username,
+        password_hash,
+        admin_create.role as AdminRole,
+        Utc::now(),
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(err) if err.kind() == ErrorKind::UniqueViolation => {
+            ApiError::InvalidAdminAccount(InvalidAdminAccountError::UsernameAlreadyInUse)
+        }
+        _ => e.into(),
+    })?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::CreateAdmin,
+        Some(format!("admin:{}", created.id)),
+        None::<&()>,
+        Some(&created),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(created))
+}
+
+#[debug_handler]
+async fn patch_admin(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Path(id): Path<AdminId>,
+    Json(admin_update): Json<AdminAccountUpdate>,
+) -> ApiResult<Json<AdminAccount>> {
+    admin_update
+        .validate()
+        .map_err(|_| ApiError::InvalidInput)?;
+
+    let password_hash = match admin_update.password {
+        Some(password) => Some(hash_password(password).await?),
+        None => None,
+    };
+
+    let mut tx = state.db.begin().await?;
+
+    if admin_update
+        .role
+        .is_some_and(|role| role != AdminRole::Superadmin)
+    {
+        ensure_other_superadmin_exists(&mut tx, id).await?;
+    }
+
+    let before = sqlx::query!(
+        "SELECT role AS \"role: AdminRole\" FROM admin_account WHERE id = $1 FOR UPDATE",
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::InvalidInput)?;
+
+    let updated = sqlx::query_as!(
+        AdminAccount,
+        "
+        UPDATE admin_account
+        SET
+            password_hash = COALESCE($2, password_hash),
+            role = COALESCE($3, role)
+        WHERE id = $1
+        RETURNING
+            id,
+            username,
+            role AS \"role: AdminRole\"
+        ",
+        id,
+        password_hash,
+        admin_update.role as Option<AdminRole>,
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::InvalidInput)?;
+
+    // A changed password logs the account out everywhere
+    if password_hash.is_some() {
+        sqlx::query!(
+            "
+            UPDATE admin_session
+            SET revoked_at = $2
+            WHERE admin_id = $1 AND revoked_at IS NULL
+            ",
+            id,
+            Utc::now(),
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // The password hash is not logged, only the fact that the password was changed
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::UpdateAdmin,
+        Some(format!("admin:{id}")),
+        Some(&json!({ "role": before.role })),
+        Some(&json!({
+            "role": updated.role,
+            "passwordChanged": password_hash.is_some(),
+        })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+#[debug_handler]
+async fn delete_admin(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Path(id): Path<AdminId>,
+) -> ApiResult<()> {
+    let mut tx = state.db.begin().await?;
+
+    ensure_other_superadmin_exists(&mut tx, id).await?;
+
+    let deleted = sqlx::query_as!(
+        AdminAccount,
+        "
+        DELETE FROM admin_account
+        WHERE id = $1
+        RETURNING
+            id,
+            username,
+            role AS \"role: AdminRole\"
+        ",
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::InvalidInput)?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::DeleteAdmin,
+        Some(format!("admin:{id}")),
+        Some(&deleted),
+        None::<&()>,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Logs out every admin, including the caller. Used when an admin token may have leaked.
+#[debug_handler]
+async fn revoke_all_admin_sessions(state: State<AppState>, admin: AdminAccount) -> ApiResult<()> {
+    let mut tx = state.db.begin().await?;
+
+    let revoked = sqlx::query!(
+        "UPDATE admin_session SET revoked_at = $1 WHERE revoked_at IS NULL",
+        Utc::now(),
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::RevokeAdminSessions,
+        None,
+        None::<&()>,
+        Some(&json!({ "revokedSessions": revoked })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Prevents locking everyone out by removing or demoting the last superadmin. The superadmin
+/// rows stay locked until the transaction ends, so two admins cannot concurrently demote or
+/// remove each other.
+async fn ensure_other_superadmin_exists(
+    tx: &mut Transaction<'_, Postgres>,
+    id: AdminId,
+) -> ApiResult<()> {
+    let superadmin_ids = sqlx::query!(
+        "
+        SELECT id
+        FROM admin_account
+        WHERE role = 'superadmin'::admin_role
+        FOR UPDATE
+        ",
+    )
+    .fetch_all(&mut **tx)
+    .await?
+    .into_iter()
+    .map(|row| row.id)
+    .collect::<Vec<_>>();
+
+    let target_is_superadmin = superadmin_ids.contains(&id);
+    let other_superadmin_count = superadmin_ids.iter().filter(|other| **other != id).count();
+
+    if target_is_superadmin && other_superadmin_count == 0 {
+        return Err(ApiError::InvalidAdminAccount(
+            InvalidAdminAccountError::LastSuperadmin,
+        ));
+    }
+
+    Ok(())
+}
@@ -1,54 +1,304 @@
 use askama::Template;
-use axum::{extract::State, response::Html, routing::get, Router};
+use axum::{
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Html,
+    routing::{get, post},
+    Router,
+};
+use serde::Deserialize;
 
 use crate::{
-    api_types::{ApiError, ApiResult},
+    api_types::{ApiError, ApiResult, AuthFailedError},
     ctx::Ctx,
-    models::{CandidateId, LoginState},
+    helpers::get_voting_results,
+    i18n::Locale,
+    middleware::resolve_voting::resolve_voting,
+    models::{LoginState, Voting, VotingId, VotingResult, VotingResultVersion, VotingState},
 };
 
 use super::AppState;
 
+const BALLOTS_PER_PAGE: i64 = 50;
+
 pub fn router(state: AppState) -> Router<AppState> {
-    Router::new().route("/", get(get_audit))
+    Router::new()
+        .route("/:id", get(get_voting_audit))
+        .route("/:id/verify", post(verify_voting_result))
+        .route_layer(from_fn_with_state(state, resolve_voting))
+        .route("/", get(get_audit))
+}
+
+/// Ballots are only shown after the voting has closed, so they cannot be used to follow the
+/// count while voting is still going on. Voters cannot see the ballots of votings that hide
+/// their vote counts.
+fn can_audit(login_state: &LoginState, hide_vote_counts: bool) -> bool {
+    match login_state {
+        LoginState::Admin(_) => true,
+        LoginState::Voter { .. } => !hide_vote_counts,
+        LoginState::NotLoggedIn => false,
+    }
+}
+
+fn require_can_audit(login_state: &LoginState, voting: &Voting) -> ApiResult<()> {
+    if !matches!(voting.state, VotingState::Closed(_)) {
+        return Err(ApiError::VotingNotClosed);
+    }
+
+    if !can_audit(login_state, voting.hide_vote_counts) {
+        return Err(ApiError::AuthFailed(
+            AuthFailedError::InsufficientPermissions,
+        ));
+    }
+
+    Ok(())
 }
 
 async fn get_audit(ctx: Ctx, state: State<AppState>) -> ApiResult<Html<String>> {
-    let votes = sqlx::query_as!(
-        AuditRow,
+    let login_state = ctx.login_state();
+
+    let votings = sqlx::query_as!(
+        AuditVoting,
         "
         SELECT
-            voting.name as voting_name,
-            vote.id,
-            COALESCE(NULLIF(ARRAY_AGG(vote.candidate_name ORDER BY vote.rank ASC), '{null}'), '{}') as \"vote!: Vec<CandidateId>\",
-            hide_vote_counts
+            voting.id,
+            voting.name,
+            voting.hide_vote_counts,
+            COUNT(DISTINCT vote.id) AS \"ballot_count!\"
         FROM
-            voting INNER JOIN vote ON voting.id = vote.voting_id
-        GROUP BY voting.name, vote.id, voting.hide_vote_counts
+            voting
+            LEFT JOIN vote ON voting.id = vote.voting_id
+        WHERE voting.state = 'closed'::voting_state
+        GROUP BY voting.id
+        ORDER BY voting.agenda_position, voting.id
         "
     )
     .fetch_all(&state.db)
-    .await?;
+    .await?
+    .into_iter()
+    .filter(|v| can_audit(&login_state, v.hide_vote_counts))
+    .collect();
 
     AuditTemplate {
-        login_state: ctx.login_state(),
-        votes,
+        login_state,
+        locale: ctx.locale(),
+        votings,
     }
     .render()
-    .map(|h| Html(h))
-    .map_err(|e| ApiError::TemplatingError(e))
+    .map(Html)
+    .map_err(ApiError::TemplatingError)
 }
 
-struct AuditRow {
-    pub voting_name: String,
-    pub id: String,
-    pub vote: Vec<CandidateId>,
+#[derive(Deserialize)]
+struct AuditQuery {
+    page: Option<i64>,
+    #[serde(default)]
+    search: String,
+}
+
+async fn get_voting_audit(
+    ctx: Ctx,
+    state: State<AppState>,
+    voting: Voting,
+    Query(query): Query<AuditQuery>,
+) -> ApiResult<Html<String>> {
+    let login_state = ctx.login_state();
+    require_can_audit(&login_state, &voting)?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let search = query.search.trim().to_string();
+    let pattern = format!(
+        "%{}%",
+        search
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+
+    // A ballot matches the search by its id or by any of the candidates on it. RON has no
+    // stored name, so it is shown and searched by its label in the language of the viewer.
+    let rows = sqlx::query!(
+        "
+        SELECT
+            vote.id::text AS \"id!\",
+            ARRAY_AGG(
+                CASE WHEN candidate.is_ron THEN $5 ELSE candidate.name END
+                ORDER BY vote.rank ASC
+            ) AS \"vote!: Vec<String>\",
+            COUNT(*) OVER () AS \"total!\"
+        FROM
+            vote
+            INNER JOIN candidate ON candidate.id = vote.candidate_id
+        WHERE vote.voting_id = $1
+        GROUP BY vote.id
+        HAVING
+            vote.id::text ILIKE $2
+            OR BOOL_OR(CASE WHEN candidate.is_ron THEN $5 ELSE candidate.name END ILIKE $2)
+        ORDER BY vote.id
+        LIMIT $3 OFFSET $4
+        ",
+        voting.id,
+        pattern,
+        BALLOTS_PER_PAGE,
+        (page - 1) * BALLOTS_PER_PAGE,
+        ctx.locale().t("candidate.ron_name"),
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    let total = rows.first().map_or(0, |r| r.total);
+    let ballots = rows
+        .into_iter()
+        .map(|r| AuditBallot {
+            id: r.id,
+            vote: r.vote,
+        })
+        .collect();
+
+    VotingAuditTemplate {
+        login_state,
+        locale: ctx.locale(),
+        voting,
+        ballots,
+        search,
+        page,
+        has_next_page: page * BALLOTS_PER_PAGE < total,
+        total,
+    }
+    .render()
+    .map(Html)
+    .map_err(ApiError::TemplatingError)
+}
+
+/// Counts the ballots of the voting again with the settings of its current result version and
+/// compares the outcome with the stored result.
+async fn verify_voting_result(
+    ctx: Ctx,
+    state: State<AppState>,
+    voting: Voting,
+) -> ApiResult<Html<String>> {
+    require_can_audit(&ctx.login_state(), &voting)?;
+
+    let version = get_voting_results(&state.db, voting.id)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(ApiError::CorruptDatabaseError)?;
+
+    let mut tx = state.db.begin().await?;
+    let recounted = voting
+        .count_votes(
+            &mut tx,
+            version.number_of_winners,
+            version.result.decimal_places,
+            &version.excluded_candidate_ids,
+        )
+        .await?;
+    tx.rollback().await?;
+
+    AuditVerificationTemplate {
+        locale: ctx.locale(),
+        matches: version.result.has_same_outcome(&recounted),
+        voting,
+        version,
+        recounted,
+    }
+    .render()
+    .map(Html)
+    .map_err(ApiError::TemplatingError)
+}
+
+struct AuditVoting {
+    pub id: VotingId,
+    pub name: String,
     pub hide_vote_counts: bool,
+    pub ballot_count: i64,
+}
+
+struct AuditBallot {
+    pub id: String,
+    pub vote: Vec<String>,
 }
 
 #[derive(Template)]
 #[template(path = "pages/audit.html")]
 struct AuditTemplate {
     login_state: LoginState,
-    votes: Vec<AuditRow>,
+    locale: Locale,
+    votings: Vec<AuditVoting>,
+}
+
+#[derive(Template)]
+#[template(path = "pages/voting-audit.html")]
+struct VotingAuditTemplate {
+    login_state: LoginState,
+    locale: Locale,
+    voting: Voting,
+    ballots: Vec<AuditBallot>,
+    search: String,
+    page: i64,
+    has_next_page: bool,
+    total: i64,
+}
+
+#[derive(Template)]
+#[template(path = "components/audit-verification.html")]
+struct AuditVerificationTemplate {
+    locale: Locale,
+    voting: Voting,
+    version: VotingResultVersion,
+    recounted: VotingResult,
+    matches: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+    use sqlx::{Pool, Postgres};
+
+    use crate::http::test_client::TestClient;
+
+    async fn create_open_voting(admin: &mut TestClient, name: &str) -> i64 {
+        let voting = admin
+            .create_voting(json!({
+                "name": name,
+                "description": "",
+                "state": "Open",
+                "hideVoteCounts": false,
+                "numberOfWinners": 1,
+                "candidates": [{ "name": "Alice" }, { "name": "Bob" }],
+            }))
+            .await;
+
+        voting["id"].as_i64().unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_audit_hides_open_votings(db: Pool<Postgres>) {
+        let mut admin = TestClient::new(db);
+        admin.login_admin().await;
+
+        let open_id = create_open_voting(&mut admin, "Still open").await;
+        let closed_id = create_open_voting(&mut admin, "Already closed").await;
+
+        let (status, body) = admin
+            .send(
+                Method::PATCH,
+                &format!("/votings/{closed_id}"),
+                Some(json!({ "state": "Closed" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        let (status, body) = admin.get("/audit").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("Already closed"));
+        assert!(!body.contains("Still open"));
+
+        let (status, _) = admin.get(&format!("/audit/{open_id}")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = admin.get(&format!("/audit/{closed_id}")).await;
+        assert_eq!(status, StatusCode::OK);
+    }
 }
@@ -0,0 +1,228 @@
+use axum::{
+    body::Bytes,
+    debug_handler,
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    middleware::from_fn,
+    response::IntoResponse,
+    routing::{get, post, put},
+    Router,
+};
+use chrono::Utc;
+use serde_json::json;
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    helpers::log_admin_event,
+    http::{presentation::PresentationEvent, AppState},
+    middleware::require_is_official::require_is_official,
+    models::{AdminAccount, AdminAction, CandidateId, VotingStateWithoutResults},
+};
+
+const MAX_IMAGE_BYTES: usize = 1024 * 1024;
+const ALLOWED_IMAGE_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/webp", "image/gif"];
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/:candidate_id/image",
+            put(put_candidate_image).delete(delete_candidate_image),
+        )
+        .route("/:candidate_id/withdraw", post(withdraw_candidate))
+        .route_layer(from_fn(require_is_official))
+        .route("/:candidate_id/image", get(get_candidate_image))
+}
+
+#[debug_handler]
+async fn get_candidate_image(
+    state: State<AppState>,
+    Path(candidate_id): Path<CandidateId>,
+) -> ApiResult<impl IntoResponse> {
+    let image = sqlx::query!(
+        "SELECT content_type, data FROM candidate_image WHERE candidate_id = $1",
+        candidate_id
+    )
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or(ApiError::CandidateImageNotFound)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, image.content_type),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (header::CACHE_CONTROL, "no-cache".to_string()),
+        ],
+        image.data,
+    ))
+}
+
+#[debug_handler]
+async fn put_candidate_image(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Path(candidate_id): Path<CandidateId>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<()> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| ALLOWED_IMAGE_TYPES.contains(value))
+        .ok_or(ApiError::InvalidInput)?;
+
+    if body.is_empty() || body.len() > MAX_IMAGE_BYTES {
+        return Err(ApiError::InvalidInput);
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let saved = sqlx::query!(
+        "
+        INSERT INTO candidate_image (candidate_id, content_type, data)
+        SELECT id, $2, $3 FROM candidate WHERE id = $1
+        ON CONFLICT (candidate_id) DO UPDATE
+        SET content_type = EXCLUDED.content_type, data = EXCLUDED.data
+        ",
+        candidate_id,
+        content_type,
+        body.as_ref()
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if saved == 0 {
+        return Err(ApiError::InvalidInput);
+    }
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::UpdateCandidateImage,
+        Some(format!("candidate:{candidate_id}")),
+        None::<&()>,
+        Some(&json!({ "contentType": content_type, "size": body.len() })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[debug_handler]
+async fn delete_candidate_image(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Path(candidate_id): Path<CandidateId>,
+) -> ApiResult<()> {
+    let mut tx = state.db.begin().await?;
+
+    let deleted = sqlx::query!(
+        "
+        DELETE FROM candidate_image
+        WHERE candidate_id = $1
+        RETURNING content_type, octet_length(data) AS \"size!\"
+        ",
+        candidate_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::CandidateImageNotFound)?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::DeleteCandidateImage,
+        Some(format!("candidate:{candidate_id}")),
+        Some(&json!({ "contentType": deleted.content_type, "size": deleted.size })),
+        None::<&()>,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Withdraws a candidate from an open voting. Ballots that have already been cast stay valid
+/// and the candidate is skipped when they are counted.
+#[debug_handler]
+async fn withdraw_candidate(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Path(candidate_id): Path<CandidateId>,
+) -> ApiResult<()> {
+    let mut tx = state.db.begin().await?;
+
+    let candidate = sqlx::query!(
+        "
+        SELECT
+            c.voting_id,
+            c.name,
+            v.state AS \"state: VotingStateWithoutResults\",
+            v.number_of_winners
+        FROM candidate AS c INNER JOIN voting AS v ON v.id = c.voting_id
+        WHERE c.id = $1
+        FOR UPDATE
+        ",
+        candidate_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::InvalidInput)?;
+
+    if candidate.state != VotingStateWithoutResults::Open {
+        return Err(ApiError::VotingNotOpen);
+    }
+
+    // The voting row is locked above, so concurrent withdrawals are counted one at a time
+    let remaining = sqlx::query_scalar!(
+        "
+        SELECT count(*) AS \"count!\"
+        FROM candidate
+        WHERE voting_id = $1 AND id <> $2 AND NOT is_ron AND withdrawn_at IS NULL
+        ",
+        candidate.voting_id,
+        candidate_id,
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if remaining == 0 || remaining < i64::from(candidate.number_of_winners) {
+        return Err(ApiError::TooFewCandidates);
+    }
+
+    let withdrawn = sqlx::query!(
+        "
+        UPDATE candidate
+        SET withdrawn_at = $2
+        WHERE id = $1 AND withdrawn_at IS NULL
+        ",
+        candidate_id,
+        Utc::now(),
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if withdrawn == 0 {
+        return Err(ApiError::InvalidInput);
+    }
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::WithdrawCandidate,
+        Some(format!("voting:{}", candidate.voting_id)),
+        None::<&()>,
+        Some(&json!({ "candidateId": candidate_id, "name": candidate.name })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    state.notify_presentations(PresentationEvent::Voting(candidate.voting_id));
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use std::{sync::atomic::Ordering, time::Duration};
+
+use axum::{debug_handler, extract::State, http::StatusCode, routing::get, Json, Router};
+use serde::Serialize;
+
+use crate::{helpers::pending_migrations, http::AppState};
+
+/// How long the readiness check waits for the database.
+const DATABASE_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/healthz", get(get_health))
+        .route("/readyz", get(get_readiness))
+}
+
+/// Liveness: the process is up and serving requests.
+#[debug_handler]
+async fn get_health() -> &'static str {
+    "ok"
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Readiness {
+    ready: bool,
+    database_reachable: bool,
+    pending_migrations: Vec<i64>,
+    shutting_down: bool,
+}
+
+/// Readiness: the database is reachable, every migration is applied, the initial admin exists
+/// and the server is not shutting down.
+#[debug_handler]
+async fn get_readiness(state: State<AppState>) -> (StatusCode, Json<Readiness>) {
+    let pending = tokio::time::timeout(DATABASE_CHECK_TIMEOUT, pending_migrations(&state.db))
+        .await
+        .ok()
+        .and_then(Result::ok);
+    let shutting_down = state.shutting_down.load(Ordering::Relaxed);
+    let database_ready = state.database_ready.load(Ordering::Acquire);
+
+    let readiness = Readiness {
+        ready: database_ready && pending.as_ref().is_some_and(Vec::is_empty) && !shutting_down,
+        database_reachable: pending.is_some(),
+        pending_migrations: pending.unwrap_or_default(),
+        shutting_down,
+    };
+
+    let status = match readiness.ready {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    (status, Json(readiness))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::Value;
+    use sqlx::{Pool, Postgres};
+
+    use crate::http::test_client::TestClient;
+
+    #[sqlx::test(migrations = false)]
+    async fn test_not_ready_with_pending_migrations(db: Pool<Postgres>) {
+        let mut client = TestClient::with_database_ready(db, false);
+
+        let (status, body) = client.get("/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        let readiness: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(readiness["databaseReachable"], true);
+        assert!(!readiness["pendingMigrations"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(client.get("/healthz").await.0, StatusCode::OK);
+        assert_eq!(client.get("/").await.0, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[sqlx::test]
+    async fn test_not_ready_until_the_database_is_prepared(db: Pool<Postgres>) {
+        let mut client = TestClient::with_database_ready(db.clone(), false);
+        assert_eq!(
+            client.get("/readyz").await.0,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        let mut client = TestClient::new(db);
+        let (status, body) = client.get("/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        let readiness: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(readiness["pendingMigrations"], Value::Array(vec![]));
+    }
+}
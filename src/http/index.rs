@@ -3,6 +3,7 @@ use axum::{response::Html, routing::get, Router};
 
 use crate::{
     ctx::Ctx,
+    i18n::Locale,
     models::{LoginState, VotingState},
 };
 use axum::extract::State;
@@ -25,12 +26,14 @@ pub fn router() -> Router<AppState> {
 #[template(path = "pages/login.html")]
 struct LoginTemplate {
     login_state: LoginState,
+    locale: Locale,
 }
 
 #[derive(Template)]
 #[template(path = "pages/voter-home.html")]
 struct VotingTemplate {
     pub login_state: LoginState,
+    pub locale: Locale,
     pub votings_list_template: VotingListTemplate,
 }
 
@@ -38,6 +41,7 @@ struct VotingTemplate {
 #[template(path = "pages/admin-home.html")]
 struct AdminVotingTemplate {
     pub login_state: LoginState,
+    pub locale: Locale,
     pub votings_list_template: AdminVotingListTemplate,
 }
 
@@ -46,28 +50,39 @@ async fn get_root(context: Ctx, state: State<AppState>) -> ApiResult<Html<String
         match context.login_state() {
             LoginState::NotLoggedIn => LoginTemplate {
                 login_state: context.login_state(),
+                locale: context.locale(),
             }
             .render()
             .map_err(|_| ApiError::InternalServerError),
             LoginState::Voter { .. } => {
-                let votings_list_template =
-                    get_votings_list_template(state.db.clone(), context.login_state(), None)
-                        .await?;
+                let votings_list_template = get_votings_list_template(
+                    state.db.clone(),
+                    &state.config.hmac_key,
+                    context.login_state(),
+                    context.locale(),
+                    None,
+                )
+                .await?;
 
                 VotingTemplate {
                     login_state: context.login_state(),
+                    locale: context.locale(),
                     votings_list_template,
                 }
                 .render()
                 .map_err(|e| e.into())
             }
-            LoginState::Admin => {
-                let votings_list_template =
-                    get_admin_votings_list_template(state.db.clone(), context.login_state())
-                        .await?;
+            LoginState::Admin(_) => {
+                let votings_list_template = get_admin_votings_list_template(
+                    state.db.clone(),
+                    context.login_state(),
+                    context.locale(),
+                )
+                .await?;
 
                 AdminVotingTemplate {
                     login_state: context.login_state(),
+                    locale: context.locale(),
                     votings_list_template,
                 }
                 .render()
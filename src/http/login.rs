@@ -7,32 +7,47 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
-use chrono::Utc;
-use jsonwebtoken::{encode, EncodingKey, Header};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tower::{buffer::BufferLayer, limit::RateLimitLayer, BoxError, ServiceBuilder};
-use tower_cookies::{Cookie, Cookies};
+use tower_cookies::{cookie::SameSite, Cookie, Cookies};
 
 use crate::{
     api_types::{ApiError, ApiResult, AuthFailedError},
-    ctx::Ctx,
+    ctx::{ClientIp, Ctx},
+    helpers::{
+        clear_login_failures, dummy_password_hash, ensure_login_allowed, record_login_failure,
+        verify_password,
+    },
     http::AppState,
-    models::LoginState,
+    i18n::Locale,
+    models::{AdminId, AdminSessionId, LoginKind, LoginState},
 };
 
 pub const AUTH_TOKEN: &str = "admin-token";
 const TOKEN_EXPIRY_DURATION_HOURS: i64 = 24;
+/// Tokens older than this are reissued on the next request, sliding the session expiry forward.
+pub const TOKEN_REFRESH_INTERVAL_MINUTES: i64 = 15;
 
 #[derive(Deserialize)]
 struct LoginPayload {
-    token: String,
+    username: String,
+    password: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct JsonWebTokenClaims {
+    pub sub: AdminId,
+    pub jti: AdminSessionId,
     exp: i64,
-    iat: i64,
+    pub iat: i64,
+}
+
+struct AdminAccountWithPassword {
+    id: AdminId,
+    password_hash: String,
 }
 
 #[derive(Serialize)]
@@ -42,10 +57,11 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/login", post(json_web_token_login))
         .route("/admin", get(admin_login))
+        .route("/admin/logout", post(admin_logout))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(|err: BoxError| async move {
-                    println!("{}", err);
+                    tracing::error!(error = %err, "login rate limiter failed");
                     Err::<(), ApiError>(ApiError::InternalServerError)
                 }))
                 .layer(BufferLayer::new(1024))
@@ -57,49 +73,191 @@ pub fn router() -> Router<AppState> {
 async fn json_web_token_login(
     state: State<AppState>,
     cookies: Cookies,
+    ClientIp(ip): ClientIp,
     Json(login_payload): Json<LoginPayload>,
 ) -> ApiResult<Json<LoginResponse>> {
-    if login_payload.token != state.config.admin_password {
-        return Err(ApiError::AuthFailed(AuthFailedError::WrongAdminToken));
-    }
+    ensure_login_allowed(
+        &state.db,
+        &state.config,
+        LoginKind::Admin,
+        &ip,
+        &login_payload.username,
+    )
+    .await?;
+
+    let admin = sqlx::query_as!(
+        AdminAccountWithPassword,
+        "
+        SELECT id, password_hash
+        FROM admin_account
+        WHERE username = $1
+        ",
+        login_payload.username
+    )
+    .fetch_optional(&state.db)
+    .await?;
+
+    // Verify even when the username does not exist, so that the response time does not tell
+    // which usernames are taken
+    let password_is_valid = verify_password(
+        login_payload.password,
+        admin
+            .as_ref()
+            .map_or(dummy_password_hash(), |admin| &admin.password_hash)
+            .to_string(),
+    )
+    .await?;
+    let admin = admin.filter(|_| password_is_valid);
+
+    let Some(admin) = admin else {
+        record_login_failure(
+            &state.db,
+            &state.config,
+            LoginKind::Admin,
+            &ip,
+            &login_payload.username,
+        )
+        .await?;
+        return Err(ApiError::AuthFailed(AuthFailedError::WrongAdminCredentials));
+    };
 
+    clear_login_failures(
+        &state.db,
+        &state.config,
+        LoginKind::Admin,
+        &login_payload.username,
+    )
+    .await?;
+
+    let session_id = AdminSessionId::new_v4();
     let current_timestamp = Utc::now();
-    let expiration_time = current_timestamp + chrono::Duration::hours(TOKEN_EXPIRY_DURATION_HOURS);
+    let expiration_time = next_admin_session_expiry(current_timestamp);
+
+    let mut tx = state.db.begin().await?;
+
+    sqlx::query!(
+        "DELETE FROM admin_session WHERE expires_at < $1",
+        current_timestamp
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "
+        INSERT INTO admin_session (id, admin_id, created_at, expires_at)
+        VALUES ($1, $2, $3, $4)
+        ",
+        session_id,
+        admin.id,
+        current_timestamp,
+        expiration_time,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    set_admin_token_cookie(
+        &cookies,
+        &state.config.hmac_key,
+        admin.id,
+        session_id,
+        current_timestamp,
+        expiration_time,
+    )?;
+
+    tx.commit().await?;
+
+    Ok(Json(LoginResponse {}))
+}
+
+#[debug_handler]
+async fn admin_logout(state: State<AppState>, cookies: Cookies) -> ApiResult<()> {
+    if let Some(claims) = decode_admin_token(&cookies, &state.config.hmac_key) {
+        sqlx::query!(
+            "
+            UPDATE admin_session
+            SET revoked_at = $2
+            WHERE id = $1 AND revoked_at IS NULL
+            ",
+            claims.jti,
+            Utc::now(),
+        )
+        .execute(&state.db)
+        .await?;
+    }
+
+    let mut cookie = Cookie::named(AUTH_TOKEN);
+    cookie.set_path("/");
+    cookies.remove(cookie);
+
+    Ok(())
+}
 
+/// Returns the claims of the admin token cookie if its signature and expiry are valid.
+/// Whether the session has been revoked has to be checked against the database separately.
+pub fn decode_admin_token(cookies: &Cookies, hmac_key: &str) -> Option<JsonWebTokenClaims> {
+    let token = cookies.get(AUTH_TOKEN)?;
+
+    decode::<JsonWebTokenClaims>(
+        token.value(),
+        &DecodingKey::from_secret(hmac_key.as_bytes()),
+        &Validation::default(),
+    )
+    .ok()
+    .map(|token_data| token_data.claims)
+}
+
+/// Issues a signed token for the given session and stores it in the admin token cookie.
+pub fn set_admin_token_cookie(
+    cookies: &Cookies,
+    hmac_key: &str,
+    admin_id: AdminId,
+    session_id: AdminSessionId,
+    issued_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+) -> ApiResult<()> {
     let claims = JsonWebTokenClaims {
-        exp: expiration_time.timestamp(),
-        iat: current_timestamp.timestamp(),
+        sub: admin_id,
+        jti: session_id,
+        exp: expires_at.timestamp(),
+        iat: issued_at.timestamp(),
     };
 
-    let token_result = encode(
+    let token = encode(
         &Header::default(),
         &claims,
-        &EncodingKey::from_secret(state.config.hmac_key.as_bytes()),
+        &EncodingKey::from_secret(hmac_key.as_bytes()),
+    )
+    .map_err(|_| ApiError::InternalServerError)?;
+
+    cookies.add(
+        Cookie::build(AUTH_TOKEN, token)
+            .http_only(true)
+            .secure(true)
+            .same_site(SameSite::Lax)
+            .path("/")
+            .expires(OffsetDateTime::from_unix_timestamp(claims.exp).unwrap()) // TODO: Fix nasty conversion between datetime types
+            .finish(),
     );
 
-    token_result
-        .map(|token| {
-            cookies.add(
-                Cookie::build(AUTH_TOKEN, token.clone())
-                    .http_only(true)
-                    .secure(true)
-                    .expires(OffsetDateTime::from_unix_timestamp(claims.exp).unwrap()) // TODO: Fix nasty conversion between datetime types
-                    .finish(),
-            );
-            Json(LoginResponse {})
-        })
-        .map_err(|_| ApiError::InternalServerError)
+    Ok(())
+}
+
+/// Slides the expiry of an active session forward, returning the new expiry time.
+pub fn next_admin_session_expiry(now: DateTime<Utc>) -> DateTime<Utc> {
+    now + chrono::Duration::hours(TOKEN_EXPIRY_DURATION_HOURS)
 }
 
 #[derive(Template)]
 #[template(path = "pages/admin-login.html")]
 struct AdminLoginTemplate {
     login_state: LoginState,
+    locale: Locale,
 }
 
 async fn admin_login(context: Ctx) -> ApiResult<Html<String>> {
     let template = AdminLoginTemplate {
         login_state: context.login_state(),
+        locale: context.locale(),
     }
     .render()
     .map_err(|_| ApiError::InternalServerError)?;
@@ -0,0 +1,141 @@
+use axum::{
+    debug_handler, extract::State, http::header, middleware::from_fn, response::IntoResponse,
+    routing::get, Router,
+};
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    http::AppState,
+    metrics::metrics,
+    middleware::require_is_admin::require_is_admin,
+    models::{TokenState, VotingStateWithoutResults},
+};
+
+/// `/metrics` for admins on the main port.
+pub fn router() -> Router<AppState> {
+    unauthenticated_router().route_layer(from_fn(require_is_admin))
+}
+
+/// `/metrics` without authentication, for the separate metrics port.
+pub fn unauthenticated_router() -> Router<AppState> {
+    Router::new().route("/metrics", get(get_metrics))
+}
+
+#[debug_handler]
+async fn get_metrics(state: State<AppState>) -> ApiResult<impl IntoResponse> {
+    let metrics = metrics();
+
+    let ballots = sqlx::query!(
+        "
+        SELECT
+            v.id,
+            v.state AS \"state: VotingStateWithoutResults\",
+            count(hv.voting_id) AS \"count!\"
+        FROM voting AS v LEFT JOIN has_voted AS hv ON hv.voting_id = v.id
+        GROUP BY v.id
+        "
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    // Deleted votings disappear from the output
+    metrics.ballots_cast.reset();
+    for row in ballots {
+        let voting_state = match row.state {
+            VotingStateWithoutResults::Draft => "draft",
+            VotingStateWithoutResults::Nominating => "nominating",
+            VotingStateWithoutResults::Open => "open",
+            VotingStateWithoutResults::Closed => "closed",
+        };
+        metrics
+            .ballots_cast
+            .with_label_values(&[&row.id.to_string(), voting_state])
+            .set(row.count);
+    }
+
+    let tokens = sqlx::query!(
+        "
+        SELECT state AS \"state: TokenState\", count(*) AS \"count!\"
+        FROM token
+        GROUP BY state
+        "
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    for (token_state, label) in [
+        (TokenState::Unactivated, "unactivated"),
+        (TokenState::Activated, "activated"),
+        (TokenState::Voided, "voided"),
+    ] {
+        let count = tokens
+            .iter()
+            .find(|row| row.state == token_state)
+            .map_or(0, |row| row.count);
+        metrics.tokens.with_label_values(&[label]).set(count);
+    }
+
+    let size = state.db.size() as i64;
+    let idle = state.db.num_idle() as i64;
+    let max = state.db.options().get_max_connections() as i64;
+    for (label, count) in [("in_use", size - idle), ("idle", idle), ("max", max)] {
+        metrics
+            .db_pool_connections
+            .with_label_values(&[label])
+            .set(count);
+    }
+
+    let body = metrics
+        .encode()
+        .map_err(|_| ApiError::InternalServerError)?;
+
+    Ok(([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use sqlx::{Pool, Postgres};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::http::test_client::{body_text, TestClient};
+
+    const METRICS_TYPE_LINE: &str = "# TYPE vaalikoppi_tokens gauge";
+
+    #[sqlx::test]
+    async fn test_metrics_require_an_admin_on_the_main_port(db: Pool<Postgres>) {
+        let mut anonymous = TestClient::new(db.clone());
+        let (status, body) = anonymous.get("/metrics").await;
+        assert_ne!(status, StatusCode::OK);
+        assert!(!body.contains(METRICS_TYPE_LINE));
+
+        let mut voter = TestClient::new(db.clone());
+        voter.login_voter("voter").await;
+        let (status, body) = voter.get("/metrics").await;
+        assert_ne!(status, StatusCode::OK);
+        assert!(!body.contains(METRICS_TYPE_LINE));
+
+        let mut admin = TestClient::new(db);
+        admin.login_admin().await;
+        let (status, body) = admin.get("/metrics").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(METRICS_TYPE_LINE));
+    }
+
+    #[sqlx::test]
+    async fn test_metrics_port_does_not_require_authentication(db: Pool<Postgres>) {
+        let state = TestClient::new(db).state;
+        let response = unauthenticated_router()
+            .with_state(state)
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_text(response).await.contains(METRICS_TYPE_LINE));
+    }
+}
@@ -1,48 +1,170 @@
-use std::sync::Arc;
+use std::{
+    future,
+    net::SocketAddr,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
-use axum::{middleware::from_fn_with_state, Router};
+use axum::{
+    middleware::{from_fn, from_fn_with_state},
+    Router,
+};
 use sqlx::{Pool, Postgres};
+use tokio::{signal, sync::broadcast};
 use tower_cookies::CookieManagerLayer;
 
-use crate::{config::Config, middleware::resolve_ctx::resolve_ctx};
+use crate::{
+    config::Config,
+    middleware::{
+        require_database_ready::require_database_ready, resolve_ctx::resolve_ctx,
+        resolve_locale::resolve_locale, trace_request::trace_request, track_metrics::track_metrics,
+        verify_csrf_token::verify_csrf_token,
+    },
+};
 
+use self::presentation::{PresentationEvent, PRESENTATION_EVENT_CAPACITY};
+
+mod admin_events;
+pub mod admins;
 pub mod audit;
+mod candidates;
+mod health;
 mod index;
 pub mod login;
+mod metrics;
+mod nominations;
+pub mod presentation;
+mod security;
 mod static_files;
+#[cfg(test)]
+pub mod test_client;
 pub mod tokens;
 pub mod user;
+mod voter_groups;
 pub mod votes;
+mod voting_templates;
 mod votings;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Pool<Postgres>,
     pub config: Arc<Config>,
+    pub presentation_events: broadcast::Sender<PresentationEvent>,
+    /// Set on SIGTERM, after which `/readyz` fails while the open requests are finished
+    pub shutting_down: Arc<AtomicBool>,
+    /// Set once the migrations have run, before which only the health checks are served
+    pub database_ready: Arc<AtomicBool>,
+}
+
+impl AppState {
+    /// Tells the open projector views that what they show may have changed.
+    pub fn notify_presentations(&self, event: PresentationEvent) {
+        // Sending only fails when no projector view is open
+        let _ = self.presentation_events.send(event);
+    }
 }
 
-pub async fn serve(db: Pool<Postgres>, config: Config) {
+pub async fn serve(db: Pool<Postgres>, config: Arc<Config>, database_ready: Arc<AtomicBool>) {
     let port = config.port;
     let state = AppState {
-        config: Arc::new(config),
+        config,
         db,
+        presentation_events: broadcast::channel(PRESENTATION_EVENT_CAPACITY).0,
+        shutting_down: Arc::new(AtomicBool::new(false)),
+        database_ready,
     };
 
-    let app: Router = router(state.clone())
-        .layer(from_fn_with_state(state.clone(), resolve_ctx))
-        .layer(CookieManagerLayer::new())
-        .with_state(state);
+    let app = app(state.clone());
+
+    let address: SocketAddr = format!("0.0.0.0:{}", port).parse().unwrap();
 
-    let address = format!("0.0.0.0:{}", port).parse().unwrap();
+    let metrics_server = state.config.metrics_port.map(|metrics_port| {
+        let metrics_app: Router = metrics::unauthenticated_router().with_state(state.clone());
+        let metrics_address: SocketAddr = format!("0.0.0.0:{}", metrics_port).parse().unwrap();
+
+        tracing::info!(address = %metrics_address, "serving metrics");
+        tokio::spawn(
+            axum::Server::bind(&metrics_address)
+                .serve(metrics_app.into_make_service())
+                .with_graceful_shutdown(termination_signal()),
+        )
+    });
+
+    tracing::info!(%address, "listening");
 
     axum::Server::bind(&address)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal(state.clone()))
         .await
         .unwrap();
+
+    if let Some(metrics_server) = metrics_server {
+        let _ = metrics_server.await;
+    }
+
+    state.db.close().await;
+    tracing::info!("shut down");
+}
+
+/// Resolves on SIGTERM or Ctrl-C. The server then stops accepting connections and finishes the
+/// open requests, so that ballots being cast are not cut off. Projector views are closed as
+/// their streams would never end, and the process exits anyway once the timeout runs out.
+async fn shutdown_signal(state: AppState) {
+    termination_signal().await;
+
+    let timeout = Duration::from_secs(state.config.shutdown_timeout_seconds);
+    tracing::info!(timeout_seconds = timeout.as_secs(), "shutting down");
+
+    state.shutting_down.store(true, Ordering::Relaxed);
+    state.notify_presentations(PresentationEvent::Shutdown);
+
+    tokio::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        tracing::warn!("open requests did not finish in time, exiting");
+        process::exit(1);
+    });
+}
+
+/// Resolves on SIGTERM or Ctrl-C.
+async fn termination_signal() {
+    let ctrl_c = async {
+        let _ = signal::ctrl_c().await;
+    };
+    let terminate = async {
+        match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(_) => future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        _ = ctrl_c => (),
+        _ = terminate => (),
+    }
+}
+
+/// The routes with the middleware that every request goes through.
+fn app(state: AppState) -> Router {
+    router(state.clone())
+        .layer(from_fn_with_state(state.clone(), resolve_ctx))
+        .layer(from_fn(verify_csrf_token))
+        .layer(from_fn(resolve_locale))
+        .layer(CookieManagerLayer::new())
+        .layer(from_fn_with_state(state.clone(), require_database_ready))
+        .layer(from_fn(trace_request))
+        .layer(from_fn(track_metrics))
+        .with_state(state)
 }
 
 fn router(state: AppState) -> Router<AppState> {
     index::router()
+        .merge(health::router())
         .nest("/tokens", tokens::router(state.clone()))
         .merge(login::router())
         .nest("/user", user::router())
@@ -50,4 +172,13 @@ fn router(state: AppState) -> Router<AppState> {
         .nest("/votings", votings::router(state.clone()))
         .merge(votes::router())
         .nest("/audit", audit::router(state.clone()))
+        .nest("/presentation", presentation::router(state.clone()))
+        .nest("/admins", admins::router())
+        .nest("/admin-events", admin_events::router())
+        .nest("/security", security::router())
+        .nest("/candidates", candidates::router())
+        .nest("/nominations", nominations::router())
+        .nest("/voting-templates", voting_templates::router())
+        .nest("/voter-groups", voter_groups::router())
+        .merge(metrics::router())
 }
@@ -0,0 +1,182 @@
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    middleware::from_fn,
+    routing::{patch, post},
+    Json, Router,
+};
+use chrono::Utc;
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    ctx::Ctx,
+    helpers::log_admin_event,
+    http::AppState,
+    middleware::{
+        require_is_official::require_is_official, require_is_voter::require_is_voter,
+        trace_request::record_voting_id,
+    },
+    models::{
+        AdminAccount, AdminAction, LoginState, Nomination, NominationCreate, NominationId,
+        NominationState, NominationUpdate, VotingStateWithoutResults,
+    },
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/:nomination_id", patch(review_nomination))
+        .route_layer(from_fn(require_is_official))
+        .merge(
+            Router::new()
+                .route("/", post(post_nomination))
+                .route_layer(from_fn(require_is_voter)),
+        )
+}
+
+/// Nominates a candidate for a voting whose nominations are open. The nomination waits for an
+/// election official to approve it.
+#[debug_handler]
+async fn post_nomination(
+    ctx: Ctx,
+    state: State<AppState>,
+    Json(nomination): Json<NominationCreate>,
+) -> ApiResult<Json<Nomination>> {
+    nomination.validate().map_err(|_| ApiError::InvalidInput)?;
+
+    let LoginState::Voter { token, alias } = ctx.login_state() else {
+        return Err(ApiError::TokenNotFound);
+    };
+
+    let name = nomination.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::InvalidInput);
+    }
+    let bio = nomination
+        .bio
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty());
+
+    record_voting_id(nomination.voting_id);
+
+    let mut tx = state.db.begin().await?;
+
+    let voting = sqlx::query!(
+        "
+        SELECT
+            state AS \"state: VotingStateWithoutResults\",
+            token_is_eligible(id, (SELECT t.id FROM token AS t WHERE t.token = $2)) AS \"is_eligible!\"
+        FROM voting
+        WHERE id = $1
+        FOR SHARE
+        ",
+        nomination.voting_id,
+        token
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::VotingNotFound)?;
+
+    if voting.state != VotingStateWithoutResults::Nominating {
+        return Err(ApiError::NominationsNotOpen);
+    }
+
+    // Only the voters who may vote may nominate
+    if !voting.is_eligible {
+        return Err(ApiError::NotEligible);
+    }
+
+    let nomination = sqlx::query_as!(
+        Nomination,
+        "
+        INSERT INTO nomination (voting_id, name, bio, nominated_by, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING
+            id,
+            voting_id,
+            name,
+            bio,
+            nominated_by,
+            state AS \"state: NominationState\",
+            created_at
+        ",
+        nomination.voting_id,
+        name,
+        bio,
+        alias,
+        Utc::now(),
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(nomination))
+}
+
+/// Approves or rejects a nomination while the nominations of its voting are open.
+#[debug_handler]
+async fn review_nomination(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Path(nomination_id): Path<NominationId>,
+    Json(update): Json<NominationUpdate>,
+) -> ApiResult<Json<Nomination>> {
+    let mut tx = state.db.begin().await?;
+
+    let existing = sqlx::query!(
+        "
+        SELECT
+            n.state AS \"state: NominationState\",
+            v.state AS \"voting_state: VotingStateWithoutResults\"
+        FROM nomination AS n INNER JOIN voting AS v ON v.id = n.voting_id
+        WHERE n.id = $1
+        FOR UPDATE
+        ",
+        nomination_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::NominationNotFound)?;
+
+    if existing.voting_state != VotingStateWithoutResults::Nominating {
+        return Err(ApiError::NominationsNotOpen);
+    }
+
+    let nomination = sqlx::query_as!(
+        Nomination,
+        "
+        UPDATE nomination
+        SET state = $2
+        WHERE id = $1
+        RETURNING
+            id,
+            voting_id,
+            name,
+            bio,
+            nominated_by,
+            state AS \"state: NominationState\",
+            created_at
+        ",
+        nomination_id,
+        update.state as NominationState,
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::ReviewNomination,
+        Some(format!("voting:{}", nomination.voting_id)),
+        Some(&json!({ "nominationId": nomination.id, "state": existing.state })),
+        Some(&json!({ "nominationId": nomination.id, "state": nomination.state, "name": nomination.name })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(nomination))
+}
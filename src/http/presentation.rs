@@ -0,0 +1,280 @@
+use std::convert::Infallible;
+
+use askama::Template;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    middleware::{from_fn, from_fn_with_state},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html,
+    },
+    routing::{get, post},
+    Router,
+};
+use futures_util::{stream, Stream};
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    ctx::Ctx,
+    helpers::{get_voting, get_voting_results, log_admin_event},
+    i18n::Locale,
+    middleware::{require_is_official::require_is_official, resolve_voting::resolve_voting},
+    models::{AdminAccount, AdminAction, LoginState, Voting, VotingId, VotingResult, VotingState},
+};
+
+use super::AppState;
+
+/// Events a slow projector view may fall behind before it skips to the latest state.
+pub const PRESENTATION_EVENT_CAPACITY: usize = 64;
+
+/// Something that may change what a projector view shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresentationEvent {
+    /// A ballot was cast or the voting, its state or its result changed.
+    Voting(VotingId),
+    /// Tokens were activated or voided, which changes the turnout of every open voting.
+    Tokens,
+    /// The server is shutting down and the views should reconnect.
+    Shutdown,
+}
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/:id/next", post(reveal_next_round))
+        .route("/:id/previous", post(hide_last_round))
+        .route_layer(from_fn(require_is_official))
+        .route("/:id", get(get_presentation_page))
+        .route("/:id/events", get(get_presentation_events))
+        .route_layer(from_fn_with_state(state, resolve_voting))
+}
+
+#[derive(Template)]
+#[template(path = "pages/voting-presentation.html")]
+struct PresentationPageTemplate {
+    login_state: LoginState,
+    locale: Locale,
+    voting: Voting,
+    content: String,
+}
+
+#[derive(Template)]
+#[template(path = "components/voting-presentation.html")]
+struct PresentationTemplate {
+    locale: Locale,
+    voting: Voting,
+    voted_count: i64,
+    eligible_count: i64,
+    /// The current result with only the revealed rounds
+    result: Option<VotingResult>,
+    round_count: usize,
+}
+
+impl PresentationTemplate {
+    fn is_fully_revealed(&self) -> bool {
+        self.result
+            .as_ref()
+            .is_some_and(|r| r.round_results.len() == self.round_count)
+    }
+}
+
+/// Renders what the projector view of the voting currently shows, or `None` if the voting has
+/// been deleted.
+async fn render_presentation(
+    db: &Pool<Postgres>,
+    id: VotingId,
+    locale: Locale,
+) -> ApiResult<Option<String>> {
+    let Some(voting) = get_voting(db, id).await? else {
+        return Ok(None);
+    };
+
+    let counts = sqlx::query!(
+        "
+        SELECT
+            (SELECT COUNT(*) FROM has_voted WHERE voting_id = $1) AS \"voted_count!\",
+            (
+                SELECT COUNT(*) FROM token
+                WHERE state = 'activated'::token_state AND token_is_eligible($1, id)
+            ) AS \"eligible_count!\",
+            (SELECT revealed_rounds FROM voting WHERE id = $1) AS \"revealed_rounds!\"
+        ",
+        id
+    )
+    .fetch_one(db)
+    .await?;
+
+    let mut result = None;
+    let mut round_count = 0;
+
+    if let VotingState::Closed(_) = voting.state {
+        if let Some(version) = get_voting_results(db, id).await?.into_iter().next() {
+            let mut revealed = version.result;
+            round_count = revealed.round_results.len();
+            revealed
+                .round_results
+                .truncate(counts.revealed_rounds.max(0) as usize);
+            result = Some(revealed);
+        }
+    }
+
+    PresentationTemplate {
+        locale,
+        voting,
+        voted_count: counts.voted_count,
+        eligible_count: counts.eligible_count,
+        result,
+        round_count,
+    }
+    .render()
+    .map(Some)
+    .map_err(ApiError::TemplatingError)
+}
+
+/// Read-only view of a voting for the projector of the meeting hall. It needs no login, so it
+/// only shows what is announced to everyone anyway.
+async fn get_presentation_page(
+    ctx: Ctx,
+    state: State<AppState>,
+    voting: Voting,
+) -> ApiResult<Html<String>> {
+    let content = render_presentation(&state.db, voting.id, ctx.locale())
+        .await?
+        .ok_or(ApiError::VotingNotFound)?;
+
+    PresentationPageTemplate {
+        login_state: ctx.login_state(),
+        locale: ctx.locale(),
+        voting,
+        content,
+    }
+    .render()
+    .map(Html)
+    .map_err(ApiError::TemplatingError)
+}
+
+/// Pushes the projector view again whenever the voting changes. The first event is sent right
+/// away so that nothing that happened while the page was loading is missed.
+async fn get_presentation_events(
+    ctx: Ctx,
+    state: State<AppState>,
+    voting: Voting,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    struct Subscription {
+        state: AppState,
+        events: Receiver<PresentationEvent>,
+        is_first: bool,
+    }
+
+    let subscription = Subscription {
+        events: state.presentation_events.subscribe(),
+        state: state.0,
+        is_first: true,
+    };
+    let (id, locale) = (voting.id, ctx.locale());
+
+    let stream = stream::unfold(subscription, move |mut sub| async move {
+        while !sub.is_first {
+            match sub.events.recv().await {
+                Ok(PresentationEvent::Voting(changed)) if changed != id => continue,
+                Ok(PresentationEvent::Shutdown) => return None,
+                Ok(_) | Err(RecvError::Lagged(_)) => break,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+        sub.is_first = false;
+
+        let html = render_presentation(&sub.state.db, id, locale)
+            .await
+            .ok()
+            .flatten()?;
+
+        Some((Ok(Event::default().event("update").data(html)), sub))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn reveal_next_round(
+    state: State<AppState>,
+    admin: AdminAccount,
+    voting: Voting,
+) -> ApiResult<StatusCode> {
+    step_reveal(&state, &admin, &voting, 1).await
+}
+
+async fn hide_last_round(
+    state: State<AppState>,
+    admin: AdminAccount,
+    voting: Voting,
+) -> ApiResult<StatusCode> {
+    step_reveal(&state, &admin, &voting, -1).await
+}
+
+async fn step_reveal(
+    state: &AppState,
+    admin: &AdminAccount,
+    voting: &Voting,
+    step: i32,
+) -> ApiResult<StatusCode> {
+    if !matches!(voting.state, VotingState::Closed(_)) {
+        return Err(ApiError::VotingNotClosed);
+    }
+
+    let round_count = get_voting_results(&state.db, voting.id)
+        .await?
+        .into_iter()
+        .next()
+        .map_or(0, |v| v.result.round_results.len() as i32);
+
+    let mut tx = state.db.begin().await?;
+
+    let revealed_rounds_before = sqlx::query!(
+        "SELECT revealed_rounds FROM voting WHERE id = $1 FOR UPDATE",
+        voting.id
+    )
+    .fetch_one(&mut *tx)
+    .await?
+    .revealed_rounds;
+
+    let revealed_rounds = sqlx::query!(
+        "
+        UPDATE voting
+        SET revealed_rounds = LEAST(GREATEST(revealed_rounds + $2, 0), $3)
+        WHERE id = $1
+        RETURNING revealed_rounds
+        ",
+        voting.id,
+        step,
+        round_count
+    )
+    .fetch_one(&mut *tx)
+    .await?
+    .revealed_rounds;
+
+    // Stepping past the first or the last round changes nothing
+    if revealed_rounds != revealed_rounds_before {
+        log_admin_event(
+            &mut *tx,
+            admin,
+            if step > 0 {
+                AdminAction::RevealRound
+            } else {
+                AdminAction::HideRound
+            },
+            Some(format!("voting:{}", voting.id)),
+            Some(&json!({ "revealedRounds": revealed_rounds_before })),
+            Some(&json!({ "revealedRounds": revealed_rounds })),
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    state.notify_presentations(PresentationEvent::Voting(voting.id));
+
+    Ok(StatusCode::NO_CONTENT)
+}
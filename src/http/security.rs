@@ -0,0 +1,196 @@
+use askama::Template;
+use axum::{
+    debug_handler,
+    extract::State,
+    middleware::from_fn,
+    response::Html,
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    ctx::Ctx,
+    helpers::{failure_window_start, locked_until},
+    http::AppState,
+    i18n::Locale,
+    middleware::{require_is_admin::require_is_admin, require_is_official::require_is_official},
+    models::{LoginFailure, LoginFailureSummary, LoginKind, LoginState},
+};
+
+const RECENT_FAILURE_COUNT: i64 = 100;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/login-failures", delete(clear_all_login_failures))
+        .route("/login-failures/ip", delete(clear_ip_login_failures))
+        .route(
+            "/login-failures/target",
+            delete(clear_target_login_failures),
+        )
+        .route_layer(from_fn(require_is_official))
+        .route("/", get(get_security_page))
+        .route_layer(from_fn(require_is_admin))
+}
+
+#[derive(Deserialize)]
+struct LoginFailureUnlock {
+    kind: LoginKind,
+    key: String,
+}
+
+#[derive(Template)]
+#[template(path = "pages/security.html")]
+struct SecurityTemplate {
+    login_state: LoginState,
+    locale: Locale,
+    ip_summaries: Vec<LoginFailureSummary>,
+    target_summaries: Vec<LoginFailureSummary>,
+    recent_failures: Vec<LoginFailure>,
+}
+
+#[debug_handler]
+async fn get_security_page(ctx: Ctx, state: State<AppState>) -> ApiResult<Html<String>> {
+    let now = Utc::now();
+    let since = failure_window_start(&state.config, now);
+
+    let ip_summaries = sqlx::query!(
+        "
+        SELECT
+            kind AS \"kind: LoginKind\",
+            ip AS \"key!\",
+            count(*) AS \"failures!\",
+            max(created_at) AS \"last_failure_at!\"
+        FROM login_failure
+        WHERE created_at > $1
+        GROUP BY kind, ip
+        ORDER BY max(created_at) DESC
+        ",
+        since
+    )
+    .fetch_all(&state.db)
+    .await?
+    .into_iter()
+    .map(|row| LoginFailureSummary {
+        kind: row.kind,
+        locked_until: locked_until(
+            &state.config,
+            row.failures,
+            row.last_failure_at,
+            state.config.login_max_failures_per_ip,
+        )
+        .filter(|locked_until| *locked_until > now),
+        key: row.key,
+        failures: row.failures,
+        last_failure_at: row.last_failure_at,
+    })
+    .collect();
+
+    let target_summaries = sqlx::query!(
+        "
+        SELECT
+            kind AS \"kind: LoginKind\",
+            target AS \"key!\",
+            count(*) AS \"failures!\",
+            max(created_at) AS \"last_failure_at!\"
+        FROM login_failure
+        WHERE created_at > $1
+        GROUP BY kind, target
+        ORDER BY max(created_at) DESC
+        ",
+        since
+    )
+    .fetch_all(&state.db)
+    .await?
+    .into_iter()
+    .map(|row| LoginFailureSummary {
+        kind: row.kind,
+        locked_until: locked_until(
+            &state.config,
+            row.failures,
+            row.last_failure_at,
+            state.config.login_max_failures_per_target,
+        )
+        .filter(|locked_until| *locked_until > now),
+        key: row.key,
+        failures: row.failures,
+        last_failure_at: row.last_failure_at,
+    })
+    .collect();
+
+    let recent_failures = sqlx::query_as!(
+        LoginFailure,
+        "
+        SELECT
+            id,
+            created_at,
+            kind AS \"kind: LoginKind\",
+            ip,
+            target
+        FROM login_failure
+        ORDER BY id DESC
+        LIMIT $1
+        ",
+        RECENT_FAILURE_COUNT
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    SecurityTemplate {
+        login_state: ctx.login_state(),
+        locale: ctx.locale(),
+        ip_summaries,
+        target_summaries,
+        recent_failures,
+    }
+    .render()
+    .map(Html)
+    .map_err(ApiError::TemplatingError)
+}
+
+/// Lifts all lockouts, e.g. when a whole meeting shares an address that got locked.
+#[debug_handler]
+async fn clear_all_login_failures(state: State<AppState>) -> ApiResult<()> {
+    sqlx::query!("DELETE FROM login_failure")
+        .execute(&state.db)
+        .await?;
+
+    Ok(())
+}
+
+/// Lifts the lockout of one address.
+#[debug_handler]
+async fn clear_ip_login_failures(
+    state: State<AppState>,
+    Json(unlock): Json<LoginFailureUnlock>,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "DELETE FROM login_failure WHERE kind = $1 AND ip = $2",
+        unlock.kind as LoginKind,
+        unlock.key,
+    )
+    .execute(&state.db)
+    .await?;
+
+    Ok(())
+}
+
+/// Lifts the lockout of one token or username, e.g. when guesses from elsewhere have locked
+/// out the rightful owner. The key is the target as it is stored in the failure log.
+#[debug_handler]
+async fn clear_target_login_failures(
+    state: State<AppState>,
+    Json(unlock): Json<LoginFailureUnlock>,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "DELETE FROM login_failure WHERE kind = $1 AND target = $2",
+        unlock.kind as LoginKind,
+        unlock.key,
+    )
+    .execute(&state.db)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::ConnectInfo,
+    http::{header, Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use envconfig::Envconfig;
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use tokio::sync::broadcast;
+use tower::ServiceExt;
+
+use crate::{
+    config::Config,
+    helpers::create_initial_admin,
+    middleware::verify_csrf_token::{CSRF_HEADER, CSRF_TOKEN},
+    models::{CandidateId, TokenId, VotingId},
+};
+
+use super::{app, presentation::PRESENTATION_EVENT_CAPACITY, AppState};
+
+pub const ADMIN_USERNAME: &str = "admin";
+pub const ADMIN_PASSWORD: &str = "secretpass";
+
+pub fn test_config() -> Config {
+    Config::init_from_hashmap(&HashMap::from([
+        ("PORT".to_string(), "0".to_string()),
+        ("DATABASE_URL".to_string(), "unused".to_string()),
+        ("HMAC_KEY".to_string(), "test".to_string()),
+        ("ADMIN_USERNAME".to_string(), ADMIN_USERNAME.to_string()),
+        ("ADMIN_PASSWORD".to_string(), ADMIN_PASSWORD.to_string()),
+    ]))
+    .unwrap()
+}
+
+pub async fn body_text(response: Response) -> String {
+    let mut body = response.into_body();
+    let mut bytes = vec![];
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk.unwrap());
+    }
+
+    String::from_utf8(bytes).unwrap()
+}
+
+/// Sends requests through the full middleware stack and keeps the cookies between them like
+/// a browser would.
+pub struct TestClient {
+    pub state: AppState,
+    app: Router,
+    cookies: HashMap<String, String>,
+}
+
+impl TestClient {
+    /// A client for a server whose database is prepared.
+    pub fn new(db: Pool<Postgres>) -> Self {
+        Self::with_database_ready(db, true)
+    }
+
+    pub fn with_database_ready(db: Pool<Postgres>, database_ready: bool) -> Self {
+        let state = AppState {
+            db,
+            config: Arc::new(test_config()),
+            presentation_events: broadcast::channel(PRESENTATION_EVENT_CAPACITY).0,
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            database_ready: Arc::new(AtomicBool::new(database_ready)),
+        };
+
+        Self {
+            app: app(state.clone()),
+            state,
+            cookies: HashMap::new(),
+        }
+    }
+
+    pub async fn get(&mut self, path: &str) -> (StatusCode, String) {
+        self.send(Method::GET, path, None).await
+    }
+
+    /// Sends the body as JSON with the CSRF header of the current cookie.
+    pub async fn send(
+        &mut self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, String) {
+        // The CSRF cookie is set by the first request
+        if method != Method::GET && !self.cookies.contains_key(CSRF_TOKEN) {
+            self.request(Method::GET, "/healthz", None).await;
+        }
+
+        self.request(method, path, body).await
+    }
+
+    async fn request(
+        &mut self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, String) {
+        let cookie_header = self
+            .cookies
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let mut request = Request::builder()
+            .method(method)
+            .uri(path)
+            .header(header::COOKIE, cookie_header)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(
+                CSRF_HEADER,
+                self.cookies.get(CSRF_TOKEN).cloned().unwrap_or_default(),
+            )
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 1234))));
+
+        let response = self.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+
+        for set_cookie in response.headers().get_all(header::SET_COOKIE) {
+            let pair = set_cookie.to_str().unwrap().split(';').next().unwrap();
+            let (name, value) = pair.split_once('=').unwrap();
+            match value {
+                "" => self.cookies.remove(name),
+                _ => self.cookies.insert(name.to_string(), value.to_string()),
+            };
+        }
+
+        (status, body_text(response).await)
+    }
+
+    /// Creates the initial superadmin and logs in as them.
+    pub async fn login_admin(&mut self) {
+        create_initial_admin(&self.state.db, &self.state.config)
+            .await
+            .unwrap();
+
+        let (status, _) = self
+            .send(
+                Method::POST,
+                "/login",
+                Some(serde_json::json!({
+                    "username": ADMIN_USERNAME,
+                    "password": ADMIN_PASSWORD,
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    /// Activates a new token and logs in with it under the alias.
+    pub async fn login_voter(&mut self, alias: &str) -> TokenId {
+        let token = format!("token-{alias}");
+        let token_id = sqlx::query_scalar!(
+            "INSERT INTO token (token, state) VALUES ($1, 'activated') RETURNING id",
+            token
+        )
+        .fetch_one(&self.state.db)
+        .await
+        .unwrap();
+
+        self.cookies.clear();
+        let (status, _) = self
+            .send(
+                Method::POST,
+                "/user/login/",
+                Some(serde_json::json!({ "token": token, "alias": alias })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        token_id
+    }
+
+    /// Creates a voting as an admin and returns it as JSON.
+    pub async fn create_voting(&mut self, voting: Value) -> Value {
+        let (status, body) = self.send(Method::POST, "/votings", Some(voting)).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        serde_json::from_str(&body).unwrap()
+    }
+
+    /// Casts a ballot as the logged in voter.
+    pub async fn vote(&mut self, voting_id: VotingId, candidates: &[CandidateId]) -> StatusCode {
+        self.send(
+            Method::POST,
+            "/votes/",
+            Some(serde_json::json!({ "voting_id": voting_id, "candidates": candidates })),
+        )
+        .await
+        .0
+    }
+}
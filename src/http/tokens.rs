@@ -8,13 +8,23 @@ use axum::{
     Router,
 };
 use serde::Serialize;
+use serde_json::json;
 use sqlx::{Postgres, QueryBuilder, Transaction};
 
 use crate::{
     api_types::{ApiError, ApiResult},
-    http::AppState,
-    middleware::{require_is_admin::require_is_admin, resolve_token::resolve_token},
-    models::{generate_token, LoginState, Token, TokenState, TokenUpdate},
+    ctx::Ctx,
+    helpers::{get_voter_groups, log_admin_event},
+    http::{presentation::PresentationEvent, AppState},
+    i18n::Locale,
+    middleware::{
+        require_is_admin::require_is_admin, require_is_official::require_is_official,
+        resolve_token::resolve_token,
+    },
+    models::{
+        generate_token, AdminAccount, AdminAction, LoginState, Token, TokenState, TokenUpdate,
+        VoterGroupTokens,
+    },
 };
 
 pub fn router(state: AppState) -> Router<AppState> {
@@ -23,8 +33,9 @@ pub fn router(state: AppState) -> Router<AppState> {
         .route_layer(from_fn_with_state(state, resolve_token))
         .route("/void-active", post(void_active_tokens))
         .route("/print", get(get_print_tokens))
-        .route("/", get(get_tokens_page))
         .route("/", post(generate_tokens))
+        .route_layer(from_fn(require_is_official))
+        .route("/", get(get_tokens_page))
         .route_layer(from_fn(require_is_admin))
 }
 
@@ -32,9 +43,12 @@ pub fn router(state: AppState) -> Router<AppState> {
 #[template(path = "components/admin-tokens.html")]
 struct TokensTemplate {
     tokens: Vec<Token>,
+    login_state: LoginState,
+    locale: Locale,
     unactivated_token_count: i32,
     activated_token_count: i32,
     voided_token_count: i32,
+    voter_groups: Vec<VoterGroupTokens>,
 }
 
 #[derive(Template)]
@@ -42,6 +56,7 @@ struct TokensTemplate {
 struct TokensPageTemplate {
     tokens: TokensTemplate,
     login_state: LoginState,
+    locale: Locale,
 }
 
 #[derive(Debug, Serialize)]
@@ -50,8 +65,13 @@ struct TokenInvalidateResult {
 }
 
 #[debug_handler]
-async fn void_active_tokens(state: State<AppState>) -> ApiResult<Json<TokenInvalidateResult>> {
-    sqlx::query_as!(
+async fn void_active_tokens(
+    admin: AdminAccount,
+    state: State<AppState>,
+) -> ApiResult<Json<TokenInvalidateResult>> {
+    let mut tx = state.db.begin().await?;
+
+    let result = sqlx::query_as!(
         TokenInvalidateResult,
         "
         WITH updated_tokens AS (
@@ -64,10 +84,24 @@ async fn void_active_tokens(state: State<AppState>) -> ApiResult<Json<TokenInval
         FROM updated_tokens
         "
     )
-    .fetch_one(&state.db)
-    .await
-    .map(|t| Json(t))
-    .map_err(|e| e.into())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::VoidActiveTokens,
+        None,
+        None::<&()>,
+        Some(&result),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    state.notify_presentations(PresentationEvent::Tokens);
+
+    Ok(Json(result))
 }
 
 #[derive(Template)]
@@ -88,13 +122,17 @@ async fn get_print_tokens(state: State<AppState>) -> ApiResult<Html<String>> {
     .fetch_all(&state.db)
     .await?;
 
-    PrintTokensTemplate { tokens: tokens }
+    PrintTokensTemplate { tokens }
         .render()
-        .map(|html| Html(html))
+        .map(Html)
         .map_err(|_| ApiError::InternalServerError)
 }
 
-async fn get_tokens<'a>(conn: &mut Transaction<'a, Postgres>) -> ApiResult<TokensTemplate> {
+async fn get_tokens(
+    conn: &mut Transaction<'_, Postgres>,
+    login_state: LoginState,
+    locale: Locale,
+) -> ApiResult<TokensTemplate> {
     let tokens = sqlx::query_as!(
         Token,
         "
@@ -119,8 +157,13 @@ async fn get_tokens<'a>(conn: &mut Transaction<'a, Postgres>) -> ApiResult<Token
         TokenState::Voided => voided_token_count += 1,
     });
 
+    let voter_groups = get_voter_groups(&mut **conn).await?;
+
     Ok(TokensTemplate {
         tokens,
+        voter_groups,
+        login_state,
+        locale,
         unactivated_token_count,
         activated_token_count,
         voided_token_count,
@@ -128,16 +171,17 @@ async fn get_tokens<'a>(conn: &mut Transaction<'a, Postgres>) -> ApiResult<Token
 }
 
 #[debug_handler]
-async fn get_tokens_page(state: State<AppState>) -> ApiResult<Html<String>> {
+async fn get_tokens_page(ctx: Ctx, state: State<AppState>) -> ApiResult<Html<String>> {
     let mut tx = state.db.begin().await?;
-    let tokens_page_template = get_tokens(&mut tx).await?;
+    let tokens_page_template = get_tokens(&mut tx, ctx.login_state(), ctx.locale()).await?;
 
     let res = TokensPageTemplate {
         tokens: tokens_page_template,
-        login_state: LoginState::Admin,
+        login_state: ctx.login_state(),
+        locale: ctx.locale(),
     }
     .render()
-    .map(|html| Html(html))
+    .map(Html)
     .map_err(|_| ApiError::InternalServerError);
 
     tx.commit().await?;
@@ -148,14 +192,15 @@ async fn get_tokens_page(state: State<AppState>) -> ApiResult<Html<String>> {
 #[debug_handler]
 async fn patch_token(
     token: Token,
+    admin: AdminAccount,
     state: State<AppState>,
     Json(token_update): Json<TokenUpdate>,
 ) -> ApiResult<Json<Token>> {
-    let state_changed_token = token
-        .handle_state_change(token_update.state)
-        .map(|t| Json(t))?;
+    let state_changed_token = token.clone().handle_state_change(token_update.state)?;
 
-    sqlx::query_as!(
+    let mut tx = state.db.begin().await?;
+
+    let updated_token = sqlx::query_as!(
         Token,
         "
         UPDATE token
@@ -170,10 +215,25 @@ async fn patch_token(
         state_changed_token.id,
         state_changed_token.state as TokenState
     )
-    .fetch_one(&state.db)
-    .await
-    .map(|t| Json(t))
-    .map_err(|e| e.into())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // The token itself is a secret and is left out of the event log
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::UpdateToken,
+        Some(format!("token:{}", token.id)),
+        Some(&json!({ "state": token.state, "alias": token.alias })),
+        Some(&json!({ "state": updated_token.state, "alias": updated_token.alias })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    state.notify_presentations(PresentationEvent::Tokens);
+
+    Ok(Json(updated_token))
 }
 
 impl Token {
@@ -193,7 +253,11 @@ impl Token {
 }
 
 #[debug_handler]
-async fn generate_tokens(state: State<AppState>) -> ApiResult<Html<String>> {
+async fn generate_tokens(
+    ctx: Ctx,
+    admin: AdminAccount,
+    state: State<AppState>,
+) -> ApiResult<Html<String>> {
     // This could be passed with some request params if necessary
     let count = 100;
 
@@ -216,7 +280,20 @@ async fn generate_tokens(state: State<AppState>) -> ApiResult<Html<String>> {
     // to the admin's browser. This shouldn't break the application but the admin UX is bad.
     query_builder.build().execute(&mut *tx).await?;
 
-    let res = get_tokens(&mut tx).await?.render().map(|html| Html(html))?;
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::GenerateTokens,
+        None,
+        None::<&()>,
+        Some(&json!({ "count": count })),
+    )
+    .await?;
+
+    let res = get_tokens(&mut tx, ctx.login_state(), ctx.locale())
+        .await?
+        .render()
+        .map(Html)?;
 
     tx.commit().await?;
 
@@ -3,7 +3,9 @@ use std::time::Duration;
 use crate::api_types::ApiError::{self, *};
 use crate::api_types::AuthFailedError::{self};
 use crate::api_types::InvalidAliasError::*;
-use crate::models::{Token, TokenState};
+use crate::ctx::ClientIp;
+use crate::helpers::{clear_login_failures, ensure_login_allowed, record_login_failure};
+use crate::models::{LoginKind, Token, TokenState};
 use crate::{api_types::ApiResult, http::AppState};
 use axum::error_handling::HandleErrorLayer;
 use axum::BoxError;
@@ -15,7 +17,7 @@ use sqlx::{self, Pool};
 use tower::buffer::BufferLayer;
 use tower::limit::RateLimitLayer;
 use tower::ServiceBuilder;
-use tower_cookies::{Cookie, Cookies};
+use tower_cookies::{cookie::SameSite, Cookie, Cookies};
 
 pub const VOTER_TOKEN: &str = "voter-token";
 pub const VOTER_TOKEN_MAX_AGE_DAYS: i64 = 1;
@@ -35,7 +37,7 @@ pub fn router() -> Router<AppState> {
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(|err: BoxError| async move {
-                    println!("{}", err);
+                    tracing::error!(error = %err, "login rate limiter failed");
                     Err::<(), ApiError>(ApiError::InternalServerError)
                 }))
                 .layer(BufferLayer::new(1024))
@@ -47,8 +49,18 @@ pub fn router() -> Router<AppState> {
 async fn user_login(
     state: State<AppState>,
     cookies: Cookies,
+    ClientIp(ip): ClientIp,
     Json(login_payload): Json<LoginPayload>,
 ) -> ApiResult<Json<LoginResponse>> {
+    ensure_login_allowed(
+        &state.db,
+        &state.config,
+        LoginKind::Voter,
+        &ip,
+        &login_payload.token,
+    )
+    .await?;
+
     let row = sqlx::query_as!(
         Token,
         "
@@ -62,38 +74,51 @@ async fn user_login(
         ",
         login_payload.token
     )
-    .fetch_one(&state.db)
-    .await
-    .map_err(|e| match e {
-        sqlx::Error::RowNotFound => AuthFailed(AuthFailedError::MissingToken),
-        _ => InternalServerError,
-    })?;
+    .fetch_optional(&state.db)
+    .await?;
 
-    match row.state {
-        TokenState::Unactivated => {
-            return Err(ApiError::AuthFailed(AuthFailedError::TokenUnactivated))
-        }
-        TokenState::Voided => return Err(ApiError::AuthFailed(AuthFailedError::TokenVoided)),
-        TokenState::Activated => {
-            // register alias
-            let token = register_and_validate_alias(
-                &state.0.db,
-                &login_payload.token,
-                &login_payload.alias,
-            )
-            .await?;
+    let auth_result = match row.map(|row| row.state) {
+        None => Err(AuthFailedError::MissingToken),
+        Some(TokenState::Unactivated) => Err(AuthFailedError::TokenUnactivated),
+        Some(TokenState::Voided) => Err(AuthFailedError::TokenVoided),
+        Some(TokenState::Activated) => Ok(()),
+    };
 
-            cookies.add(
-                Cookie::build(VOTER_TOKEN, token.token)
-                    .http_only(true)
-                    .path("/")
-                    .secure(true)
-                    .max_age(time::Duration::days(VOTER_TOKEN_MAX_AGE_DAYS))
-                    .finish(),
-            );
-            Ok(Json(LoginResponse {}))
-        }
+    if let Err(error) = auth_result {
+        record_login_failure(
+            &state.db,
+            &state.config,
+            LoginKind::Voter,
+            &ip,
+            &login_payload.token,
+        )
+        .await?;
+        return Err(AuthFailed(error));
     }
+
+    clear_login_failures(
+        &state.db,
+        &state.config,
+        LoginKind::Voter,
+        &login_payload.token,
+    )
+    .await?;
+
+    // register alias
+    let token =
+        register_and_validate_alias(&state.0.db, &login_payload.token, &login_payload.alias)
+            .await?;
+
+    cookies.add(
+        Cookie::build(VOTER_TOKEN, token.token)
+            .http_only(true)
+            .path("/")
+            .secure(true)
+            .same_site(SameSite::Lax)
+            .max_age(time::Duration::days(VOTER_TOKEN_MAX_AGE_DAYS))
+            .finish(),
+    );
+    Ok(Json(LoginResponse {}))
 }
 
 #[derive(Serialize)]
@@ -143,5 +168,5 @@ async fn register_and_validate_alias(
         _ => InternalServerError,
     })?;
 
-    return Ok(token);
+    Ok(token)
 }
@@ -0,0 +1,216 @@
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+    middleware::from_fn,
+    routing::{delete, get, put},
+    Json, Router,
+};
+use chrono::Utc;
+use serde_json::json;
+use sqlx::error::ErrorKind;
+use std::collections::HashSet;
+use validator::Validate;
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    helpers::{get_voter_groups, log_admin_event},
+    http::{presentation::PresentationEvent, AppState},
+    middleware::require_is_official::require_is_official,
+    models::{
+        AdminAccount, AdminAction, VoterGroup, VoterGroupCreate, VoterGroupId, VoterGroupTokens,
+        VoterGroupTokensUpdate,
+    },
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_groups).post(post_group))
+        .route("/:id", delete(delete_group))
+        .route("/:id/tokens", put(put_group_tokens))
+        .route_layer(from_fn(require_is_official))
+}
+
+#[debug_handler]
+async fn get_groups(state: State<AppState>) -> ApiResult<Json<Vec<VoterGroupTokens>>> {
+    get_voter_groups(&state.db).await.map(Json)
+}
+
+#[debug_handler]
+async fn post_group(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Json(group_create): Json<VoterGroupCreate>,
+) -> ApiResult<Json<VoterGroup>> {
+    group_create
+        .validate()
+        .map_err(|_| ApiError::InvalidInput)?;
+
+    let name = group_create.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::InvalidInput);
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let group = sqlx::query_as!(
+        VoterGroup,
+        "
+        INSERT INTO voter_group (name, created_at)
+        VALUES ($1, $2)
+        RETURNING id, name
+        ",
+        name,
+        Utc::now(),
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(err) if err.kind() == ErrorKind::UniqueViolation => {
+            ApiError::InvalidInput
+        }
+        e => e.into(),
+    })?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::CreateVoterGroup,
+        Some(format!("voter_group:{}", group.id)),
+        None::<&()>,
+        Some(&group),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(group))
+}
+
+/// Deletes a group that is not eligible in any voting.
+#[debug_handler]
+async fn delete_group(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Path(id): Path<VoterGroupId>,
+) -> ApiResult<StatusCode> {
+    let mut tx = state.db.begin().await?;
+
+    let group = sqlx::query_as!(
+        VoterGroup,
+        "
+        DELETE FROM voter_group
+        WHERE id = $1
+        RETURNING id, name
+        ",
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(err) if err.kind() == ErrorKind::ForeignKeyViolation => {
+            ApiError::VoterGroupInUse
+        }
+        e => e.into(),
+    })?
+    .ok_or(ApiError::VoterGroupNotFound)?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::DeleteVoterGroup,
+        Some(format!("voter_group:{}", group.id)),
+        Some(&group),
+        None::<&()>,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    state.notify_presentations(PresentationEvent::Tokens);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Replaces the tokens of a group. Affects the turnout of the open votings the group is
+/// eligible in.
+#[debug_handler]
+async fn put_group_tokens(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Path(id): Path<VoterGroupId>,
+    Json(update): Json<VoterGroupTokensUpdate>,
+) -> ApiResult<Json<VoterGroupTokens>> {
+    let token_ids = update
+        .token_ids
+        .into_iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let mut tx = state.db.begin().await?;
+
+    let previous = sqlx::query!(
+        "
+        SELECT
+            ARRAY(
+                SELECT t.token_id FROM token_voter_group AS t
+                WHERE t.voter_group_id = g.id
+                ORDER BY t.token_id
+            ) AS \"token_ids!\"
+        FROM voter_group AS g
+        WHERE g.id = $1
+        FOR UPDATE
+        ",
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::VoterGroupNotFound)?
+    .token_ids;
+
+    sqlx::query!(
+        "DELETE FROM token_voter_group WHERE voter_group_id = $1",
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let inserted = sqlx::query!(
+        "
+        INSERT INTO token_voter_group (token_id, voter_group_id)
+        SELECT id, $1 FROM token WHERE id = ANY($2)
+        ",
+        id,
+        &token_ids
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // Some of the tokens do not exist
+    if inserted.rows_affected() as usize != token_ids.len() {
+        return Err(ApiError::InvalidInput);
+    }
+
+    let group = get_voter_groups(&mut *tx)
+        .await?
+        .into_iter()
+        .find(|g| g.id == id)
+        .ok_or(ApiError::VoterGroupNotFound)?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::UpdateVoterGroupTokens,
+        Some(format!("voter_group:{}", group.id)),
+        Some(&json!({ "tokenIds": previous })),
+        Some(&json!({ "tokenIds": group.token_ids })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    state.notify_presentations(PresentationEvent::Tokens);
+
+    Ok(Json(group))
+}
@@ -2,17 +2,18 @@ use std::ops::DerefMut;
 
 use crate::api_types::ApiError;
 
-use crate::models::{LoginState, VotingStateWithoutResults};
+use crate::helpers::ballot_pseudonym;
+use crate::models::{CandidateId, LoginState, VotingStateWithoutResults};
 use crate::{
     api_types::{ApiError::AlreadyVoted, ApiError::InternalServerError, ApiResult},
     ctx::Ctx,
-    http::AppState,
-    middleware::require_is_voter::require_is_voter,
+    http::{presentation::PresentationEvent, AppState},
+    middleware::{require_is_voter::require_is_voter, trace_request::record_voting_id},
 };
 use askama::Template;
 use axum::response::Html;
 use axum::{debug_handler, extract::State, middleware::from_fn, routing::post, Json, Router};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use sqlx::error::ErrorKind;
 use sqlx::{QueryBuilder, Row};
 use uuid::Uuid;
@@ -23,16 +24,10 @@ pub fn router() -> Router<AppState> {
         .route_layer(from_fn(require_is_voter))
 }
 use crate::http::votings::{get_votings, get_votings_list_template};
-#[derive(Serialize)]
-struct PostVoteResponse {
-    candidates: Vec<String>,
-    voting_id: i32,
-}
-
 #[derive(Deserialize)]
 
 struct PostVotePayload {
-    candidates: Vec<String>,
+    candidates: Vec<CandidateId>,
     voting_id: i32,
 }
 
@@ -42,52 +37,99 @@ async fn post_vote(
     context: Ctx,
     Json(post_vote_payload): Json<PostVotePayload>,
 ) -> ApiResult<Html<String>> {
-    let token = match context.login_state() {
-        LoginState::Voter { token, .. } => Ok(token),
-        LoginState::NotLoggedIn => {
-            println!("Not logged in");
-            Err(ApiError::TokenNotFound)
-        }
-        LoginState::Admin => {
-            println!("Not logged in");
-            Err(ApiError::TokenNotFound)
-        }
+    let (token, alias) = match context.login_state() {
+        LoginState::Voter { token, alias } => Ok((token, alias)),
+        LoginState::NotLoggedIn | LoginState::Admin(_) => Err(ApiError::TokenNotFound),
     }?;
 
+    record_voting_id(post_vote_payload.voting_id);
+
     // This will practically never collide
     let uuid = uuid::Uuid::new_v4();
     // Start a transaction to add tuples to both vote and has_voted
     let mut tx = state.db.begin().await?;
 
-    // Ensure that the voting exists and is open
-    let voting_state = sqlx::query!(
+    // Ensure that the voting exists and is open. The row stays locked until the ballot is
+    // stored, so the voting cannot be closed, recounted or reset in between.
+    let voting = sqlx::query!(
         "
         SELECT
-            state as \"state: VotingStateWithoutResults\"
+            state as \"state: VotingStateWithoutResults\",
+            is_public_ballot,
+            allow_revote,
+            token_is_eligible(id, (SELECT t.id FROM token AS t WHERE t.token = $2)) AS \"is_eligible!\"
         FROM voting WHERE id = $1
+        FOR SHARE
         ",
-        post_vote_payload.voting_id
+        post_vote_payload.voting_id,
+        token
     )
-    .map(|r| r.state)
     .fetch_one(&mut *tx)
     .await
     .map_err(|_| ApiError::VotingNotFound)?;
 
-    match voting_state {
+    match voting.state {
         VotingStateWithoutResults::Open => Ok(()),
         _ => Err(ApiError::VotingNotOpen),
     }?;
 
+    if !voting.is_eligible {
+        return Err(ApiError::NotEligible);
+    }
+
+    // In votings that allow re-voting, the earlier ballot of the voter is found through a keyed
+    // pseudonym of the token and replaced by the new one
+    let pseudonym = voting
+        .allow_revote
+        .then(|| ballot_pseudonym(&state.config.hmac_key, &token, post_vote_payload.voting_id));
+
+    let previous_ballot_id = match &pseudonym {
+        Some(pseudonym) => {
+            sqlx::query!(
+                "
+            SELECT ballot_id
+            FROM ballot_pseudonym
+            WHERE voting_id = $1 AND pseudonym = $2
+            FOR UPDATE
+            ",
+                post_vote_payload.voting_id,
+                pseudonym
+            )
+            .map(|r| r.ballot_id)
+            .fetch_optional(tx.deref_mut())
+            .await?
+        }
+        None => None,
+    };
+
+    if let Some(previous_ballot_id) = previous_ballot_id {
+        sqlx::query!(
+            "DELETE FROM vote WHERE voting_id = $1 AND id = $2",
+            post_vote_payload.voting_id,
+            previous_ballot_id
+        )
+        .execute(tx.deref_mut())
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM ballot_voter WHERE voting_id = $1 AND ballot_id = $2",
+            post_vote_payload.voting_id,
+            previous_ballot_id
+        )
+        .execute(tx.deref_mut())
+        .await?;
+    }
+
     // If the voter does not vote for anyone ( candidates = [] ), then don't insert anything into vote, and the tx wont fail to syntax error
-    let insert_vote: Option<Uuid> = if post_vote_payload.candidates.len() > 0 {
-        QueryBuilder::new("INSERT INTO vote(id, candidate_name, voting_id, rank) ")
+    let insert_vote: Option<Uuid> = if !post_vote_payload.candidates.is_empty() {
+        QueryBuilder::new("INSERT INTO vote(id, candidate_id, voting_id, rank) ")
             .push_values(
                 post_vote_payload.candidates.iter().enumerate(),
-                |mut query_builder, (index, candidate_name)| {
+                |mut query_builder, (index, candidate_id)| {
                     query_builder
                         .push_bind(uuid)
-                        .push_bind(candidate_name)
-                        .push_bind(post_vote_payload.voting_id.clone())
+                        .push_bind(candidate_id)
+                        .push_bind(post_vote_payload.voting_id)
                         .push_bind(index as i32 + 1); // ranks start at 1 (rank int DEFAULT 1 defined in the db schema), not 0
                 },
             )
@@ -104,36 +146,234 @@ async fn post_vote(
         None
     };
 
-    // Duplicate key error prevents us from voting twice, and the tx fails
-    let _insert_has_voted = sqlx::query!(
-        "INSERT INTO has_voted (token_token, voting_id) VALUES ($1, $2) ",
-        token,
-        post_vote_payload.voting_id
-    )
-    .execute(tx.deref_mut())
-    .await
-    .map_err(|e| match e {
-        // Handle unique key error (trying to vote twice)
-        sqlx::Error::Database(err) if err.kind() == ErrorKind::UniqueViolation => AlreadyVoted,
-        _ => InternalServerError,
-    })?;
+    // Duplicate key error prevents us from voting twice, and the tx fails. A replaced ballot
+    // has been marked as voted already.
+    if previous_ballot_id.is_none() {
+        sqlx::query!(
+            "INSERT INTO has_voted (token_token, voting_id) VALUES ($1, $2) ",
+            token,
+            post_vote_payload.voting_id
+        )
+        .execute(tx.deref_mut())
+        .await
+        .map_err(|e| match e {
+            // Handle unique key error (trying to vote twice)
+            sqlx::Error::Database(err) if err.kind() == ErrorKind::UniqueViolation => AlreadyVoted,
+            _ => InternalServerError,
+        })?;
+    }
+
+    if let Some(pseudonym) = &pseudonym {
+        sqlx::query!(
+            "
+            INSERT INTO ballot_pseudonym (voting_id, pseudonym, ballot_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (voting_id, pseudonym) DO UPDATE SET ballot_id = EXCLUDED.ballot_id
+            ",
+            post_vote_payload.voting_id,
+            pseudonym,
+            uuid
+        )
+        .execute(tx.deref_mut())
+        .await?;
+    }
+
+    // Public votings record who cast the ballot. An empty ballot has no vote rows but is still
+    // listed under the voter's alias.
+    if voting.is_public_ballot {
+        sqlx::query!(
+            "INSERT INTO ballot_voter (voting_id, ballot_id, voter_alias) VALUES ($1, $2, $3)",
+            post_vote_payload.voting_id,
+            uuid,
+            alias
+        )
+        .execute(tx.deref_mut())
+        .await?;
+    }
 
-    let _txresult = tx.commit().await?;
+    tx.commit().await?;
+
+    state.notify_presentations(PresentationEvent::Voting(post_vote_payload.voting_id));
 
     // TODO add meaningful error messages
 
     match context.login_state() {
         LoginState::Voter { .. } => get_votings_list_template(
             state.db.clone(),
+            &state.config.hmac_key,
             context.login_state(),
+            context.locale(),
             insert_vote.map(|u| vec![u.to_string()]),
             // TODO change Option<Vec<String>> to Option<String>
         )
         .await?
         .render()
-        .map(|html| Html(html))
+        .map(Html)
         .map_err(|_| ApiError::InternalServerError),
 
         _ => get_votings(context, state).await,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+    use sqlx::{Pool, Postgres};
+
+    use crate::{http::test_client::TestClient, models::VotingId};
+
+    async fn create_open_voting(db: &Pool<Postgres>, options: Value) -> (VotingId, Vec<i32>) {
+        let mut admin = TestClient::new(db.clone());
+        admin.login_admin().await;
+
+        let mut voting = json!({
+            "name": "Chair",
+            "description": "",
+            "state": "Open",
+            "hideVoteCounts": false,
+            "numberOfWinners": 1,
+            "candidates": [{ "name": "Alice" }, { "name": "Bob" }],
+        });
+        voting
+            .as_object_mut()
+            .unwrap()
+            .extend(options.as_object().unwrap().clone());
+
+        let voting = admin.create_voting(voting).await;
+        let candidate_ids = voting["candidates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["id"].as_i64().unwrap() as i32)
+            .collect();
+
+        (voting["id"].as_i64().unwrap() as VotingId, candidate_ids)
+    }
+
+    #[sqlx::test]
+    async fn test_public_ballot_stores_the_alias(db: Pool<Postgres>) {
+        let (voting_id, candidates) =
+            create_open_voting(&db, json!({ "isPublicBallot": true })).await;
+
+        let mut voter = TestClient::new(db.clone());
+        voter.login_voter("alice-voter").await;
+        assert_eq!(
+            voter.vote(voting_id, &candidates[..1]).await,
+            StatusCode::OK
+        );
+
+        let aliases = sqlx::query_scalar!(
+            "SELECT voter_alias FROM ballot_voter WHERE voting_id = $1",
+            voting_id
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(aliases, ["alice-voter"]);
+    }
+
+    #[sqlx::test]
+    async fn test_secret_ballot_does_not_store_the_alias(db: Pool<Postgres>) {
+        let (voting_id, candidates) = create_open_voting(&db, json!({})).await;
+
+        let mut voter = TestClient::new(db.clone());
+        voter.login_voter("alice-voter").await;
+        assert_eq!(
+            voter.vote(voting_id, &candidates[..1]).await,
+            StatusCode::OK
+        );
+
+        let count = sqlx::query_scalar!(
+            "SELECT count(*) AS \"count!\" FROM ballot_voter WHERE voting_id = $1",
+            voting_id
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    async fn voted_candidates(db: &Pool<Postgres>, voting_id: VotingId) -> Vec<i32> {
+        sqlx::query_scalar!(
+            "SELECT candidate_id FROM vote WHERE voting_id = $1 ORDER BY id, rank",
+            voting_id
+        )
+        .fetch_all(db)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_revote_replaces_the_earlier_ballot(db: Pool<Postgres>) {
+        let (voting_id, candidates) = create_open_voting(&db, json!({ "allowRevote": true })).await;
+
+        let mut voter = TestClient::new(db.clone());
+        voter.login_voter("voter").await;
+        assert_eq!(
+            voter.vote(voting_id, &candidates[..1]).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            voter.vote(voting_id, &candidates[1..]).await,
+            StatusCode::OK
+        );
+
+        assert_eq!(voted_candidates(&db, voting_id).await, &candidates[1..]);
+
+        let voters = sqlx::query_scalar!(
+            "SELECT count(*) AS \"count!\" FROM has_voted WHERE voting_id = $1",
+            voting_id
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(voters, 1);
+
+        let mut admin = TestClient::new(db.clone());
+        admin.login_admin().await;
+        let (status, body) = admin
+            .send(
+                Method::PATCH,
+                &format!("/votings/{voting_id}"),
+                Some(json!({ "state": "Closed" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        let first_round = sqlx::query!(
+            "
+            SELECT candidate_id, vote_count
+            FROM candidate_result_data
+            WHERE voting_id = $1 AND version = 1 AND round = 1
+            ORDER BY candidate_id
+            ",
+            voting_id
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.candidate_id, row.vote_count))
+        .collect::<Vec<_>>();
+        assert_eq!(first_round, [(candidates[0], 0.0), (candidates[1], 1.0)]);
+    }
+
+    #[sqlx::test]
+    async fn test_second_ballot_is_rejected_without_revote(db: Pool<Postgres>) {
+        let (voting_id, candidates) = create_open_voting(&db, json!({})).await;
+
+        let mut voter = TestClient::new(db.clone());
+        voter.login_voter("voter").await;
+        assert_eq!(
+            voter.vote(voting_id, &candidates[..1]).await,
+            StatusCode::OK
+        );
+        assert_ne!(
+            voter.vote(voting_id, &candidates[1..]).await,
+            StatusCode::OK
+        );
+
+        assert_eq!(voted_candidates(&db, voting_id).await, &candidates[..1]);
+    }
+}
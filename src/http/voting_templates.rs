@@ -0,0 +1,141 @@
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+    middleware::from_fn,
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::Utc;
+use sqlx::error::ErrorKind;
+use validator::Validate;
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    helpers::{get_voting_templates, log_admin_event},
+    http::AppState,
+    middleware::require_is_official::require_is_official,
+    models::{AdminAccount, AdminAction, VotingTemplate, VotingTemplateCreate, VotingTemplateId},
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_templates).post(post_template))
+        .route("/:id", delete(delete_template))
+        .route_layer(from_fn(require_is_official))
+}
+
+#[debug_handler]
+async fn get_templates(state: State<AppState>) -> ApiResult<Json<Vec<VotingTemplate>>> {
+    get_voting_templates(&state.db).await.map(Json)
+}
+
+#[debug_handler]
+async fn post_template(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Json(template_create): Json<VotingTemplateCreate>,
+) -> ApiResult<Json<VotingTemplate>> {
+    template_create
+        .validate()
+        .map_err(|_| ApiError::InvalidInput)?;
+
+    let mut tx = state.db.begin().await?;
+
+    let template = sqlx::query_as!(
+        VotingTemplate,
+        "
+        INSERT INTO voting_template (name, description, hide_vote_counts, is_public_ballot, allow_revote, number_of_winners, decimal_places, include_ron, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING
+            id,
+            name,
+            description,
+            hide_vote_counts,
+            is_public_ballot,
+            allow_revote,
+            number_of_winners,
+            decimal_places,
+            include_ron,
+            created_at
+        ",
+        template_create.name.trim(),
+        template_create.description,
+        template_create.hide_vote_counts,
+        template_create.is_public_ballot,
+        template_create.allow_revote,
+        template_create.number_of_winners,
+        template_create.decimal_places,
+        template_create.include_ron,
+        Utc::now(),
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(err) if err.kind() == ErrorKind::UniqueViolation => {
+            ApiError::InvalidInput
+        }
+        e => e.into(),
+    })?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::CreateVotingTemplate,
+        Some(format!("voting_template:{}", template.id)),
+        None::<&()>,
+        Some(&template),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(template))
+}
+
+/// Deletes a template. Votings created from it are not affected.
+#[debug_handler]
+async fn delete_template(
+    state: State<AppState>,
+    admin: AdminAccount,
+    Path(id): Path<VotingTemplateId>,
+) -> ApiResult<StatusCode> {
+    let mut tx = state.db.begin().await?;
+
+    let template = sqlx::query_as!(
+        VotingTemplate,
+        "
+        DELETE FROM voting_template
+        WHERE id = $1
+        RETURNING
+            id,
+            name,
+            description,
+            hide_vote_counts,
+            is_public_ballot,
+            allow_revote,
+            number_of_winners,
+            decimal_places,
+            include_ron,
+            created_at
+        ",
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(ApiError::VotingTemplateNotFound)?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::DeleteVotingTemplate,
+        Some(format!("voting_template:{}", template.id)),
+        Some(&template),
+        None::<&()>,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
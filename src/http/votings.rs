@@ -2,58 +2,188 @@ use ::serde::{Deserialize, Serialize};
 use axum::{
     debug_handler,
     extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
     middleware::{from_fn, from_fn_with_state},
     response::Html,
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
     Json, Router,
 };
 use chrono::Utc;
-use sqlx::{postgres::PgRow, Executor, Pool, Postgres, QueryBuilder, Row};
-use std::collections::HashMap;
+use serde_json::json;
+use sqlx::{Pool, Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
 use validator::Validate;
 
 use askama::Template;
 use chrono::DateTime;
 
 use crate::{
-    api_types::{
-        ApiError::{self, InternalServerError},
-        ApiResult,
-    },
+    api_types::{ApiError, ApiResult},
     ctx::Ctx,
-    helpers::calculate_stv_result,
-    middleware::{require_is_admin::require_is_admin, resolve_voting::resolve_voting},
+    helpers::{
+        ballot_pseudonym, calculate_stv_result, exclude_candidates, get_candidates,
+        get_candidates_of_all_votings, get_eligible_groups, get_eligible_groups_of_all_votings,
+        get_nominations_of_nominating_votings, get_public_ballots,
+        get_public_ballots_of_closed_votings, get_voter_groups, get_voting_results,
+        get_voting_templates, log_admin_event, store_voting_result, vote_transfer_chart,
+        VoteTransferChart,
+    },
+    i18n::Locale,
+    middleware::{
+        require_is_admin::require_is_admin, require_is_official::require_is_official,
+        require_is_superadmin::require_is_superadmin, resolve_voting::resolve_voting,
+    },
     models::{
-        Alias, CandidateId, CandidateResultData, LoginState, PassingCandidateResult, Voting,
-        VotingCreate, VotingForVoterTemplate, VotingId, VotingResult, VotingRoundResult,
-        VotingState, VotingStateWithoutResults, VotingUpdate,
+        AdminAccount, AdminAction, Agenda, Alias, Candidate, CandidateId, CandidateInput,
+        CandidateResultData, LoginState, Nomination, NonTransferableWeight, PassingCandidateResult,
+        PublicBallot, VoterGroup, VoterGroupId, VoterGroupTokens, Voting, VotingCreate,
+        VotingForVoterTemplate, VotingId, VotingRecount, VotingReorder, VotingResult,
+        VotingResultVersion, VotingRoundResult, VotingState, VotingStateWithoutResults,
+        VotingTemplate, VotingUpdate,
     },
 };
 
-use super::AppState;
+use super::{presentation::PresentationEvent, AppState};
 
 pub fn router(state: AppState) -> Router<AppState> {
     Router::new()
         .route("/:id", patch(patch_voting))
         .route("/:id", delete(delete_voting))
-        .route_layer(from_fn_with_state(state, resolve_voting))
-        .route("/deleteall", post(delete_all_data))
+        .route("/:id/recount", post(recount_voting))
+        .route_layer(from_fn_with_state(state.clone(), resolve_voting))
         .route("/", post(post_voting))
-        .route_layer(from_fn(require_is_admin))
+        .route("/import", post(import_agenda))
+        .route("/order", put(reorder_votings))
+        .route_layer(from_fn(require_is_official))
         .route("/", get(get_votings))
+        .merge(
+            Router::new()
+                .route("/:id/results", get(get_voting_results_page))
+                .route_layer(from_fn_with_state(state, resolve_voting))
+                .route_layer(from_fn(require_is_admin)),
+        )
+        .merge(
+            Router::new()
+                .route("/deleteall", post(delete_all_data))
+                .route_layer(from_fn(require_is_superadmin)),
+        )
 }
 
 #[debug_handler]
 async fn post_voting(
+    admin: AdminAccount,
     state: State<AppState>,
     Json(voting_create): Json<VotingCreate>,
 ) -> ApiResult<Json<Voting>> {
+    let mut tx = state.db.begin().await?;
+    let voting = create_voting(&mut tx, &admin, voting_create).await?;
+    tx.commit().await?;
+
+    Ok(Json(voting))
+}
+
+/// Creates every voting of a meeting agenda as a draft in one transaction, so either the whole
+/// agenda is imported or nothing is. The body is YAML if the content type says so and JSON
+/// otherwise.
+#[debug_handler]
+async fn import_agenda(
+    admin: AdminAccount,
+    state: State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> ApiResult<Json<Vec<Voting>>> {
+    let is_yaml = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("yaml"));
+
+    let agenda: Agenda = if is_yaml {
+        serde_yaml::from_str(&body).map_err(|e| ApiError::InvalidAgenda(e.to_string()))?
+    } else {
+        serde_json::from_str(&body).map_err(|e| ApiError::InvalidAgenda(e.to_string()))?
+    };
+
+    if agenda.votings.is_empty() {
+        return Err(ApiError::InvalidAgenda("no votings".to_string()));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let templates = get_voting_templates(&mut *tx).await?;
+    let voter_groups = get_voter_groups(&mut *tx).await?;
+
+    let mut votings = vec![];
+
+    for (i, item) in agenda.votings.into_iter().enumerate() {
+        let template = match &item.template {
+            Some(name) => Some(templates.iter().find(|t| &t.name == name).ok_or_else(|| {
+                ApiError::InvalidAgenda(format!("voting {}: unknown template {name}", i + 1))
+            })?),
+            None => None,
+        };
+
+        let eligible_groups = item
+            .eligible_groups
+            .iter()
+            .map(|name| {
+                voter_groups
+                    .iter()
+                    .find(|g| &g.name == name)
+                    .map(|g| g.id)
+                    .ok_or_else(|| {
+                        ApiError::InvalidAgenda(format!(
+                            "voting {}: unknown voter group {name}",
+                            i + 1
+                        ))
+                    })
+            })
+            .collect::<ApiResult<Vec<_>>>()?;
+
+        let mut voting_create = item.into_voting_create(template).ok_or_else(|| {
+            ApiError::InvalidAgenda(format!("voting {}: number of winners missing", i + 1))
+        })?;
+        voting_create.eligible_groups = eligible_groups;
+
+        let voting = create_voting(&mut tx, &admin, voting_create)
+            .await
+            .map_err(|e| match e {
+                ApiError::InvalidInput => ApiError::InvalidAgenda(format!("voting {}", i + 1)),
+                e => e,
+            })?;
+
+        votings.push(voting);
+    }
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::ImportAgenda,
+        None,
+        None::<&()>,
+        Some(&json!({ "votingIds": votings.iter().map(|v| v.id).collect::<Vec<_>>() })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(votings))
+}
+
+async fn create_voting(
+    tx: &mut Transaction<'_, Postgres>,
+    admin: &AdminAccount,
+    voting_create: VotingCreate,
+) -> ApiResult<Voting> {
+    voting_create
+        .validate()
+        .map_err(|_| ApiError::InvalidInput)?;
+
     let voting_state = voting_create
         .state
         .unwrap_or(VotingStateWithoutResults::Draft);
 
     match voting_state {
-        VotingStateWithoutResults::Draft => Ok(()),
+        VotingStateWithoutResults::Draft | VotingStateWithoutResults::Nominating => Ok(()),
         VotingStateWithoutResults::Open => {
             if voting_create
                 .candidates
@@ -69,12 +199,12 @@ async fn post_voting(
         VotingStateWithoutResults::Closed => Err(ApiError::InvalidInput),
     }?;
 
-    let mut tx = state.db.begin().await?;
+    lock_agenda(tx).await?;
 
     let mut voting = sqlx::query!(
         "
-        INSERT INTO voting (name, description, state, created_at, hide_vote_counts, number_of_winners)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO voting (name, description, state, created_at, hide_vote_counts, is_public_ballot, allow_revote, number_of_winners, decimal_places, agenda_position, agenda_group)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, (SELECT COALESCE(MAX(agenda_position), 0) + 1 FROM voting), $10)
         RETURNING
             id,
             name,
@@ -82,14 +212,23 @@ async fn post_voting(
             state AS \"state: VotingStateWithoutResults\",
             created_at,
             hide_vote_counts,
-            number_of_winners;
+            is_public_ballot,
+            allow_revote,
+            number_of_winners,
+            decimal_places,
+            agenda_position,
+            agenda_group;
         ",
         voting_create.name,
         voting_create.description,
         voting_state as VotingStateWithoutResults,
         Utc::now(),
         voting_create.hide_vote_counts,
+        voting_create.is_public_ballot,
+        voting_create.allow_revote,
         voting_create.number_of_winners,
+        voting_create.decimal_places,
+        voting_create.agenda_group,
     )
     .map(|row| Voting {
         id: row.id,
@@ -98,85 +237,473 @@ async fn post_voting(
         state: VotingState::from(row.state),
         created_at: row.created_at,
         hide_vote_counts: row.hide_vote_counts,
+        is_public_ballot: row.is_public_ballot,
+        allow_revote: row.allow_revote,
         number_of_winners: row.number_of_winners,
+        decimal_places: row.decimal_places,
+        agenda_position: row.agenda_position,
+        agenda_group: row.agenda_group,
+        eligible_groups: vec![],
         candidates: vec![],
     })
-    .fetch_one(&mut *tx)
+    .fetch_one(&mut **tx)
     .await?;
 
-    let candidates = insert_candidates_into_db(
+    save_candidates(
+        tx,
         voting.id,
-        voting_create.candidates.unwrap_or(vec![]),
-        &mut *tx,
+        &[],
+        voting_create.candidates.unwrap_or_default(),
     )
     .await?;
 
-    voting.candidates = candidates;
+    save_ron(tx, voting.id, voting_create.include_ron).await?;
 
-    tx.commit().await?;
+    save_eligible_groups(tx, voting.id, &voting_create.eligible_groups).await?;
 
-    Ok(Json(voting))
+    voting.candidates = get_candidates(&mut **tx, voting.id).await?;
+    voting.eligible_groups = get_eligible_groups(&mut **tx, voting.id).await?;
+
+    log_admin_event(
+        &mut **tx,
+        admin,
+        AdminAction::CreateVoting,
+        Some(format!("voting:{}", voting.id)),
+        None::<&()>,
+        Some(&voting),
+    )
+    .await?;
+
+    Ok(voting)
 }
 
-async fn insert_candidates_into_db<T>(
+/// Replaces the eligible groups of a voting, see [`Voting::eligible_groups`].
+async fn save_eligible_groups(
+    tx: &mut Transaction<'_, Postgres>,
     voting_id: VotingId,
-    candidates: Vec<CandidateId>,
-    executor: &mut T,
-) -> ApiResult<Vec<CandidateId>>
-where
-    for<'e> &'e mut T: Executor<'e, Database = Postgres>,
-{
-    if candidates.is_empty() {
-        return Ok(vec![]);
+    group_ids: &[VoterGroupId],
+) -> ApiResult<()> {
+    let group_ids = group_ids
+        .iter()
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    sqlx::query!(
+        "DELETE FROM voting_eligible_group WHERE voting_id = $1",
+        voting_id
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    let inserted = sqlx::query!(
+        "
+        INSERT INTO voting_eligible_group (voting_id, voter_group_id)
+        SELECT $1, id FROM voter_group WHERE id = ANY($2)
+        ",
+        voting_id,
+        &group_ids
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    // Some of the groups do not exist
+    if inserted.rows_affected() as usize != group_ids.len() {
+        return Err(ApiError::InvalidInput);
     }
 
-    let mut query_builder: QueryBuilder<Postgres> =
-        QueryBuilder::new("INSERT INTO candidate(voting_id, name) ");
+    Ok(())
+}
 
-    query_builder.push_values(candidates, |mut b, name| {
-        b.push_bind(voting_id).push_bind(name);
-    });
+/// Replaces the candidate list of a voting. Existing candidates are matched by id so that
+/// renaming or reordering keeps their ballots and images.
+async fn save_candidates(
+    tx: &mut Transaction<'_, Postgres>,
+    voting_id: VotingId,
+    existing_candidates: &[Candidate],
+    candidates: Vec<CandidateInput>,
+) -> ApiResult<()> {
+    // RON is managed with the include_ron flag and cannot be edited as a candidate
+    let unknown_id = candidates
+        .iter()
+        .filter_map(|c| c.id)
+        .any(|id| !existing_candidates.iter().any(|e| e.id == id && !e.is_ron));
+    let names = candidates.iter().map(|c| &c.name).collect::<HashSet<_>>();
+
+    if unknown_id || names.len() != candidates.len() {
+        return Err(ApiError::InvalidInput);
+    }
 
-    query_builder.push("RETURNING name");
+    let kept_ids = candidates.iter().filter_map(|c| c.id).collect::<Vec<_>>();
 
-    query_builder
-        .build()
-        .map(|row: PgRow| row.get::<CandidateId, &'static str>("name"))
-        .fetch_all(executor)
-        .await
-        .map_err(|e| e.into())
+    sqlx::query!(
+        "DELETE FROM candidate WHERE voting_id = $1 AND NOT is_ron AND NOT (id = ANY($2))",
+        voting_id,
+        &kept_ids,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    for (display_order, candidate) in (0..).zip(candidates) {
+        match candidate.id {
+            Some(id) => {
+                sqlx::query!(
+                    "
+                    UPDATE candidate
+                    SET name = $3, bio = $4, display_order = $5
+                    WHERE id = $1 AND voting_id = $2
+                    ",
+                    id,
+                    voting_id,
+                    candidate.name,
+                    candidate.bio,
+                    display_order,
+                )
+                .execute(&mut **tx)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    "
+                    INSERT INTO candidate (voting_id, name, bio, display_order)
+                    VALUES ($1, $2, $3, $4)
+                    ",
+                    voting_id,
+                    candidate.name,
+                    candidate.bio,
+                    display_order,
+                )
+                .execute(&mut **tx)
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds or removes the re-open nominations pseudo-candidate of a voting.
+async fn save_ron(
+    tx: &mut Transaction<'_, Postgres>,
+    voting_id: VotingId,
+    include_ron: bool,
+) -> ApiResult<()> {
+    if include_ron {
+        sqlx::query!(
+            "
+            INSERT INTO candidate (voting_id, name, display_order, is_ron)
+            VALUES ($1, '', 0, true)
+            ON CONFLICT (voting_id) WHERE is_ron DO NOTHING
+            ",
+            voting_id,
+        )
+        .execute(&mut **tx)
+        .await?;
+    } else {
+        sqlx::query!(
+            "DELETE FROM candidate WHERE voting_id = $1 AND is_ron",
+            voting_id
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Adds the approved nominees after the existing candidates. A nominee with the name of an
+/// existing candidate or of an earlier nominee is added only once.
+async fn add_approved_nominees(
+    tx: &mut Transaction<'_, Postgres>,
+    voting_id: VotingId,
+) -> ApiResult<()> {
+    let nominees = sqlx::query!(
+        "
+        SELECT name, bio
+        FROM nomination
+        WHERE voting_id = $1 AND state = 'approved'::nomination_state
+        ORDER BY created_at, id
+        ",
+        voting_id
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let existing = get_candidates(&mut **tx, voting_id).await?;
+    let mut names = existing
+        .iter()
+        .map(|c| c.name.to_lowercase())
+        .collect::<HashSet<_>>();
+    let mut display_order = existing
+        .iter()
+        .filter(|c| !c.is_ron)
+        .map(|c| c.display_order + 1)
+        .max()
+        .unwrap_or(0);
+
+    for nominee in nominees {
+        if !names.insert(nominee.name.to_lowercase()) {
+            continue;
+        }
+
+        sqlx::query!(
+            "
+            INSERT INTO candidate (voting_id, name, bio, display_order)
+            VALUES ($1, $2, $3, $4)
+            ",
+            voting_id,
+            nominee.name,
+            nominee.bio,
+            display_order,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        display_order += 1;
+    }
+
+    Ok(())
 }
 
 #[debug_handler]
 async fn patch_voting(
     existing_voting: Voting,
+    admin: AdminAccount,
     state: State<AppState>,
     _id: Path<VotingId>,
     Json(voting_update): Json<VotingUpdate>,
 ) -> ApiResult<Json<Voting>> {
     let res = existing_voting
-        .handle_patch(state.db.clone(), voting_update)
+        .handle_patch(state.db.clone(), voting_update, &admin)
         .await
-        .map(|v| Json(v))?;
+        .map(Json)?;
+
+    state.notify_presentations(PresentationEvent::Voting(existing_voting.id));
+
     Ok(res)
 }
 
+#[debug_handler]
+async fn recount_voting(
+    existing_voting: Voting,
+    admin: AdminAccount,
+    state: State<AppState>,
+    _id: Path<VotingId>,
+    Json(recount): Json<VotingRecount>,
+) -> ApiResult<Json<VotingResultVersion>> {
+    recount.validate().map_err(|_| ApiError::InvalidInput)?;
+
+    if existing_voting.state == VotingStateWithoutResults::Closed {
+        Ok(())
+    } else {
+        Err(ApiError::VotingNotClosed)
+    }?;
+
+    let candidate_ids = existing_voting.candidate_ids();
+    if recount
+        .excluded_candidates
+        .iter()
+        .any(|id| !candidate_ids.contains(id))
+    {
+        return Err(ApiError::InvalidInput);
+    }
+
+    let number_of_winners = recount
+        .number_of_winners
+        .unwrap_or(existing_voting.number_of_winners);
+    let decimal_places = recount
+        .decimal_places
+        .unwrap_or(existing_voting.decimal_places);
+
+    // Withdrawn candidates are always left out of the count
+    let mut excluded_candidate_ids = existing_voting.withdrawn_candidate_ids();
+    recount.excluded_candidates.iter().for_each(|id| {
+        if !excluded_candidate_ids.contains(id) {
+            excluded_candidate_ids.push(*id);
+        }
+    });
+
+    // RON cannot fill a seat, so at least one real candidate has to be left in the count
+    if existing_voting
+        .candidates
+        .iter()
+        .all(|c| c.is_ron || excluded_candidate_ids.contains(&c.id))
+    {
+        return Err(ApiError::InvalidInput);
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    // Concurrent recounts would otherwise compute the same next result version
+    lock_voting(
+        &mut tx,
+        existing_voting.id,
+        VotingStateWithoutResults::Closed,
+    )
+    .await?;
+
+    let result = existing_voting
+        .count_votes(
+            &mut tx,
+            number_of_winners,
+            decimal_places,
+            &excluded_candidate_ids,
+        )
+        .await?;
+
+    let version = store_voting_result(
+        &mut tx,
+        existing_voting.id,
+        number_of_winners,
+        &excluded_candidate_ids,
+        &admin,
+        &result,
+    )
+    .await?;
+
+    sqlx::query!(
+        "
+        UPDATE voting
+        SET number_of_winners = $2, decimal_places = $3, revealed_rounds = 0
+        WHERE id = $1
+        ",
+        existing_voting.id,
+        number_of_winners,
+        decimal_places,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::RecountVoting,
+        Some(format!("voting:{}", existing_voting.id)),
+        Some(&json!({
+            "numberOfWinners": existing_voting.number_of_winners,
+            "decimalPlaces": existing_voting.decimal_places,
+        })),
+        Some(&json!({
+            "version": version,
+            "numberOfWinners": number_of_winners,
+            "decimalPlaces": decimal_places,
+            "excludedCandidates": excluded_candidate_ids,
+            "winners": result.winners,
+        })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    state.notify_presentations(PresentationEvent::Voting(existing_voting.id));
+
+    let versions = get_voting_results(&state.db, existing_voting.id).await?;
+    versions
+        .into_iter()
+        .find(|v| v.version == version)
+        .map(Json)
+        .ok_or(ApiError::InternalServerError)
+}
+
+/// Locks the voting row until the end of the transaction and checks that the voting is still
+/// in the expected state.
+/// Key of the advisory lock taken by [`lock_agenda`]
+const AGENDA_LOCK_KEY: i64 = 0x6167656e6461;
+
+/// Serializes the changes to the agenda positions until the end of the transaction, so that
+/// concurrent creations cannot get the same next position. Taken before any voting row is
+/// locked, to keep the lock order the same everywhere.
+async fn lock_agenda(tx: &mut Transaction<'_, Postgres>) -> ApiResult<()> {
+    sqlx::query!("SELECT pg_advisory_xact_lock($1)", AGENDA_LOCK_KEY)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+async fn lock_voting(
+    tx: &mut Transaction<'_, Postgres>,
+    id: VotingId,
+    expected_state: VotingStateWithoutResults,
+) -> ApiResult<()> {
+    let current_state = sqlx::query!(
+        "SELECT state AS \"state: VotingStateWithoutResults\" FROM voting WHERE id = $1 FOR UPDATE",
+        id
+    )
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or(ApiError::VotingNotFound)?
+    .state;
+
+    match (current_state, expected_state) {
+        (state, expected) if state == expected => Ok(()),
+        (VotingStateWithoutResults::Closed, _) => Err(ApiError::VotingAlreadyClosed),
+        (_, VotingStateWithoutResults::Closed) => Err(ApiError::VotingNotClosed),
+        _ => Err(ApiError::InvalidInput),
+    }
+}
+
+#[derive(Template)]
+#[template(path = "pages/voting-results.html")]
+struct VotingResultsTemplate {
+    login_state: LoginState,
+    locale: Locale,
+    voting: Voting,
+    versions: Vec<VotingResultVersion>,
+    public_ballots: Vec<PublicBallot>,
+}
+
+impl VotingResultsTemplate {
+    fn vote_transfer_chart(&self, result: &VotingResult) -> Option<VoteTransferChart> {
+        vote_transfer_chart(&self.voting, result, self.locale)
+    }
+}
+
+#[debug_handler]
+async fn get_voting_results_page(
+    ctx: Ctx,
+    voting: Voting,
+    state: State<AppState>,
+    _id: Path<VotingId>,
+) -> ApiResult<Html<String>> {
+    let versions = get_voting_results(&state.db, voting.id).await?;
+    let public_ballots = get_public_ballots(&state.db, voting.id).await?;
+
+    VotingResultsTemplate {
+        login_state: ctx.login_state(),
+        locale: ctx.locale(),
+        voting,
+        versions,
+        public_ballots,
+    }
+    .render()
+    .map(Html)
+    .map_err(ApiError::TemplatingError)
+}
+
 #[debug_handler]
 pub async fn get_votings(ctx: Ctx, state: State<AppState>) -> ApiResult<Html<String>> {
     match ctx.login_state() {
         LoginState::NotLoggedIn => todo!(),
-        LoginState::Voter { .. } => {
-            get_votings_list_template(state.db.clone(), ctx.login_state(), None)
+        LoginState::Voter { .. } => get_votings_list_template(
+            state.db.clone(),
+            &state.config.hmac_key,
+            ctx.login_state(),
+            ctx.locale(),
+            None,
+        )
+        .await?
+        .render()
+        .map(Html)
+        .map_err(|_| ApiError::InternalServerError),
+        LoginState::Admin(_) => {
+            get_admin_votings_list_template(state.db.clone(), ctx.login_state(), ctx.locale())
                 .await?
                 .render()
-                .map(|html| Html(html))
+                .map(Html)
                 .map_err(|_| ApiError::InternalServerError)
         }
-        LoginState::Admin => get_admin_votings_list_template(state.db.clone(), ctx.login_state())
-            .await?
-            .render()
-            .map(|html| Html(html))
-            .map_err(|_| ApiError::InternalServerError),
     }
 }
 
@@ -189,8 +716,9 @@ impl Voting {
         &self,
         db: Pool<Postgres>,
         voting_update: VotingUpdate,
+        admin: &AdminAccount,
     ) -> ApiResult<Voting> {
-        println!("handle patch for voting {}", self.id);
+        tracing::debug!(voting_id = self.id, "updating voting");
         match (
             &self.state,
             voting_update
@@ -199,9 +727,9 @@ impl Voting {
         ) {
             (VotingState::Closed { .. }, _) => Err(ApiError::VotingAlreadyClosed),
             (_, VotingStateWithoutResults::Closed) => {
-                self.try_close_voting(db, voting_update).await
+                self.try_close_voting(db, voting_update, admin).await
             }
-            (_, _) => self.try_modify_and_reset_votes(db, voting_update).await,
+            (_, _) => self.try_modify_voting(db, voting_update, admin).await,
         }
     }
 
@@ -209,6 +737,7 @@ impl Voting {
         &self,
         db: Pool<Postgres>,
         voting_update: VotingUpdate,
+        admin: &AdminAccount,
     ) -> ApiResult<Voting> {
         if self.state != VotingStateWithoutResults::Open {
             return Err(ApiError::InvalidInput);
@@ -231,6 +760,9 @@ impl Voting {
 
         let mut tx = db.begin().await?;
 
+        // A concurrent close or recount must not store a result version of its own
+        lock_voting(&mut tx, self.id, VotingStateWithoutResults::Open).await?;
+
         let count_of_active_tokens_that_have_not_voted = sqlx::query!(
             "
             WITH activated_tokens_without_vote AS (
@@ -238,6 +770,7 @@ impl Voting {
                 FROM token
                 WHERE
                     state = 'activated'::token_state
+                    AND token_is_eligible($1, id)
                     AND token NOT IN (
                         SELECT token_token AS token
                         FROM has_voted
@@ -257,9 +790,73 @@ impl Voting {
             return Err(ApiError::NotAllActiveTokensHaveVoted);
         }
 
+        let excluded_candidate_ids = self.withdrawn_candidate_ids();
+
+        let result = self
+            .count_votes(
+                &mut tx,
+                self.number_of_winners,
+                self.decimal_places,
+                &excluded_candidate_ids,
+            )
+            .await?;
+
+        store_voting_result(
+            &mut tx,
+            self.id,
+            self.number_of_winners,
+            &excluded_candidate_ids,
+            admin,
+            &result,
+        )
+        .await?;
+
+        let updated_voting = sqlx::query_as!(
+            VotingStateResult,
+            "
+                UPDATE voting
+                SET state = 'closed'::voting_state, revealed_rounds = 0
+                WHERE id = $1
+                returning state AS \"state: VotingStateWithoutResults\";
+                ",
+            self.id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        clone.state = VotingState::from(updated_voting.state);
+
+        // Ballots can no longer be replaced, so the links to the voters are not needed anymore
+        sqlx::query!("DELETE FROM ballot_pseudonym WHERE voting_id = $1", self.id)
+            .execute(&mut *tx)
+            .await?;
+
+        log_admin_event(
+            &mut *tx,
+            admin,
+            AdminAction::CloseVoting,
+            Some(format!("voting:{}", self.id)),
+            Some(self),
+            Some(&clone),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(clone)
+    }
+
+    /// Counts the stored ballots of the voting with the excluded candidates left out.
+    pub(crate) async fn count_votes(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        number_of_winners: i32,
+        decimal_places: Option<i32>,
+        excluded_candidate_ids: &[CandidateId],
+    ) -> ApiResult<VotingResult> {
         let votes = sqlx::query!(
             "
-            SELECT COALESCE(NULLIF(ARRAY_AGG(candidate_name ORDER BY rank), '{NULL}'), '{}') AS \"vote!: Vec<CandidateId>\"
+            SELECT COALESCE(NULLIF(ARRAY_AGG(candidate_id ORDER BY rank), '{NULL}'), '{}') AS \"vote!: Vec<CandidateId>\"
             FROM vote
             WHERE voting_id = $1
             GROUP BY id
@@ -267,151 +864,123 @@ impl Voting {
             self.id,
         ).map(|row| {
             row.vote
-        }).fetch_all(&mut *tx).await?;
+        }).fetch_all(&mut **tx).await?;
 
-        let number_of_winners: usize = self
-            .number_of_winners
+        let number_of_winners: usize = number_of_winners
             .try_into()
             .map_err(|_| ApiError::InternalServerError)?;
 
-        let round_results =
-            calculate_stv_result(self.candidates.clone(), votes, number_of_winners)?.round_results;
-
-        let mut winning_candidates = vec![];
-        let mut passing_candidates = vec![];
-        let mut dropped_candidates = vec![];
-
-        let round_results_references = round_results.iter().collect::<Vec<_>>();
-        round_results.iter().for_each(|r| {
-            r.candidate_results.iter().for_each(|c| {
-                if c.is_selected {
-                    winning_candidates.push((&c.data, r.round));
-                } else {
-                    passing_candidates.push((&c.data, r.round));
-                }
-            });
+        let (candidates, votes) =
+            exclude_candidates(self.candidate_ids(), votes, excluded_candidate_ids);
 
-            r.dropped_candidate.iter().for_each(|c| {
-                dropped_candidates.push((c, r.round));
-            });
-        });
-
-        QueryBuilder::new("INSERT INTO voting_round_result (voting_id, round)")
-            .push_values(round_results_references.clone(), |mut b, res| {
-                b.push_bind(self.id).push_bind(res.round);
-            })
-            .build()
-            .execute(&mut *tx)
-            .await?;
-
-        let all_candidate_data = [
-            winning_candidates.clone(),
-            passing_candidates.clone(),
-            dropped_candidates,
-        ]
-        .concat();
+        calculate_stv_result(candidates, votes, number_of_winners, decimal_places)
+    }
 
-        QueryBuilder::new(
-            "INSERT INTO candidate_result_data (name, round, voting_id, vote_count, is_draw)",
-        )
-        .push_values(all_candidate_data, |mut b, (result, round)| {
-            b.push_bind(&result.name)
-                .push_bind(round)
-                .push_bind(self.id)
-                .push_bind(result.vote_count)
-                .push_bind(result.is_draw);
-        })
-        .build()
-        .execute(&mut *tx)
-        .await?;
+    async fn reset_votes(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        admin: &AdminAccount,
+    ) -> ApiResult<()> {
+        let deleted_ballots = sqlx::query!("DELETE FROM vote WHERE voting_id = $1", self.id)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
 
-        if winning_candidates.len() > 0 {
-            QueryBuilder::new(
-                "INSERT INTO passing_candidate_result (name, round, voting_id, is_selected)",
-            )
-            .push_values(winning_candidates, |mut b, (result, round)| {
-                b.push_bind(&result.name)
-                    .push_bind(round)
-                    .push_bind(self.id)
-                    .push_bind(true);
-            })
-            .build()
-            .execute(&mut *tx)
+        sqlx::query!("DELETE FROM ballot_voter WHERE voting_id = $1", self.id)
+            .execute(&mut **tx)
             .await?;
-        }
 
-        if passing_candidates.len() > 0 {
-            QueryBuilder::new(
-                "INSERT INTO passing_candidate_result (name, round, voting_id, is_selected)",
-            )
-            .push_values(passing_candidates, |mut b, (result, round)| {
-                b.push_bind(&result.name)
-                    .push_bind(round)
-                    .push_bind(self.id)
-                    .push_bind(false);
-            })
-            .build()
-            .execute(&mut *tx)
+        sqlx::query!("DELETE FROM ballot_pseudonym WHERE voting_id = $1", self.id)
+            .execute(&mut **tx)
             .await?;
-        }
 
-        let updated_voting = sqlx::query_as!(
-            VotingStateResult,
-            "
-                UPDATE voting
-                SET state = 'closed'::voting_state
-                WHERE id = $1
-                returning state AS \"state: VotingStateWithoutResults\";
-                ",
-            self.id
+        let deleted_voters = sqlx::query!("DELETE FROM has_voted WHERE voting_id = $1", self.id)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+
+        log_admin_event(
+            &mut **tx,
+            admin,
+            AdminAction::ResetVotes,
+            Some(format!("voting:{}", self.id)),
+            None::<&()>,
+            Some(&json!({
+                "deletedBallots": deleted_ballots,
+                "deletedVoters": deleted_voters,
+            })),
         )
-        .fetch_one(&mut *tx)
-        .await?;
-
-        tx.commit().await?;
-
-        clone.state = VotingState::from(updated_voting.state);
-        return Ok(clone);
+        .await
     }
 
-    async fn try_modify_and_reset_votes(
+    async fn try_modify_voting(
         &self,
         db: Pool<Postgres>,
         voting_update: VotingUpdate,
+        admin: &AdminAccount,
     ) -> ApiResult<Voting> {
-        let voting_state = voting_update.state.unwrap_or(self.state.clone().into());
-        match voting_state {
-            VotingStateWithoutResults::Open => {
-                if voting_update
-                    .candidates
-                    .as_ref()
-                    .unwrap_or(&self.candidates)
-                    .is_empty()
-                {
-                    Err(ApiError::InvalidInput)
-                } else {
-                    Ok(())
-                }
-            }
-            _ => Ok(()),
-        }?;
+        voting_update
+            .validate()
+            .map_err(|_| ApiError::InvalidInput)?;
 
         let mut tx = db.begin().await?;
 
-        sqlx::query!("DELETE FROM candidate WHERE voting_id = $1", self.id)
-            .execute(&mut *tx)
-            .await?;
+        // The voting was read before the transaction, so make sure that a concurrent close or
+        // vote reset has not changed its state in between
+        lock_voting(&mut tx, self.id, self.state.clone().into()).await?;
 
-        sqlx::query!("DELETE FROM has_voted WHERE voting_id = $1", self.id)
-            .execute(&mut *tx)
-            .await?;
+        let voting_state = voting_update.state.unwrap_or(self.state.clone().into());
 
-        let candidates = insert_candidates_into_db(
-            self.id,
-            voting_update.candidates.unwrap_or(self.candidates.clone()),
-            &mut *tx,
-        )
-        .await?;
+        // Nominations are opened before the voting, not in the middle of it
+        if voting_state == VotingStateWithoutResults::Nominating
+            && !matches!(self.state, VotingState::Draft | VotingState::Nominating)
+        {
+            return Err(ApiError::InvalidInput);
+        }
+
+        // Who may vote is settled before the voting opens
+        if self.state == VotingStateWithoutResults::Open
+            && voting_update
+                .eligible_groups
+                .as_ref()
+                .is_some_and(|g| !self.has_eligible_groups(g))
+        {
+            return Err(ApiError::InvalidInput);
+        }
+
+        let affects_ballots = self.state == VotingStateWithoutResults::Open
+            && self.update_affects_ballots(&voting_update);
+
+        if affects_ballots && !voting_update.reset_votes {
+            return Err(ApiError::VoteResetNotConfirmed);
+        }
+
+        if voting_update.reset_votes && self.state == VotingStateWithoutResults::Open {
+            self.reset_votes(&mut tx, admin).await?;
+        }
+
+        if let Some(candidates) = voting_update.candidates {
+            save_candidates(&mut tx, self.id, &self.candidates, candidates).await?;
+        }
+
+        if let Some(include_ron) = voting_update.include_ron {
+            save_ron(&mut tx, self.id, include_ron).await?;
+        }
+
+        if let Some(group_ids) = &voting_update.eligible_groups {
+            save_eligible_groups(&mut tx, self.id, group_ids).await?;
+        }
+
+        if self.is_nominating() && voting_state == VotingStateWithoutResults::Open {
+            add_approved_nominees(&mut tx, self.id).await?;
+        }
+
+        let candidates = get_candidates(&mut *tx, self.id).await?;
+        let eligible_groups = get_eligible_groups(&mut *tx, self.id).await?;
+
+        if voting_state == VotingStateWithoutResults::Open && candidates.iter().all(|c| c.is_ron) {
+            return Err(ApiError::InvalidInput);
+        }
 
         let voting = sqlx::query!(
             "
@@ -421,7 +990,11 @@ impl Voting {
                 description = COALESCE($3, description),
                 state = COALESCE($4, state),
                 hide_vote_counts = COALESCE($5, hide_vote_counts),
-                number_of_winners = COALESCE($6, number_of_winners)
+                number_of_winners = COALESCE($6, number_of_winners),
+                is_public_ballot = COALESCE($7, is_public_ballot),
+                allow_revote = COALESCE($8, allow_revote),
+                decimal_places = CASE WHEN $9 THEN $10 ELSE decimal_places END,
+                agenda_group = CASE WHEN $11 THEN $12 ELSE agenda_group END
             WHERE id = $1
             RETURNING
                 id,
@@ -430,7 +1003,12 @@ impl Voting {
                 state AS \"state: VotingStateWithoutResults\",
                 created_at,
                 hide_vote_counts,
-                number_of_winners;
+                is_public_ballot,
+                allow_revote,
+                number_of_winners,
+                decimal_places,
+                agenda_position,
+                agenda_group;
             ",
             self.id,
             voting_update.name,
@@ -438,6 +1016,12 @@ impl Voting {
             voting_update.state as Option<VotingStateWithoutResults>,
             voting_update.hide_vote_counts,
             voting_update.number_of_winners,
+            voting_update.is_public_ballot,
+            voting_update.allow_revote,
+            voting_update.decimal_places.is_some(),
+            voting_update.decimal_places.flatten(),
+            voting_update.agenda_group.is_some(),
+            voting_update.agenda_group.flatten(),
         )
         .map(|row| Voting {
             id: row.id,
@@ -446,18 +1030,99 @@ impl Voting {
             state: VotingState::from(row.state),
             created_at: row.created_at,
             hide_vote_counts: row.hide_vote_counts,
+            is_public_ballot: row.is_public_ballot,
+            allow_revote: row.allow_revote,
             number_of_winners: row.number_of_winners,
+            decimal_places: row.decimal_places,
+            agenda_position: row.agenda_position,
+            agenda_group: row.agenda_group,
+            eligible_groups: eligible_groups.clone(),
             candidates: candidates.clone(),
         })
         .fetch_one(&mut *tx)
         .await?;
 
+        log_admin_event(
+            &mut *tx,
+            admin,
+            AdminAction::UpdateVoting,
+            Some(format!("voting:{}", self.id)),
+            Some(self),
+            Some(&voting),
+        )
+        .await?;
+
         tx.commit().await?;
 
         Ok(voting)
     }
 }
 
+/// Moves votings on the agenda, see [`VotingReorder`].
+#[debug_handler]
+async fn reorder_votings(
+    admin: AdminAccount,
+    state: State<AppState>,
+    Json(reorder): Json<VotingReorder>,
+) -> ApiResult<StatusCode> {
+    let unique_ids = reorder.voting_ids.iter().collect::<HashSet<_>>();
+    if reorder.voting_ids.is_empty() || unique_ids.len() != reorder.voting_ids.len() {
+        return Err(ApiError::InvalidInput);
+    }
+
+    let mut tx = state.db.begin().await?;
+    lock_agenda(&mut tx).await?;
+
+    let before = sqlx::query!(
+        "
+        SELECT id, agenda_position
+        FROM voting
+        WHERE id = ANY($1)
+        ORDER BY agenda_position, id
+        FOR UPDATE
+        ",
+        &reorder.voting_ids
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if before.len() != reorder.voting_ids.len() {
+        return Err(ApiError::VotingNotFound);
+    }
+
+    let positions = before
+        .iter()
+        .map(|row| row.agenda_position)
+        .collect::<Vec<_>>();
+
+    sqlx::query!(
+        "
+        UPDATE voting
+        SET agenda_position = new_order.agenda_position
+        FROM UNNEST($1::int[], $2::int[]) AS new_order (id, agenda_position)
+        WHERE voting.id = new_order.id
+        ",
+        &reorder.voting_ids,
+        &positions
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::ReorderVotings,
+        None,
+        Some(&json!({ "votingIds": before.iter().map(|row| row.id).collect::<Vec<_>>() })),
+        Some(&json!({ "votingIds": reorder.voting_ids })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 #[derive(Serialize)]
 struct DeletedRowsCount {
     count: i64,
@@ -466,15 +1131,20 @@ struct DeletedRowsCount {
 #[debug_handler]
 async fn delete_voting(
     existing_voting: Voting,
+    admin: AdminAccount,
     state: State<AppState>,
     id: Path<VotingId>,
 ) -> ApiResult<()> {
     match existing_voting.state {
         VotingState::Draft => Ok(()),
+        VotingState::Nominating => Ok(()),
         VotingState::Open => Ok(()),
         _ => Err(ApiError::VotingAlreadyClosed),
     }?;
 
+    let mut tx = state.db.begin().await?;
+    lock_agenda(&mut tx).await?;
+
     let query_result = sqlx::query_as!(
         DeletedRowsCount,
         "
@@ -488,7 +1158,7 @@ async fn delete_voting(
         ",
         id.0
     )
-    .fetch_one(&state.db)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|_| ApiError::VotingNotFound)?;
 
@@ -496,13 +1166,48 @@ async fn delete_voting(
         ..=-1 => Err(ApiError::InternalServerError),
         0 => Err(ApiError::VotingNotFound),
         1.. => Ok(()),
-    }
+    }?;
+
+    // Keep the agenda numbering without gaps
+    sqlx::query!(
+        "
+        UPDATE voting
+        SET agenda_position = agenda_position - 1
+        WHERE agenda_position > $1
+        ",
+        existing_voting.agenda_position
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::DeleteVoting,
+        Some(format!("voting:{}", existing_voting.id)),
+        Some(&existing_voting),
+        None::<&()>,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    state.notify_presentations(PresentationEvent::Voting(existing_voting.id));
+
+    Ok(())
 }
 
 #[debug_handler]
-async fn delete_all_data(state: State<AppState>) -> ApiResult<Json<DeletedRowsCount>> {
+async fn delete_all_data(
+    admin: AdminAccount,
+    state: State<AppState>,
+) -> ApiResult<Json<DeletedRowsCount>> {
     let mut tx = state.db.begin().await?;
 
+    sqlx::query!("DELETE FROM vote_transfer")
+        .execute(&mut *tx)
+        .await?;
+
     sqlx::query!("DELETE FROM passing_candidate_result")
         .execute(&mut *tx)
         .await?;
@@ -515,21 +1220,30 @@ async fn delete_all_data(state: State<AppState>) -> ApiResult<Json<DeletedRowsCo
         .execute(&mut *tx)
         .await?;
 
-    sqlx::query!("DELETE FROM has_voted")
+    sqlx::query!("DELETE FROM voting_result")
         .execute(&mut *tx)
         .await?;
 
-    sqlx::query!("DELETE FROM vote")
+    sqlx::query!("DELETE FROM ballot_voter")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM ballot_pseudonym")
         .execute(&mut *tx)
         .await?;
+    sqlx::query!("DELETE FROM has_voted")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query!("DELETE FROM vote").execute(&mut *tx).await?;
 
     sqlx::query!("DELETE FROM candidate")
         .execute(&mut *tx)
         .await?;
 
-    sqlx::query!("DELETE FROM voting")
+    let deleted_voting_count = sqlx::query!("DELETE FROM voting")
         .execute(&mut *tx)
-        .await?;
+        .await?
+        .rows_affected();
 
     let result = sqlx::query_as!(
         DeletedRowsCount,
@@ -545,6 +1259,19 @@ async fn delete_all_data(state: State<AppState>) -> ApiResult<Json<DeletedRowsCo
     .fetch_one(&mut *tx)
     .await?;
 
+    log_admin_event(
+        &mut *tx,
+        &admin,
+        AdminAction::DeleteAllData,
+        None,
+        None::<&()>,
+        Some(&json!({
+            "deletedVotingCount": deleted_voting_count,
+            "deletedTokenCount": result.count,
+        })),
+    )
+    .await?;
+
     tx.commit().await?;
 
     Ok(Json(result))
@@ -552,12 +1279,17 @@ async fn delete_all_data(state: State<AppState>) -> ApiResult<Json<DeletedRowsCo
 
 #[derive(Template)]
 #[template(path = "components/voting-list.html")]
-
 pub struct VotingListTemplate {
     pub open_votings: Vec<VotingForVoterTemplate>,
     pub draft_votings: Vec<Voting>,
     pub closed_votings: Vec<Voting>,
+    pub public_ballots: HashMap<VotingId, Vec<PublicBallot>>,
+    /// Approved nominations of the votings whose nominations are open
+    pub nominations: HashMap<VotingId, Vec<Nomination>>,
+    /// Only listed to admins, who choose the eligible groups of draft votings
+    pub voter_groups: Vec<VoterGroupTokens>,
     pub login_state: LoginState,
+    pub locale: Locale,
     pub newly_created_vote_uuids: Option<Vec<String>>,
 }
 
@@ -565,6 +1297,7 @@ struct VotingData {
     open_votings: Vec<VotingForVoterTemplate>,
     closed_votings: Vec<Voting>,
     draft_votings: Vec<Voting>,
+    public_ballots: HashMap<VotingId, Vec<PublicBallot>>,
 }
 async fn get_voting_data(
     db: Pool<Postgres>,
@@ -576,18 +1309,24 @@ async fn get_voting_data(
     };
     let rows = sqlx::query!(
         "
-        with passing_candidate_result_data AS (
+        with latest_results AS (
+            SELECT voting_id, MAX(version) AS version
+            FROM voting_result
+            GROUP BY voting_id
+        ),
+        passing_candidate_result_data AS (
             SELECT p.*, c.vote_count, c.is_draw
             FROM passing_candidate_result as p INNER JOIN candidate_result_data as c
                 ON p.voting_id = c.voting_id
-                AND p.name = c.name
+                AND p.version = c.version
+                AND p.candidate_id = c.candidate_id
                 AND p.round = c.round
         ),
         dropped_candidates AS (
-            SELECT name, round, voting_id, vote_count, is_draw
+            SELECT candidate_id, round, voting_id, version, vote_count, is_draw
             FROM candidate_result_data
-            WHERE (voting_id, round, name) NOT IN (
-                SELECT voting_id, round, name
+            WHERE (voting_id, version, round, candidate_id) NOT IN (
+                SELECT voting_id, version, round, candidate_id
                 FROM passing_candidate_result
             )
         ),
@@ -600,25 +1339,24 @@ async fn get_voting_data(
             SELECT
                 r.voting_id as voting_id,
                 r.round as round,
-                d.name as dropped_candidate_name,
+                d.candidate_id as dropped_candidate_id,
                 d.vote_count as dropped_candidate_vote_count,
                 d.is_draw as dropped_candidate_is_draw,
-                COALESCE(NULLIF(ARRAY_AGG(p.name), '{NULL}'), '{}') as candidate_names,
+                r.exhausted_weight as exhausted_weight,
+                r.lost_weight as lost_weight,
+                COALESCE(NULLIF(ARRAY_AGG(p.candidate_id), '{NULL}'), '{}') as candidate_ids,
                 COALESCE(NULLIF(ARRAY_AGG(p.is_selected), '{NULL}'), '{}') as candidate_is_selected,
                 COALESCE(NULLIF(ARRAY_AGG(p.vote_count), '{NULL}'), '{}') as candidate_vote_count,
                 COALESCE(NULLIF(ARRAY_AGG(p.is_draw), '{NULL}'), '{}') as candidate_is_draw
             FROM
                 voting_round_result as r
+                INNER JOIN latest_results as l
+                    ON r.voting_id = l.voting_id AND r.version = l.version
                 LEFT JOIN passing_candidate_result_data as p
-                    ON r.voting_id = p.voting_id AND r.round = p.round
+                    ON r.voting_id = p.voting_id AND r.version = p.version AND r.round = p.round
                 LEFT JOIN dropped_candidates as d
-                    ON r.voting_id = d.voting_id AND r.round = d.round
-            GROUP BY (r.voting_id, r.round, d.name, d.vote_count, d.is_draw)
-        ),
-        voting_with_candidates AS (
-            SELECT v.*, COALESCE(NULLIF(ARRAY_AGG(c.name), '{NULL}'), '{}') as candidates
-            FROM voting AS v LEFT JOIN candidate AS c ON v.id = c.voting_id
-            GROUP BY v.id
+                    ON r.voting_id = d.voting_id AND r.version = d.version AND r.round = d.round
+            GROUP BY (r.voting_id, r.round, r.exhausted_weight, r.lost_weight, d.candidate_id, d.vote_count, d.is_draw)
         )
 
         --- The return type of ARRAY_AGG has to be mangled so it returns an empty list. This is not exactly type safe.
@@ -629,64 +1367,79 @@ async fn get_voting_data(
             v.description as \"description!: String\",
             v.created_at as \"created_at!: DateTime<Utc>\",
             v.hide_vote_counts as \"hide_vote_counts!: bool\",
+            v.is_public_ballot as \"is_public_ballot!: bool\",
+            v.allow_revote as \"allow_revote!: bool\",
             v.number_of_winners,
-            v.candidates as \"candidates!: Vec<CandidateId>\",
+            v.decimal_places,
+            v.agenda_position,
+            v.agenda_group,
             r.round as \"round?: i32\",
-            r.dropped_candidate_name as \"dropped_candidate_name?: String\",
+            r.dropped_candidate_id as \"dropped_candidate_id?: CandidateId\",
             r.dropped_candidate_vote_count as \"dropped_candidate_vote_count?: f64\",
             r.dropped_candidate_is_draw as \"dropped_candidate_is_draw?: bool\",
-            r.candidate_names as \"candidate_names?: Vec<CandidateId>\",
+            r.exhausted_weight as \"exhausted_weight?: f64\",
+            r.lost_weight as \"lost_weight?: f64\",
+            r.candidate_ids as \"candidate_ids?: Vec<CandidateId>\",
             r.candidate_is_selected as \"candidate_is_selected?: Vec<bool>\",
             r.candidate_vote_count as \"candidate_vote_count?: Vec<f64>\",
             r.candidate_is_draw as \"candidate_is_draw?: Vec<bool>\",
-            (hv.token_token = $1) as \"you_have_voted?: bool\"
+            (hv.token_token = $1) as \"you_have_voted?: bool\",
+            token_is_eligible(v.id, (SELECT t.id FROM token AS t WHERE t.token = $1)) as \"is_eligible!: bool\"
         FROM
-            voting_with_candidates AS v            
+            voting AS v
             LEFT JOIN round_results AS r ON v.id = r.voting_id
             LEFT JOIN has_voted hv on v.id = hv.voting_id and hv.token_token = $1
         ORDER BY round ASC, candidate_vote_count DESC, v.created_at ASC;
         ", token
         ).fetch_all(&db);
 
+    let mut candidates = get_candidates_of_all_votings(&db).await?;
+    let public_ballots = get_public_ballots_of_closed_votings(&db, None).await?;
+    let mut eligible_groups = get_eligible_groups_of_all_votings(&db).await?;
     let mut votings: HashMap<VotingId, VotingForVoterTemplate> = HashMap::new();
+    let mut ineligible_voting_ids: HashSet<VotingId> = HashSet::new();
 
     let rows = rows.await?;
     //println!("{:#?}", rows);
     rows.into_iter().try_for_each(|rec| {
+        if !rec.is_eligible {
+            ineligible_voting_ids.insert(rec.id);
+        }
         // println!("rec: {:#?}", rec);
         let candidate_results = rec
-            .candidate_names
+            .candidate_ids
             .zip(rec.candidate_is_selected)
             .zip(rec.candidate_vote_count)
             .zip(rec.candidate_is_draw)
-            .map(|(((names, is_selecteds), vote_counts), is_draws)| {
-                names
-                    .into_iter()
-                    .zip(is_selecteds.into_iter())
-                    .zip(vote_counts.into_iter())
-                    .zip(is_draws.into_iter())
-                    .map(
-                        |(((name, is_selected), vote_count), is_draw)| PassingCandidateResult {
+            .map(|(((ids, is_selecteds), vote_counts), is_draws)| {
+                ids.into_iter()
+                    .zip(is_selecteds)
+                    .zip(vote_counts)
+                    .zip(is_draws)
+                    .map(|(((candidate_id, is_selected), vote_count), is_draw)| {
+                        PassingCandidateResult {
                             data: CandidateResultData {
-                                name,
+                                candidate_id,
                                 vote_count,
                                 is_draw,
                             },
                             is_selected,
-                        },
-                    )
+                        }
+                    })
                     .collect::<Vec<PassingCandidateResult>>()
             });
         // println!("candidate_results: {:#?}", candidate_results);
         let dropped_candidate: Option<CandidateResultData> = rec
-            .dropped_candidate_name
+            .dropped_candidate_id
             .zip(rec.dropped_candidate_vote_count)
             .zip(rec.dropped_candidate_is_draw)
-            .map(|((name, vote_count), is_draw)| CandidateResultData {
-                name,
-                vote_count,
-                is_draw,
-            });
+            .map(
+                |((candidate_id, vote_count), is_draw)| CandidateResultData {
+                    candidate_id,
+                    vote_count,
+                    is_draw,
+                },
+            );
         // println!("dropped_candidate: {:#?}", dropped_candidate);
         let round_result: Option<VotingRoundResult> =
             rec.round
@@ -695,6 +1448,11 @@ async fn get_voting_data(
                     round,
                     dropped_candidate,
                     candidate_results: results,
+                    transfers: vec![],
+                    non_transferable: NonTransferableWeight {
+                        exhausted: rec.exhausted_weight.unwrap_or_default(),
+                        lost: rec.lost_weight.unwrap_or_default(),
+                    },
                 });
         // println!("round_result: {:#?}", round_result);
 
@@ -704,6 +1462,8 @@ async fn get_voting_data(
             Some(v) => match (&mut v.state, round_result) {
                 (VotingState::Draft, None) => Ok(()),
                 (VotingState::Draft, Some(_)) => Err(ApiError::CorruptDatabaseError),
+                (VotingState::Nominating, None) => Ok(()),
+                (VotingState::Nominating, Some(_)) => Err(ApiError::CorruptDatabaseError),
                 (VotingState::Open, None) => Ok(()),
                 (VotingState::Open, Some(_)) => Err(ApiError::CorruptDatabaseError),
                 (VotingState::Closed(_), None) => Err(ApiError::CorruptDatabaseError),
@@ -713,7 +1473,7 @@ async fn get_voting_data(
                             .candidate_results
                             .iter()
                             .filter(|c| c.is_selected)
-                            .map(|c| c.data.name.to_owned()),
+                            .map(|c| c.data.candidate_id),
                     );
                     existing_result.round_results.push(result);
                     Ok(())
@@ -727,26 +1487,35 @@ async fn get_voting_data(
                                 .candidate_results
                                 .iter()
                                 .filter(|c| c.is_selected)
-                                .map(|c| c.data.name.to_owned())
+                                .map(|c| c.data.candidate_id)
                                 .collect(),
                             round_results: vec![round_result],
+                            decimal_places: rec.decimal_places,
                         }))
                     }
                     (VotingStateWithoutResults::Open, None) => Ok(VotingState::Open),
                     (VotingStateWithoutResults::Draft, None) => Ok(VotingState::Draft),
+                    (VotingStateWithoutResults::Nominating, None) => Ok(VotingState::Nominating),
                     _ => Err(ApiError::CorruptDatabaseError),
                 }?;
 
                 let voting = VotingForVoterTemplate {
                     id: rec.id,
-                    candidates: rec.candidates,
+                    candidates: candidates.remove(&rec.id).unwrap_or_default(),
                     name: rec.name,
                     description: rec.description,
                     state,
                     created_at: rec.created_at,
                     hide_vote_counts: rec.hide_vote_counts,
+                    is_public_ballot: rec.is_public_ballot,
+                    allow_revote: rec.allow_revote,
                     you_have_voted: rec.you_have_voted.unwrap_or(false),
+                    your_ballot: None,
                     number_of_winners: rec.number_of_winners,
+                    decimal_places: rec.decimal_places,
+                    agenda_position: rec.agenda_position,
+                    agenda_group: rec.agenda_group,
+                    eligible_groups: eligible_groups.remove(&rec.id).unwrap_or_default(),
                 };
 
                 votings.insert(rec.id, voting);
@@ -759,32 +1528,80 @@ async fn get_voting_data(
     let mut open_votings: Vec<VotingForVoterTemplate> = vec![];
     let mut results_votings: Vec<Voting> = vec![];
 
-    votings.values().for_each(|f| match &f.state {
-        VotingState::Draft => draft_votings.push(f.to_owned().into()),
-        VotingState::Open => open_votings.push(f.to_owned()),
-        VotingState::Closed(VotingResult { .. }) => results_votings.push(f.to_owned().into()),
-    });
+    // Voters only see the votings they may vote in, and the results of all votings
+    let is_voter = login_state.is_voter();
+    votings
+        .values()
+        .filter(|f| {
+            !is_voter
+                || !ineligible_voting_ids.contains(&f.id)
+                || matches!(f.state, VotingState::Closed(_))
+        })
+        .for_each(|f| match &f.state {
+            VotingState::Draft | VotingState::Nominating => draft_votings.push(f.to_owned().into()),
+            VotingState::Open => open_votings.push(f.to_owned()),
+            VotingState::Closed(VotingResult { .. }) => results_votings.push(f.to_owned().into()),
+        });
+
+    draft_votings.sort_by_key(|v| (v.agenda_position, v.id));
+    open_votings.sort_by_key(|v| (v.agenda_position, v.id));
+    results_votings.sort_by_key(|v| (v.agenda_position, v.id));
 
     Ok(VotingData {
         open_votings,
         draft_votings,
         closed_votings: results_votings,
+        public_ballots,
     })
 }
 
 pub async fn get_votings_list_template(
     db: Pool<Postgres>,
+    hmac_key: &str,
     login_state: LoginState,
+    locale: Locale,
     newly_created_vote_uuids: Option<Vec<String>>,
 ) -> ApiResult<VotingListTemplate> {
-    let data = get_voting_data(db, &login_state).await?;
+    let mut data = get_voting_data(db.clone(), &login_state).await?;
+
+    // Voters can review the ballot they may still replace
+    if let LoginState::Voter { token, .. } = &login_state {
+        for voting in data
+            .open_votings
+            .iter_mut()
+            .filter(|v| v.allow_revote && v.you_have_voted)
+        {
+            voting.your_ballot = sqlx::query!(
+                "
+                SELECT
+                    COALESCE(
+                        ARRAY_AGG(v.candidate_id ORDER BY v.rank) FILTER (WHERE v.candidate_id IS NOT NULL),
+                        '{}'
+                    ) AS \"candidates!\"
+                FROM ballot_pseudonym AS p
+                    LEFT JOIN vote AS v ON v.voting_id = p.voting_id AND v.id = p.ballot_id
+                WHERE p.voting_id = $1 AND p.pseudonym = $2
+                GROUP BY p.ballot_id
+                ",
+                voting.id,
+                ballot_pseudonym(hmac_key, token, voting.id)
+            )
+            .map(|row| row.candidates)
+            .fetch_optional(&db)
+            .await?;
+        }
+    }
 
     let template = VotingListTemplate {
         open_votings: data.open_votings,
         draft_votings: data.draft_votings,
         closed_votings: data.closed_votings,
+        public_ballots: data.public_ballots,
+        nominations: get_nominations_of_nominating_votings(&db, true).await?,
+        voter_groups: vec![],
         // csrf_token: todo!(),
-        login_state: login_state,
+        login_state,
+        locale,
         newly_created_vote_uuids,
     };
 
@@ -797,16 +1614,21 @@ pub async fn get_votings_list_template(
 #[derive(Validate, Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AdminOpenVoting {
-    pub id: VotingId,           // voting
-    pub name: String,           // voting
-    pub description: String,    // voting
-    pub state: VotingState,     // voting
-    pub hide_vote_counts: bool, // voting
-    pub number_of_winners: i32, // voting
+    pub id: VotingId,                     // voting
+    pub name: String,                     // voting
+    pub description: String,              // voting
+    pub state: VotingState,               // voting
+    pub hide_vote_counts: bool,           // voting
+    pub is_public_ballot: bool,           // voting
+    pub allow_revote: bool,               // voting
+    pub number_of_winners: i32,           // voting
+    pub agenda_position: i32,             // voting
+    pub agenda_group: Option<String>,     // voting
+    pub eligible_groups: Vec<VoterGroup>, // voting_eligible_group
 
     pub total_votes: i32,                         // has_voted
-    pub eligible_token_count: i32,                // live count of activated tokens
-    pub candidates: Vec<CandidateId>,             // candidate
+    pub eligible_token_count: i32,                // live count of activated, eligible tokens
+    pub candidates: Vec<Candidate>,               // candidate
     pub tokens_not_voted: Vec<AdminDisplayToken>, // token (active) join has_voted
 }
 
@@ -817,9 +1639,32 @@ pub struct AdminDraftVoting {
     pub name: String,
     pub description: String,
     pub state: VotingState,
-    pub candidates: Vec<CandidateId>,
+    pub candidates: Vec<Candidate>,
     pub hide_vote_counts: bool,
+    pub is_public_ballot: bool,
+    pub allow_revote: bool,
     pub number_of_winners: i32,
+    pub agenda_position: i32,
+    pub agenda_group: Option<String>,
+    pub eligible_groups: Vec<VoterGroup>,
+}
+
+impl AdminDraftVoting {
+    pub fn is_nominating(&self) -> bool {
+        self.state == VotingStateWithoutResults::Nominating
+    }
+
+    pub fn has_ron(&self) -> bool {
+        self.candidates.iter().any(|c| c.is_ron)
+    }
+
+    pub fn regular_candidates(&self) -> Vec<&Candidate> {
+        self.candidates.iter().filter(|c| !c.is_ron).collect()
+    }
+
+    pub fn is_eligible_group(&self, group_id: &VoterGroupId) -> bool {
+        self.eligible_groups.iter().any(|g| g.id == *group_id)
+    }
 }
 
 #[derive(Validate, Debug, Clone, Deserialize, Serialize)]
@@ -834,12 +1679,19 @@ pub struct AdminVotingListTemplate {
     pub draft_votings: Vec<AdminDraftVoting>,
     pub open_votings: Vec<AdminOpenVoting>,
     pub closed_votings: Vec<Voting>, // ??
+    pub public_ballots: HashMap<VotingId, Vec<PublicBallot>>,
+    /// All nominations of the votings whose nominations are open, to be reviewed
+    pub nominations: HashMap<VotingId, Vec<Nomination>>,
+    pub voting_templates: Vec<VotingTemplate>,
+    pub voter_groups: Vec<VoterGroupTokens>,
     pub login_state: LoginState,
+    pub locale: Locale,
 }
 
 pub async fn get_admin_votings_list_template(
     db: Pool<Postgres>,
     login_state: LoginState,
+    locale: Locale,
 ) -> ApiResult<AdminVotingListTemplate> {
     let rows = sqlx::query!(
         "
@@ -847,8 +1699,10 @@ pub async fn get_admin_votings_list_template(
             select 
                 v.id, 
                 coalesce(nullif(
-                    -- filter all unactivated and voided tokens here
-                    array_agg(row(t.token, t.alias)) filter (where t.state = 'activated'::token_state), 
+                    -- filter all unactivated, voided and ineligible tokens here
+                    array_agg(row(t.token, t.alias)) filter (
+                        where t.state = 'activated'::token_state and token_is_eligible(v.id, t.id)
+                    ), 
                 '{NULL}'), '{}') as unused_tokens
             from voting v 
             cross join token t
@@ -856,6 +1710,16 @@ pub async fn get_admin_votings_list_template(
             where hv.token_token is null
             group by v.id
         ), 
+        e_t as ( -- count of activated tokens that may vote in each voting
+            select
+                v.id,
+                count(t.id) filter (
+                    where t.state = 'activated'::token_state and token_is_eligible(v.id, t.id)
+                ) as eligible_token_count
+            from voting v
+            left join token t on true
+            group by v.id
+        ), 
         t_v as ( -- count of votes for each voting
             select
                 v.id,
@@ -864,50 +1728,45 @@ pub async fn get_admin_votings_list_template(
             left join has_voted hv on v.id = hv.voting_id
             group by v.id
         ),
-        v_c as ( -- votings and their corresponding candidates
+        v_c as (
             select
                 v.id,
                 v.name,
                 v.description,
                 v.state,
                 v.hide_vote_counts,
+                v.is_public_ballot,
+                v.allow_revote,
                 v.number_of_winners,
-                coalesce(nullif(array_agg(c.name), '{null}'), '{}') as candidates
+                v.agenda_position,
+                v.agenda_group
             from voting v
-            left join candidate c on v.id = c.voting_id
-            group by v.id
         )
         select 
             v_c.id,
             v_c.name,
             v_c.description,
             v_c.state as \"voting_state!: VotingStateWithoutResults\",
-            v_c.candidates as \"candidates!: Vec<String>\",
             v_c.hide_vote_counts,
+            v_c.is_public_ballot,
+            v_c.allow_revote,
             v_c.number_of_winners,
+            v_c.agenda_position,
+            v_c.agenda_group,
             COALESCE(u_t.unused_tokens, '{}') as \"unused_tokens!: Vec<(String, Alias)>\",
-            t_v.total_votes
-        from v_c natural join t_v left join u_t
-            on v_c.id = u_t.id;
+            t_v.total_votes,
+            e_t.eligible_token_count as \"eligible_token_count!\"
+        from v_c natural join t_v natural join e_t left join u_t
+            on v_c.id = u_t.id
+        order by v_c.agenda_position, v_c.id;
         "
     )
     .fetch_all(&db)
     .await?;
 
-    let count_of_live_tokens = sqlx::query!(
-        "
-        select
-            count(*)
-        from token 
-        WHERE state = 'activated'::token_state;
-        ",
-    )
-    .fetch_one(&db)
-    .await?
-    .count
-    .ok_or(InternalServerError)? as i32;
-
-    let data = get_voting_data(db, &login_state).await?;
+    let mut candidates = get_candidates_of_all_votings(&db).await?;
+    let mut eligible_groups = get_eligible_groups_of_all_votings(&db).await?;
+    let data = get_voting_data(db.clone(), &login_state).await?;
 
     let mut open_votings: Vec<AdminOpenVoting> = vec![];
     let mut draft_votings: Vec<AdminDraftVoting> = vec![];
@@ -915,25 +1774,36 @@ pub async fn get_admin_votings_list_template(
 
     rows.iter().for_each(|row| match row.voting_state {
         VotingStateWithoutResults::Closed => (),
-        VotingStateWithoutResults::Draft => draft_votings.push(AdminDraftVoting {
-            id: row.id,
-            name: row.name.clone(),
-            description: row.description.clone(),
-            state: row.voting_state.into(),
-            hide_vote_counts: row.hide_vote_counts,
-            number_of_winners: row.number_of_winners,
-            candidates: row.candidates.clone(),
-        }),
+        VotingStateWithoutResults::Draft | VotingStateWithoutResults::Nominating => draft_votings
+            .push(AdminDraftVoting {
+                id: row.id,
+                name: row.name.clone(),
+                description: row.description.clone(),
+                state: row.voting_state.into(),
+                hide_vote_counts: row.hide_vote_counts,
+                is_public_ballot: row.is_public_ballot,
+                allow_revote: row.allow_revote,
+                number_of_winners: row.number_of_winners,
+                agenda_position: row.agenda_position,
+                agenda_group: row.agenda_group.clone(),
+                eligible_groups: eligible_groups.remove(&row.id).unwrap_or_default(),
+                candidates: candidates.remove(&row.id).unwrap_or_default(),
+            }),
         VotingStateWithoutResults::Open => open_votings.push(AdminOpenVoting {
             id: row.id,
             description: row.description.clone(),
             name: row.name.clone(),
             state: row.voting_state.into(),
             hide_vote_counts: row.hide_vote_counts,
+            is_public_ballot: row.is_public_ballot,
+            allow_revote: row.allow_revote,
             number_of_winners: row.number_of_winners,
-            total_votes: row.total_votes.map_or(-1 as i32, |t| t as i32),
-            eligible_token_count: count_of_live_tokens,
-            candidates: row.candidates.clone(),
+            agenda_position: row.agenda_position,
+            agenda_group: row.agenda_group.clone(),
+            eligible_groups: eligible_groups.remove(&row.id).unwrap_or_default(),
+            total_votes: row.total_votes.map_or(-1, |t| t as i32),
+            eligible_token_count: row.eligible_token_count as i32,
+            candidates: candidates.remove(&row.id).unwrap_or_default(),
             tokens_not_voted: row
                 .unused_tokens
                 .iter()
@@ -949,6 +1819,187 @@ pub async fn get_admin_votings_list_template(
         open_votings,
         draft_votings,
         closed_votings,
+        public_ballots: data.public_ballots,
+        nominations: get_nominations_of_nominating_votings(&db, false).await?,
+        voting_templates: get_voting_templates(&db).await?,
+        voter_groups: get_voter_groups(&db).await?,
         login_state,
+        locale,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+    use sqlx::{Pool, Postgres};
+
+    use crate::{http::test_client::TestClient, models::VotingId};
+
+    async fn create_open_voting(admin: &mut TestClient) -> Value {
+        admin
+            .create_voting(json!({
+                "name": "Chair",
+                "description": "",
+                "state": "Open",
+                "hideVoteCounts": false,
+                "numberOfWinners": 1,
+                "candidates": [{ "name": "Alice" }, { "name": "Bob" }],
+            }))
+            .await
+    }
+
+    async fn ballot_count(db: &Pool<Postgres>, voting_id: VotingId) -> i64 {
+        sqlx::query_scalar!(
+            "SELECT count(*) AS \"count!\" FROM has_voted WHERE voting_id = $1",
+            voting_id
+        )
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_metadata_patch_keeps_the_ballots_of_an_open_voting(db: Pool<Postgres>) {
+        let mut admin = TestClient::new(db.clone());
+        admin.login_admin().await;
+        let voting = create_open_voting(&mut admin).await;
+        let voting_id = voting["id"].as_i64().unwrap() as VotingId;
+        let alice = voting["candidates"][0]["id"].as_i64().unwrap() as i32;
+
+        let mut voter = TestClient::new(db.clone());
+        voter.login_voter("voter").await;
+        assert_eq!(voter.vote(voting_id, &[alice]).await, StatusCode::OK);
+
+        let (status, body) = admin
+            .send(
+                Method::PATCH,
+                &format!("/votings/{voting_id}"),
+                Some(json!({ "name": "Chairperson", "description": "Updated" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        assert_eq!(ballot_count(&db, voting_id).await, 1);
+    }
+
+    #[sqlx::test]
+    async fn test_renaming_a_candidate_keeps_the_ballots_of_an_open_voting(db: Pool<Postgres>) {
+        let mut admin = TestClient::new(db.clone());
+        admin.login_admin().await;
+        let voting = create_open_voting(&mut admin).await;
+        let voting_id = voting["id"].as_i64().unwrap() as VotingId;
+        let alice = voting["candidates"][0]["id"].as_i64().unwrap() as i32;
+
+        let mut voter = TestClient::new(db.clone());
+        voter.login_voter("voter").await;
+        assert_eq!(voter.vote(voting_id, &[alice]).await, StatusCode::OK);
+
+        let mut candidates = voting["candidates"].as_array().unwrap().clone();
+        candidates[0]["name"] = json!("Alice Smith");
+
+        let (status, body) = admin
+            .send(
+                Method::PATCH,
+                &format!("/votings/{voting_id}"),
+                Some(json!({ "candidates": candidates })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(ballot_count(&db, voting_id).await, 1);
+    }
+
+    #[sqlx::test]
+    async fn test_adding_a_candidate_to_an_open_voting_requires_confirmation(db: Pool<Postgres>) {
+        let mut admin = TestClient::new(db.clone());
+        admin.login_admin().await;
+        let voting = create_open_voting(&mut admin).await;
+        let voting_id = voting["id"].as_i64().unwrap() as VotingId;
+        let alice = voting["candidates"][0]["id"].as_i64().unwrap() as i32;
+
+        let mut voter = TestClient::new(db.clone());
+        voter.login_voter("voter").await;
+        assert_eq!(voter.vote(voting_id, &[alice]).await, StatusCode::OK);
+
+        let mut candidates = voting["candidates"].as_array().unwrap().clone();
+        candidates.push(json!({ "name": "Carol" }));
+
+        let (status, _) = admin
+            .send(
+                Method::PATCH,
+                &format!("/votings/{voting_id}"),
+                Some(json!({ "candidates": candidates })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(ballot_count(&db, voting_id).await, 1);
+
+        let (status, body) = admin
+            .send(
+                Method::PATCH,
+                &format!("/votings/{voting_id}"),
+                Some(json!({ "candidates": candidates, "resetVotes": true })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(ballot_count(&db, voting_id).await, 0);
+    }
+
+    #[sqlx::test]
+    async fn test_opening_a_voting_promotes_the_approved_nominees(db: Pool<Postgres>) {
+        let mut admin = TestClient::new(db.clone());
+        admin.login_admin().await;
+        let voting = admin
+            .create_voting(json!({
+                "name": "Chair",
+                "description": "",
+                "state": "Nominating",
+                "hideVoteCounts": false,
+                "numberOfWinners": 1,
+                "candidates": [{ "name": "Alice" }],
+            }))
+            .await;
+        let voting_id = voting["id"].as_i64().unwrap() as VotingId;
+
+        let mut voter = TestClient::new(db.clone());
+        voter.login_voter("voter").await;
+        for (name, review) in [("Carol", "Approved"), ("Dave", "Rejected")] {
+            let (status, body) = voter
+                .send(
+                    Method::POST,
+                    "/nominations",
+                    Some(json!({ "votingId": voting_id, "name": name })),
+                )
+                .await;
+            assert_eq!(status, StatusCode::OK, "{body}");
+            let nomination: Value = serde_json::from_str(&body).unwrap();
+
+            let (status, body) = admin
+                .send(
+                    Method::PATCH,
+                    &format!("/nominations/{}", nomination["id"]),
+                    Some(json!({ "state": review })),
+                )
+                .await;
+            assert_eq!(status, StatusCode::OK, "{body}");
+        }
+
+        let (status, body) = admin
+            .send(
+                Method::PATCH,
+                &format!("/votings/{voting_id}"),
+                Some(json!({ "state": "Open" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        let voting: Value = serde_json::from_str(&body).unwrap();
+        let names = voting["candidates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Alice", "Carol"]);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{collections::HashMap, fmt::Display, future::Future, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+/// Cookie that stores the language picked by the user. Set by the language toggle in the
+/// navigation bar and preferred over the `Accept-Language` header.
+pub const LOCALE_COOKIE: &str = "lang";
+
+/// Prefix of the catalog entries that are handed to the scripts of the page.
+const JS_KEY_PREFIX: &str = "js.";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Fi,
+    Sv,
+    En,
+}
+
+tokio::task_local! {
+    static CURRENT_LOCALE: Locale;
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::Fi, Locale::Sv, Locale::En];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Fi => "fi",
+            Locale::Sv => "sv",
+            Locale::En => "en",
+        }
+    }
+
+    /// Name of the language in the language itself, shown in the language toggle.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::Fi => "Suomi",
+            Locale::Sv => "Svenska",
+            Locale::En => "English",
+        }
+    }
+
+    /// Parses a language tag such as `sv` or `sv-FI`. Only the primary subtag is looked at.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split(['-', '_']).next()?;
+        Locale::ALL
+            .into_iter()
+            .find(|l| l.code().eq_ignore_ascii_case(primary))
+    }
+
+    /// Picks the supported language the client prefers the most from an `Accept-Language`
+    /// header, e.g. `sv-FI,sv;q=0.9,en;q=0.8`.
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        header
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let locale = Locale::from_tag(parts.next()?)?;
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (quality > 0.0).then_some((locale, quality))
+            })
+            // The first of equally preferred languages wins
+            .fold(
+                None,
+                |best: Option<(Locale, f32)>, (locale, quality)| match best {
+                    Some((_, best_quality)) if best_quality >= quality => best,
+                    _ => Some((locale, quality)),
+                },
+            )
+            .map(|(locale, _)| locale)
+    }
+
+    /// Locale of the request that is being handled, for code that has no access to the
+    /// request such as the error responses.
+    pub fn current() -> Locale {
+        CURRENT_LOCALE.try_with(|l| *l).unwrap_or_default()
+    }
+
+    /// Runs the handling of a request with `self` as the [`Locale::current`] locale.
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        CURRENT_LOCALE.scope(self, f).await
+    }
+
+    /// Translation of a catalog entry. Entries missing from the catalog fall back to Finnish
+    /// and then to the key itself.
+    pub fn t<'a>(&self, key: &'a str) -> &'a str {
+        catalog(*self)
+            .get(key)
+            .or_else(|| catalog(Locale::Fi).get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// Translation of a catalog entry with its `{}` placeholder replaced by `arg`.
+    pub fn t1(&self, key: &str, arg: impl Display) -> String {
+        self.t(key).replacen("{}", &arg.to_string(), 1)
+    }
+
+    /// The catalog entries used by the scripts as a JSON object, without their `js.` prefix.
+    pub fn js_catalog(&self) -> String {
+        let entries = catalog(Locale::Fi)
+            .keys()
+            .filter_map(|key| {
+                let js_key = key.strip_prefix(JS_KEY_PREFIX)?;
+                Some((js_key, self.t(key)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        // Only our own catalog ends up in the script, but keep it from closing the tag anyway
+        serde_json::to_string(&entries)
+            .unwrap_or_else(|_| "{}".to_string())
+            .replace("</", "<\\/")
+    }
+}
+
+type Catalog = HashMap<String, String>;
+
+fn catalog(locale: Locale) -> &'static Catalog {
+    static CATALOGS: OnceLock<HashMap<Locale, Catalog>> = OnceLock::new();
+
+    &CATALOGS.get_or_init(|| {
+        HashMap::from([
+            (Locale::Fi, parse_catalog(include_str!("locales/fi.json"))),
+            (Locale::Sv, parse_catalog(include_str!("locales/sv.json"))),
+            (Locale::En, parse_catalog(include_str!("locales/en.json"))),
+        ])
+    })[&locale]
+}
+
+fn parse_catalog(json: &str) -> Catalog {
+    serde_json::from_str(json).expect("Translation catalog is not a JSON object of strings")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_catalogs_have_the_same_entries() {
+        let keys = |locale| catalog(locale).keys().collect::<BTreeSet<_>>();
+        let placeholders = |locale, key: &String| catalog(locale)[key].matches("{}").count();
+
+        for locale in [Locale::Sv, Locale::En] {
+            assert_eq!(keys(Locale::Fi), keys(locale), "{:?}", locale);
+
+            keys(Locale::Fi).into_iter().for_each(|key| {
+                assert_eq!(
+                    placeholders(Locale::Fi, key),
+                    placeholders(locale, key),
+                    "{:?} {}",
+                    locale,
+                    key
+                );
+            });
+        }
+    }
+
+    #[test]
+    fn test_accept_language_negotiation() {
+        assert_eq!(
+            Locale::from_accept_language("sv-FI,sv;q=0.9,en;q=0.8"),
+            Some(Locale::Sv)
+        );
+        assert_eq!(
+            Locale::from_accept_language("de-DE,en;q=0.7,fi;q=0.8"),
+            Some(Locale::Fi)
+        );
+        assert_eq!(
+            Locale::from_accept_language("en-US,en;q=0.9"),
+            Some(Locale::En)
+        );
+        assert_eq!(Locale::from_accept_language("fi;q=0,de"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+    }
+}
@@ -3,13 +3,17 @@ mod config;
 mod ctx;
 mod helpers;
 mod http;
+mod i18n;
+mod metrics;
 mod middleware;
 mod models;
 
+use std::sync::{atomic::AtomicBool, Arc};
+
 use dotenv::dotenv;
 use envconfig::Envconfig;
 
-use helpers::create_pg_pool;
+use helpers::{create_pg_pool, init_tracing, prepare_database};
 use http::serve;
 
 use config::Config;
@@ -19,14 +23,19 @@ async fn main() {
     dotenv().ok();
     let config = Config::init_from_env().unwrap();
 
-    let pool = create_pg_pool(&config.database_url, 3)
-        .await
-        .expect("Failed to create connection pool!");
+    init_tracing(&config).expect("Invalid LOG_LEVEL");
+
+    let config = Arc::new(config);
+
+    let pool = create_pg_pool(&config.database_url, 3).expect("Invalid DATABASE_URL");
 
-    sqlx::migrate!()
-        .run(&pool)
-        .await
-        .expect("Running DB migrations failed");
+    // The server starts right away and reports itself ready once the database is prepared
+    let database_ready = Arc::new(AtomicBool::new(false));
+    tokio::spawn(prepare_database(
+        pool.clone(),
+        config.clone(),
+        database_ready.clone(),
+    ));
 
-    serve(pool, config).await;
+    serve(pool, config, database_ready).await;
 }
@@ -0,0 +1,101 @@
+use std::sync::OnceLock;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+/// Collectors of the Prometheus metrics served at `/metrics`. Counters and histograms are
+/// updated as things happen. The gauges are refreshed from the database on every scrape.
+pub struct Metrics {
+    registry: Registry,
+    pub http_requests: IntCounterVec,
+    pub http_request_duration: HistogramVec,
+    pub login_failures: IntCounterVec,
+    pub stv_count_duration: HistogramVec,
+    pub ballots_cast: IntGaugeVec,
+    pub tokens: IntGaugeVec,
+    pub db_pool_connections: IntGaugeVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("vaalikoppi".to_string()), None)
+            .expect("Invalid metrics prefix");
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route and status"),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time spent handling HTTP requests by route",
+            ),
+            &["method", "route"],
+        )
+        .unwrap();
+        let login_failures = IntCounterVec::new(
+            Opts::new("login_failures_total", "Failed voter and admin logins"),
+            &["kind"],
+        )
+        .unwrap();
+        let stv_count_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "stv_count_duration_seconds",
+                "Time spent counting the result of a voting",
+            )
+            .buckets(vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0]),
+            &["arithmetic"],
+        )
+        .unwrap();
+        let ballots_cast = IntGaugeVec::new(
+            Opts::new("ballots_cast", "Voters who have voted in each voting"),
+            &["voting_id", "state"],
+        )
+        .unwrap();
+        let tokens = IntGaugeVec::new(Opts::new("tokens", "Tokens by state"), &["state"]).unwrap();
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Connections of the database pool"),
+            &["state"],
+        )
+        .unwrap();
+
+        for collector in [
+            Box::new(http_requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(http_request_duration.clone()),
+            Box::new(login_failures.clone()),
+            Box::new(stv_count_duration.clone()),
+            Box::new(ballots_cast.clone()),
+            Box::new(tokens.clone()),
+            Box::new(db_pool_connections.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("Registering a metric failed");
+        }
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            login_failures,
+            stv_count_duration,
+            ballots_cast,
+            tokens,
+            db_pool_connections,
+        }
+    }
+
+    /// All metrics in the Prometheus text format.
+    pub fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = vec![];
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
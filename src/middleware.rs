@@ -1,5 +1,12 @@
+pub mod require_database_ready;
 pub mod require_is_admin;
+pub mod require_is_official;
+pub mod require_is_superadmin;
 pub mod require_is_voter;
 pub mod resolve_ctx;
+pub mod resolve_locale;
 pub mod resolve_token;
 pub mod resolve_voting;
+pub mod trace_request;
+pub mod track_metrics;
+pub mod verify_csrf_token;
@@ -0,0 +1,28 @@
+use std::sync::atomic::Ordering;
+
+use axum::{extract::State, http::Request, middleware::Next, response::Response};
+
+use crate::{
+    api_types::{ApiError, ApiResult},
+    http::AppState,
+};
+
+/// Paths served while the database is still being prepared, so that orchestrators can tell
+/// a starting server from a broken one.
+const ALWAYS_SERVED_PATHS: [&str; 2] = ["/healthz", "/readyz"];
+
+/// Answers 503 until the migrations have run and the initial admin exists. Until then the
+/// queries of the handlers could hit a missing table or column.
+pub async fn require_database_ready<B>(
+    State(state): State<AppState>,
+    req: Request<B>,
+    next: Next<B>,
+) -> ApiResult<Response> {
+    if state.database_ready.load(Ordering::Acquire)
+        || ALWAYS_SERVED_PATHS.contains(&req.uri().path())
+    {
+        return Ok(next.run(req).await);
+    }
+
+    Err(ApiError::DatabaseNotReady)
+}
@@ -9,11 +9,10 @@ pub async fn require_is_admin<B>(
     req: Request<B>,
     next: Next<B>,
 ) -> ApiResult<Response> {
-    println!("{:?}", context);
     let state = context.login_state();
 
     match state {
-        crate::models::LoginState::Admin => Ok(next.run(req).await),
+        crate::models::LoginState::Admin(_) => Ok(next.run(req).await),
         _ => Err(ApiError::TokenNotFound),
     }
 }
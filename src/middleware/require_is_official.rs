@@ -0,0 +1,23 @@
+use crate::{
+    api_types::{ApiError, ApiResult, AuthFailedError},
+    ctx::Ctx,
+};
+use axum::{http::Request, middleware::Next, response::Response};
+
+pub async fn require_is_official<B>(
+    context: Ctx,
+    req: Request<B>,
+    next: Next<B>,
+) -> ApiResult<Response> {
+    let state = context.login_state();
+
+    match state {
+        crate::models::LoginState::Admin(admin) if admin.role.can_manage_votings() => {
+            Ok(next.run(req).await)
+        }
+        crate::models::LoginState::Admin(_) => Err(ApiError::AuthFailed(
+            AuthFailedError::InsufficientPermissions,
+        )),
+        _ => Err(ApiError::TokenNotFound),
+    }
+}
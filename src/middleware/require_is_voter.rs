@@ -9,7 +9,6 @@ pub async fn require_is_voter<B>(
     req: Request<B>,
     next: Next<B>,
 ) -> ApiResult<Response> {
-    println!("{:?}", context);
     let state = context.login_state();
 
     match state {
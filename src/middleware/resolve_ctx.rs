@@ -1,27 +1,33 @@
 use crate::{
-    api_types::{
-        ApiResult,
-    },
+    api_types::ApiResult,
     ctx::Ctx,
     http::{
-        login::{JsonWebTokenClaims, AUTH_TOKEN},
+        login::{
+            decode_admin_token, next_admin_session_expiry, set_admin_token_cookie,
+            JsonWebTokenClaims, TOKEN_REFRESH_INTERVAL_MINUTES,
+        },
         user::VOTER_TOKEN,
         AppState,
     },
-    models::{LoginState, Token, TokenState},
+    i18n::Locale,
+    middleware::trace_request::record_role,
+    models::{AdminAccount, AdminRole, LoginState, Token, TokenState},
 };
 use axum::{extract::State, http::Request, middleware::Next, response::Response};
-use jsonwebtoken::{decode, DecodingKey, TokenData, Validation};
+use chrono::Utc;
 use tower_cookies::Cookies;
 
-
-
 pub async fn resolve_ctx<B>(
     cookies: Cookies,
     state: State<AppState>,
     mut req: Request<B>,
     next: Next<B>,
 ) -> ApiResult<Response> {
+    let locale = req
+        .extensions()
+        .get::<Locale>()
+        .copied()
+        .unwrap_or_default();
     let voter_token = cookies.get(VOTER_TOKEN).map(|c| c.value().to_string());
 
     // Check if valid voter token is found.
@@ -48,41 +54,131 @@ pub async fn resolve_ctx<B>(
         None => Ok(None),
     }?;
 
-    if let Some(token) = resolved_voter_token {
-        match (token.state, token.alias) {
-            (TokenState::Activated, Some(alias)) => {
-                let ctx = Ctx::new(LoginState::Voter {
-                    token: token.token,
-                    alias,
-                });
-                req.extensions_mut().insert(ctx);
-                return Ok(next.run(req).await);
-            }
-            _ => (),
-        }
+    if let Some(Token {
+        token,
+        state: TokenState::Activated,
+        alias: Some(alias),
+        ..
+    }) = resolved_voter_token
+    {
+        let ctx = Ctx::new(LoginState::Voter { token, alias }, locale);
+        record_role(ctx.login_state().role());
+        req.extensions_mut().insert(ctx);
+        return Ok(next.run(req).await);
     }
 
-    let admin_token = cookies.get(AUTH_TOKEN).map(|c| c.value().to_string());
-
     // Check if valid admin token is found
-    let resolved_admin_token: Option<TokenData<JsonWebTokenClaims>> = admin_token
-        .map(|t| {
-            decode::<JsonWebTokenClaims>(
-                &t,
-                &DecodingKey::from_secret(state.config.hmac_key.as_bytes()),
-                &Validation::default(),
+    let admin_claims = decode_admin_token(&cookies, &state.config.hmac_key);
+
+    // The session and account are looked up on every request so that revoked sessions,
+    // deleted accounts and role changes take effect immediately
+    let resolved_admin = match &admin_claims {
+        Some(claims) => {
+            sqlx::query_as!(
+                AdminAccount,
+                "
+                SELECT
+                    admin_account.id,
+                    admin_account.username,
+                    admin_account.role AS \"role: AdminRole\"
+                FROM admin_session
+                JOIN admin_account ON admin_account.id = admin_session.admin_id
+                WHERE
+                    admin_session.id = $1
+                    AND admin_session.admin_id = $2
+                    AND admin_session.revoked_at IS NULL
+                    AND admin_session.expires_at > $3
+                ",
+                claims.jti,
+                claims.sub,
+                Utc::now(),
             )
-            .ok()
-        })
-        .flatten();
+            .fetch_optional(&state.db)
+            .await
+        }
+        None => Ok(None),
+    }?;
 
-    if let Some(_token_data) = resolved_admin_token {
-        let ctx = Ctx::new(LoginState::Admin);
+    if let (Some(admin), Some(claims)) = (&resolved_admin, &admin_claims) {
+        refresh_admin_session(&state, &cookies, claims).await?;
+
+        let ctx = Ctx::new(LoginState::Admin(admin.clone()), locale);
+        record_role(ctx.login_state().role());
         req.extensions_mut().insert(ctx);
         return Ok(next.run(req).await);
     }
 
-    let ctx: Ctx = Ctx::new(LoginState::NotLoggedIn);
+    let ctx: Ctx = Ctx::new(LoginState::NotLoggedIn, locale);
+    record_role(ctx.login_state().role());
     req.extensions_mut().insert(ctx);
     Ok(next.run(req).await)
 }
+
+/// Reissues the admin token once it is older than the refresh interval so that active
+/// sessions stay logged in while idle ones expire.
+async fn refresh_admin_session(
+    state: &AppState,
+    cookies: &Cookies,
+    claims: &JsonWebTokenClaims,
+) -> ApiResult<()> {
+    let now = Utc::now();
+
+    if now.timestamp() - claims.iat < TOKEN_REFRESH_INTERVAL_MINUTES * 60 {
+        return Ok(());
+    }
+
+    let expires_at = next_admin_session_expiry(now);
+
+    sqlx::query!(
+        "UPDATE admin_session SET expires_at = $2 WHERE id = $1",
+        claims.jti,
+        expires_at,
+    )
+    .execute(&state.db)
+    .await?;
+
+    set_admin_token_cookie(
+        cookies,
+        &state.config.hmac_key,
+        claims.sub,
+        claims.jti,
+        now,
+        expires_at,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use sqlx::{Pool, Postgres};
+
+    use crate::http::test_client::TestClient;
+
+    #[sqlx::test]
+    async fn test_revoked_admin_session_is_rejected(db: Pool<Postgres>) {
+        let mut client = TestClient::new(db.clone());
+        client.login_admin().await;
+        assert_eq!(client.get("/metrics").await.0, StatusCode::OK);
+
+        sqlx::query!("UPDATE admin_session SET revoked_at = now()")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        assert_ne!(client.get("/metrics").await.0, StatusCode::OK);
+    }
+
+    #[sqlx::test]
+    async fn test_expired_admin_session_is_rejected(db: Pool<Postgres>) {
+        let mut client = TestClient::new(db.clone());
+        client.login_admin().await;
+        assert_eq!(client.get("/metrics").await.0, StatusCode::OK);
+
+        sqlx::query!("UPDATE admin_session SET expires_at = now() - interval '1 minute'")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        assert_ne!(client.get("/metrics").await.0, StatusCode::OK);
+    }
+}
@@ -0,0 +1,28 @@
+use axum::{
+    http::{header::ACCEPT_LANGUAGE, Request},
+    middleware::Next,
+    response::Response,
+};
+use tower_cookies::Cookies;
+
+use crate::i18n::{Locale, LOCALE_COOKIE};
+
+/// Picks the language of the response: the one chosen with the language toggle, then the one
+/// the browser prefers and Finnish as the last resort. The locale is stored in the request
+/// extensions for the templates and made current for the whole request so that errors are
+/// translated too.
+pub async fn resolve_locale<B>(cookies: Cookies, mut req: Request<B>, next: Next<B>) -> Response {
+    let locale = cookies
+        .get(LOCALE_COOKIE)
+        .and_then(|c| Locale::from_tag(c.value()))
+        .or_else(|| {
+            req.headers()
+                .get(ACCEPT_LANGUAGE)
+                .and_then(|header| header.to_str().ok())
+                .and_then(Locale::from_accept_language)
+        })
+        .unwrap_or_default();
+
+    req.extensions_mut().insert(locale);
+    locale.scope(next.run(req)).await
+}
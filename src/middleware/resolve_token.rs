@@ -34,9 +34,9 @@ pub async fn resolve_token<B>(
     .fetch_optional(&state.db)
     .await?;
 
-    token.map(|t| {
+    if let Some(t) = token {
         req.extensions_mut().insert(t);
-    });
+    }
 
     Ok(next.run(req).await)
 }
@@ -48,7 +48,7 @@ impl<S: Send + Sync> FromRequestParts<S> for Token {
         parts
             .extensions
             .get::<Token>()
-            .map(|token| token.clone())
+            .cloned()
             .ok_or(ApiError::TokenNotFound)
     }
 }
@@ -11,8 +11,10 @@ use crate::{
         ApiError::{self, VotingNotFound},
         ApiResult,
     },
+    helpers::get_voting,
     http::AppState,
-    models::{CandidateId, Voting, VotingId, VotingStateWithoutResults},
+    middleware::trace_request::record_voting_id,
+    models::{Voting, VotingId},
 };
 
 pub async fn resolve_voting<B>(
@@ -21,31 +23,13 @@ pub async fn resolve_voting<B>(
     mut req: Request<B>,
     next: Next<B>,
 ) -> ApiResult<Response> {
-    let voting = sqlx::query_as!(
-        Voting,
-        "
-        SELECT
-            v.id,
-            v.name,
-            v.description,
-            v.state AS \"state: VotingStateWithoutResults\",
-            v.created_at,
-            v.hide_vote_counts,
-            v.number_of_winners,
-            COALESCE(NULLIF(ARRAY_AGG(c.name), '{NULL}'), '{}') AS \"candidates!: Vec<CandidateId>\"
-        FROM voting as v LEFT JOIN candidate as c
-            ON v.id = c.voting_id
-        WHERE v.id = $1
-        GROUP BY v.id;
-        ",
-        id
-    )
-    .fetch_optional(&state.db)
-    .await?;
+    record_voting_id(id);
 
-    voting.map(|v| {
+    let voting = get_voting(&state.db, id).await?;
+
+    if let Some(v) = voting {
         req.extensions_mut().insert(v);
-    });
+    }
 
     Ok(next.run(req).await)
 }
@@ -57,7 +41,7 @@ impl<S: Send + Sync> FromRequestParts<S> for Voting {
         parts
             .extensions
             .get::<Voting>()
-            .map(|voting| voting.clone())
+            .cloned()
             .ok_or(VotingNotFound)
     }
 }
@@ -0,0 +1,41 @@
+use std::time::Instant;
+
+use axum::{http::Request, middleware::Next, response::Response};
+use tracing::{field::Empty, Instrument};
+
+/// Wraps the request in a span and logs its outcome. The role of the client and the voting are
+/// recorded on the span later by [`record_role`] and [`record_voting_id`]. Only the path is
+/// logged, never the query, headers or body, so that tokens and ballots stay out of the logs.
+pub async fn trace_request<B>(req: Request<B>, next: Next<B>) -> Response {
+    let span = tracing::info_span!(
+        "request",
+        method = %req.method(),
+        path = req.uri().path(),
+        role = Empty,
+        voting_id = Empty,
+    );
+    let start = Instant::now();
+
+    let response = next.run(req).instrument(span.clone()).await;
+
+    let status = response.status().as_u16();
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+    span.in_scope(|| {
+        if response.status().is_server_error() {
+            tracing::error!(status, latency_ms, "request failed");
+        } else {
+            tracing::info!(status, latency_ms, "request finished");
+        }
+    });
+
+    response
+}
+
+/// Records the role of the client, see [`crate::models::LoginState::role`].
+pub fn record_role(role: &str) {
+    tracing::Span::current().record("role", role);
+}
+
+pub fn record_voting_id(voting_id: i32) {
+    tracing::Span::current().record("voting_id", voting_id);
+}
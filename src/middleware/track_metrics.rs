@@ -0,0 +1,30 @@
+use std::time::Instant;
+
+use axum::{extract::MatchedPath, http::Request, middleware::Next, response::Response};
+
+use crate::metrics::metrics;
+
+/// Counts requests and their latency per route. The route pattern, such as `/votings/:id`, is
+/// used instead of the path to keep the number of series bounded.
+pub async fn track_metrics<B>(req: Request<B>, next: Next<B>) -> Response {
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched".to_string(), |path| path.as_str().to_string());
+    let start = Instant::now();
+
+    let response = next.run(req).await;
+
+    let metrics = metrics();
+    metrics
+        .http_requests
+        .with_label_values(&[&method, &route, response.status().as_str()])
+        .inc();
+    metrics
+        .http_request_duration
+        .with_label_values(&[&method, &route])
+        .observe(start.elapsed().as_secs_f64());
+
+    response
+}
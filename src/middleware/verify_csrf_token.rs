@@ -0,0 +1,57 @@
+use crate::api_types::{ApiError, ApiResult};
+use axum::{
+    http::{Method, Request},
+    middleware::Next,
+    response::Response,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use tower_cookies::{cookie::SameSite, Cookie, Cookies};
+
+pub const CSRF_TOKEN: &str = "csrftoken";
+pub const CSRF_HEADER: &str = "x-csrftoken";
+const CSRF_TOKEN_LENGTH: usize = 32;
+
+/// Double-submit cookie protection: every state-changing request has to repeat the value of
+/// the CSRF cookie in a header. Other sites can make the browser send the cookie but cannot
+/// read it, so they cannot produce a matching header.
+pub async fn verify_csrf_token<B>(
+    cookies: Cookies,
+    req: Request<B>,
+    next: Next<B>,
+) -> ApiResult<Response> {
+    let cookie_token = cookies.get(CSRF_TOKEN).map(|c| c.value().to_string());
+
+    let is_safe_method = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+
+    if !is_safe_method {
+        let header_token = req
+            .headers()
+            .get(CSRF_HEADER)
+            .and_then(|header| header.to_str().ok());
+
+        match (&cookie_token, header_token) {
+            (Some(cookie_token), Some(header_token))
+                if !cookie_token.is_empty() && cookie_token == header_token => {}
+            _ => return Err(ApiError::InvalidCsrfToken),
+        }
+    }
+
+    if cookie_token.is_none() {
+        let token: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(CSRF_TOKEN_LENGTH)
+            .map(char::from)
+            .collect();
+
+        // Readable by our own scripts, which copy it into the header
+        cookies.add(
+            Cookie::build(CSRF_TOKEN, token)
+                .path("/")
+                .secure(true)
+                .same_site(SameSite::Strict)
+                .finish(),
+        );
+    }
+
+    Ok(next.run(req).await)
+}
@@ -2,13 +2,23 @@ use chrono::{DateTime, Utc};
 use float_cmp::approx_eq;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::iter;
+use std::{
+    fmt::{self, Display},
+    iter,
+};
 use uuid::Uuid;
 use validator::Validate;
 
-pub type CandidateId = String;
+use crate::i18n::Locale;
+
+pub type CandidateId = i32;
 pub type VotingId = i32;
+pub type NominationId = i32;
+pub type VotingTemplateId = i32;
+pub type VoterGroupId = i32;
 pub type TokenId = i32;
+pub type AdminId = i32;
+pub type AdminSessionId = Uuid;
 pub type Alias = Option<String>;
 
 static CHARSET: &[u8] = b"0123456789ABCDEFGHJKLMNPQRSTUVWXYZ";
@@ -22,10 +32,6 @@ pub fn generate_token() -> String {
         .collect::<String>()
 }
 
-trait Voteable {
-    fn calculate_results(&self) -> Vec<CandidateId>;
-}
-
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum LoginState {
     NotLoggedIn,
@@ -33,13 +39,210 @@ pub enum LoginState {
     // TODO it might be better to create a new struct, e.g., "ValidToken",
     // That only contains the data that we want to represent a valid voter login state.
     Voter { token: String, alias: String },
+    Admin(AdminAccount),
+}
+
+impl LoginState {
+    pub fn can_manage_votings(&self) -> bool {
+        matches!(self, LoginState::Admin(admin) if admin.role.can_manage_votings())
+    }
+
+    pub fn can_manage_admins(&self) -> bool {
+        matches!(self, LoginState::Admin(admin) if admin.role.can_manage_admins())
+    }
+
+    pub fn is_admin(&self) -> bool {
+        matches!(self, LoginState::Admin(_))
+    }
+
+    pub fn is_voter(&self) -> bool {
+        matches!(self, LoginState::Voter { .. })
+    }
+
+    /// Who the client is, for logs and metrics. Does not reveal the token.
+    pub fn role(&self) -> &'static str {
+        match self {
+            LoginState::NotLoggedIn => "anonymous",
+            LoginState::Voter { .. } => "voter",
+            LoginState::Admin(admin) => match admin.role {
+                AdminRole::Superadmin => "superadmin",
+                AdminRole::Official => "official",
+                AdminRole::Observer => "observer",
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "admin_role", rename_all = "lowercase")]
+pub enum AdminRole {
+    Superadmin,
+    Official,
+    Observer,
+}
+
+impl AdminRole {
+    /// Officials and superadmins can run votings and manage tokens.
+    pub fn can_manage_votings(&self) -> bool {
+        matches!(self, AdminRole::Superadmin | AdminRole::Official)
+    }
+
+    /// Only superadmins can manage admin accounts and wipe all data.
+    pub fn can_manage_admins(&self) -> bool {
+        matches!(self, AdminRole::Superadmin)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct AdminAccount {
+    pub id: AdminId,
+    pub username: String,
+    pub role: AdminRole,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "admin_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AdminAction {
+    CreateVoting,
+    UpdateVoting,
+    CloseVoting,
+    DeleteVoting,
+    UpdateToken,
+    VoidActiveTokens,
+    GenerateTokens,
+    DeleteAllData,
+    UpdateCandidateImage,
+    ResetVotes,
+    RecountVoting,
+    WithdrawCandidate,
+    ReviewNomination,
+    CreateVotingTemplate,
+    DeleteVotingTemplate,
+    ImportAgenda,
+    ReorderVotings,
+    CreateVoterGroup,
+    DeleteVoterGroup,
+    UpdateVoterGroupTokens,
+    RevealRound,
+    HideRound,
+    DeleteCandidateImage,
+    CreateAdmin,
+    UpdateAdmin,
+    DeleteAdmin,
+    RevokeAdminSessions,
+}
+
+impl Display for AdminAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            AdminAction::CreateVoting => "create_voting",
+            AdminAction::UpdateVoting => "update_voting",
+            AdminAction::CloseVoting => "close_voting",
+            AdminAction::DeleteVoting => "delete_voting",
+            AdminAction::UpdateToken => "update_token",
+            AdminAction::VoidActiveTokens => "void_active_tokens",
+            AdminAction::GenerateTokens => "generate_tokens",
+            AdminAction::DeleteAllData => "delete_all_data",
+            AdminAction::UpdateCandidateImage => "update_candidate_image",
+            AdminAction::ResetVotes => "reset_votes",
+            AdminAction::RecountVoting => "recount_voting",
+            AdminAction::WithdrawCandidate => "withdraw_candidate",
+            AdminAction::ReviewNomination => "review_nomination",
+            AdminAction::CreateVotingTemplate => "create_voting_template",
+            AdminAction::DeleteVotingTemplate => "delete_voting_template",
+            AdminAction::ImportAgenda => "import_agenda",
+            AdminAction::ReorderVotings => "reorder_votings",
+            AdminAction::CreateVoterGroup => "create_voter_group",
+            AdminAction::DeleteVoterGroup => "delete_voter_group",
+            AdminAction::UpdateVoterGroupTokens => "update_voter_group_tokens",
+            AdminAction::RevealRound => "reveal_round",
+            AdminAction::HideRound => "hide_round",
+            AdminAction::DeleteCandidateImage => "delete_candidate_image",
+            AdminAction::CreateAdmin => "create_admin",
+            AdminAction::UpdateAdmin => "update_admin",
+            AdminAction::DeleteAdmin => "delete_admin",
+            AdminAction::RevokeAdminSessions => "revoke_admin_sessions",
+        };
+        write!(f, "{action}")
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminEvent {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub admin_id: AdminId,
+    pub admin_username: String,
+    pub action: AdminAction,
+    pub target: Option<String>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "login_kind", rename_all = "lowercase")]
+pub enum LoginKind {
+    Voter,
     Admin,
 }
 
+impl LoginKind {
+    /// Usernames are shown as such. The keyed hashes of voter tokens are shortened, as they only
+    /// serve to tell the attempted codes apart.
+    pub fn display_target<'a>(&self, target: &'a str) -> &'a str {
+        match self {
+            LoginKind::Admin => target,
+            LoginKind::Voter => target.get(..12).unwrap_or(target),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginFailure {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub kind: LoginKind,
+    pub ip: String,
+    /// Attempted username, or a keyed hash of the attempted voter token
+    pub target: String,
+}
+
+/// Failed logins from one address or against one target within the failure window.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginFailureSummary {
+    pub kind: LoginKind,
+    pub key: String,
+    pub failures: i64,
+    pub last_failure_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Validate, Deserialize)]
+pub struct AdminAccountCreate {
+    #[validate(length(min = 1, max = 64))]
+    pub username: String,
+    #[validate(length(min = 8, max = 1024))]
+    pub password: String,
+    pub role: AdminRole,
+}
+
+#[derive(Validate, Deserialize)]
+pub struct AdminAccountUpdate {
+    #[validate(length(min = 8, max = 1024))]
+    pub password: Option<String>,
+    pub role: Option<AdminRole>,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, sqlx::Type)]
 #[sqlx(type_name = "voting_state", rename_all = "lowercase")]
 pub enum VotingStateWithoutResults {
     Draft,
+    /// Voters can nominate candidates, see [`Nomination`].
+    Nominating,
     Open,
     Closed,
 }
@@ -56,6 +259,7 @@ impl From<VotingState> for VotingStateWithoutResults {
     fn from(value: VotingState) -> Self {
         match value {
             VotingState::Draft => VotingStateWithoutResults::Draft,
+            VotingState::Nominating => VotingStateWithoutResults::Nominating,
             VotingState::Open => VotingStateWithoutResults::Open,
             VotingState::Closed { .. } => VotingStateWithoutResults::Closed,
         }
@@ -66,6 +270,7 @@ impl From<VotingState> for VotingStateWithoutResults {
 #[serde(rename_all = "camelCase")]
 pub enum VotingState {
     Draft,
+    Nominating,
     Open,
     #[serde(rename_all = "camelCase")]
     Closed(VotingResult),
@@ -75,16 +280,111 @@ pub enum VotingState {
 pub struct VotingResult {
     pub round_results: Vec<VotingRoundResult>,
     pub winners: Vec<CandidateId>,
+    /// Precision the count was done with, see [`Voting::decimal_places`].
+    pub decimal_places: Option<i32>,
+}
+
+impl VotingResult {
+    /// Winners of the count without the re-open nominations pseudo-candidate.
+    pub fn elected(&self, ron: Option<CandidateId>) -> Vec<CandidateId> {
+        self.winners
+            .iter()
+            .filter(|id| Some(**id) != ron)
+            .copied()
+            .collect()
+    }
+
+    pub fn unfilled_seats(&self, number_of_winners: usize, ron: Option<CandidateId>) -> usize {
+        number_of_winners.saturating_sub(self.elected(ron).len())
+    }
+
+    /// Vote count with the decimals of a fixed-point count, or four decimals for a floating
+    /// point count.
+    pub fn format_vote_count(&self, vote_count: &f64) -> String {
+        let decimals = self.decimal_places.map_or(4, |d| d as usize);
+        format!("{vote_count:.decimals$}")
+    }
+
+    /// Whether a draw was decided at random on some round, in which case a recount may end up
+    /// with a different outcome.
+    pub fn has_draws(&self) -> bool {
+        self.round_results.iter().any(|r| {
+            r.candidate_results.iter().any(|c| c.data.is_draw)
+                || r.dropped_candidate.as_ref().is_some_and(|d| d.is_draw)
+        })
+    }
+
+    /// Whether two counts elected the same candidates with the same vote counts on every round.
+    /// The transfers are not compared, they are implied by the vote counts.
+    pub fn has_same_outcome(&self, other: &VotingResult) -> bool {
+        let tallies = |result: &VotingResult| {
+            result
+                .round_results
+                .iter()
+                .map(|r| {
+                    let mut candidates = r.candidate_results.clone();
+                    candidates.sort_by_key(|c| c.data.candidate_id);
+                    (candidates, r.dropped_candidate.clone())
+                })
+                .collect::<Vec<_>>()
+        };
+        let winners = |result: &VotingResult| {
+            let mut winners = result.winners.clone();
+            winners.sort();
+            winners
+        };
+
+        winners(self) == winners(other) && tallies(self) == tallies(other)
+    }
+}
+
+pub type ResultVersion = i32;
+
+/// One stored count of a closed voting. Closing a voting stores the first version and every
+/// recount adds a new one; the latest version is the current result.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VotingResultVersion {
+    pub version: ResultVersion,
+    pub created_at: DateTime<Utc>,
+    pub number_of_winners: i32,
+    pub excluded_candidate_ids: Vec<CandidateId>,
+    pub admin_username: Option<String>,
+    pub result: VotingResult,
+}
+
+/// A ballot of a public voting together with the alias of the voter. An empty ballot has no
+/// candidates.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicBallot {
+    pub voter_alias: String,
+    pub candidates: Vec<CandidateId>,
+}
+
+#[derive(Validate, Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VotingRecount {
+    #[validate(range(min = 1))]
+    pub number_of_winners: Option<i32>,
+    #[serde(default)]
+    pub excluded_candidates: Vec<CandidateId>,
+    /// Overrides [`Voting::decimal_places`] when present, `Some(None)` counts in floating point.
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    #[validate(range(min = 0, max = 9))]
+    pub decimal_places: Option<Option<i32>>,
 }
 
 impl From<VotingStateWithoutResults> for VotingState {
     fn from(value: VotingStateWithoutResults) -> Self {
         match value {
             VotingStateWithoutResults::Draft => Self::Draft,
+            VotingStateWithoutResults::Nominating => Self::Nominating,
             VotingStateWithoutResults::Open => Self::Open,
             VotingStateWithoutResults::Closed => Self::Closed(VotingResult {
                 round_results: vec![],
                 winners: vec![],
+                decimal_places: None,
             }),
         }
     }
@@ -92,12 +392,19 @@ impl From<VotingStateWithoutResults> for VotingState {
 
 impl PartialEq<VotingStateWithoutResults> for VotingState {
     fn eq(&self, other: &VotingStateWithoutResults) -> bool {
-        match (self, other) {
-            (VotingState::Draft, VotingStateWithoutResults::Draft) => true,
-            (VotingState::Open, VotingStateWithoutResults::Open) => true,
-            (VotingState::Closed { .. }, VotingStateWithoutResults::Closed) => true,
-            _ => false,
-        }
+        matches!(
+            (self, other),
+            (VotingState::Draft, VotingStateWithoutResults::Draft)
+                | (
+                    VotingState::Nominating,
+                    VotingStateWithoutResults::Nominating
+                )
+                | (VotingState::Open, VotingStateWithoutResults::Open)
+                | (
+                    VotingState::Closed { .. },
+                    VotingStateWithoutResults::Closed
+                )
+        )
     }
 }
 
@@ -112,8 +419,140 @@ pub struct Voting {
     pub state: VotingState,
     pub created_at: DateTime<Utc>,
     pub hide_vote_counts: bool,
+    /// Roll-call voting: the alias of the voter is stored with each ballot and the ballots are
+    /// listed by name in the results.
+    pub is_public_ballot: bool,
+    /// Voters may replace their ballot until the voting closes. Only the latest ballot counts.
+    pub allow_revote: bool,
     pub number_of_winners: i32,
-    pub candidates: Vec<CandidateId>,
+    /// Counts in fixed-point decimals truncating every transfer value to this many decimal
+    /// places. `None` counts in floating point.
+    pub decimal_places: Option<i32>,
+    /// Number of the voting on the meeting agenda. Every list of votings is in this order.
+    pub agenda_position: i32,
+    /// Section of the agenda the voting belongs to, such as "Elections"
+    pub agenda_group: Option<String>,
+    /// Only tokens in one of these groups may vote. Empty if every activated token may vote.
+    pub eligible_groups: Vec<VoterGroup>,
+    pub candidates: Vec<Candidate>,
+}
+
+impl Voting {
+    pub fn candidate_ids(&self) -> Vec<CandidateId> {
+        self.candidates.iter().map(|c| c.id).collect()
+    }
+
+    pub fn withdrawn_candidate_ids(&self) -> Vec<CandidateId> {
+        self.candidates
+            .iter()
+            .filter(|c| c.withdrawn_at.is_some())
+            .map(|c| c.id)
+            .collect()
+    }
+
+    pub fn candidate(&self, id: CandidateId) -> Option<&Candidate> {
+        self.candidates.iter().find(|c| c.id == id)
+    }
+
+    /// Display name of a candidate, used when rendering ballots and results.
+    pub fn candidate_name(&self, id: &CandidateId, locale: &Locale) -> String {
+        self.candidate(*id)
+            .map(|c| c.display_name(locale))
+            .unwrap_or_else(|| format!("#{id}"))
+    }
+
+    pub fn candidate_names(&self, ids: &[CandidateId], locale: &Locale) -> String {
+        ids.iter()
+            .map(|id| self.candidate_name(id, locale))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn candidate_has_image(&self, id: &CandidateId) -> bool {
+        self.candidate(*id).is_some_and(|c| c.has_image)
+    }
+
+    /// Whether the ballot has the re-open nominations (RON) pseudo-candidate. RON is counted like
+    /// any other candidate, but a seat it wins stays unfilled.
+    pub fn has_ron(&self) -> bool {
+        self.candidates.iter().any(|c| c.is_ron)
+    }
+
+    /// Whether exactly the given groups are eligible, in any order.
+    pub fn has_eligible_groups(&self, group_ids: &[VoterGroupId]) -> bool {
+        let mut current = self
+            .eligible_groups
+            .iter()
+            .map(|g| g.id)
+            .collect::<Vec<_>>();
+        let mut given = group_ids.to_vec();
+        current.sort();
+        given.sort();
+        given.dedup();
+        current == given
+    }
+
+    pub fn is_eligible_group(&self, group_id: &VoterGroupId) -> bool {
+        self.eligible_groups.iter().any(|g| g.id == *group_id)
+    }
+
+    /// Candidates other than RON in display order.
+    pub fn regular_candidates(&self) -> Vec<&Candidate> {
+        self.candidates.iter().filter(|c| !c.is_ron).collect()
+    }
+
+    pub fn is_nominating(&self) -> bool {
+        self.state == VotingStateWithoutResults::Nominating
+    }
+
+    /// Names of the elected candidates. Seats won by RON are not listed.
+    pub fn elected_names(&self, result: &VotingResult, locale: &Locale) -> String {
+        self.candidate_names(&result.elected(self.ron_candidate_id()), locale)
+    }
+
+    /// Seats left unfilled by a count, either because RON won them or because there were not
+    /// enough candidates.
+    pub fn unfilled_seats(&self, result: &VotingResult, number_of_winners: &i32) -> usize {
+        result.unfilled_seats(
+            (*number_of_winners).try_into().unwrap_or(0),
+            self.ron_candidate_id(),
+        )
+    }
+
+    fn ron_candidate_id(&self) -> Option<CandidateId> {
+        self.candidates.iter().find(|c| c.is_ron).map(|c| c.id)
+    }
+
+    /// Whether applying the update would change the meaning of ballots that have already been
+    /// cast. Adding or removing candidates or RON, changing the number of winners and moving the
+    /// voting back to a draft do, as does changing ballot secrecy because the ballots cast so far
+    /// were given under the old promise. Ballots refer to candidates by id, so the name,
+    /// description, candidate names and introductions and display order do not.
+    pub fn update_affects_ballots(&self, update: &VotingUpdate) -> bool {
+        let candidates_changed = update.candidates.as_ref().is_some_and(|inputs| {
+            let mut before = self
+                .candidates
+                .iter()
+                .filter(|c| !c.is_ron)
+                .map(|c| Some(c.id))
+                .collect::<Vec<_>>();
+            let mut after = inputs.iter().map(|c| c.id).collect::<Vec<_>>();
+            before.sort();
+            after.sort();
+            before != after
+        });
+
+        candidates_changed
+            || update.include_ron.is_some_and(|r| r != self.has_ron())
+            || update
+                .is_public_ballot
+                .is_some_and(|p| p != self.is_public_ballot)
+            || update
+                .number_of_winners
+                .is_some_and(|n| n != self.number_of_winners)
+            || (self.state == VotingStateWithoutResults::Open
+                && update.state == Some(VotingStateWithoutResults::Draft))
+    }
 }
 
 #[derive(Clone, Serialize)]
@@ -125,9 +564,37 @@ pub struct VotingForVoterTemplate {
     pub state: VotingState,
     pub created_at: DateTime<Utc>,
     pub hide_vote_counts: bool,
-    pub candidates: Vec<CandidateId>,
+    pub is_public_ballot: bool,
+    pub allow_revote: bool,
+    pub candidates: Vec<Candidate>,
     pub number_of_winners: i32,
+    pub decimal_places: Option<i32>,
+    pub agenda_position: i32,
+    pub agenda_group: Option<String>,
+    pub eligible_groups: Vec<VoterGroup>,
     pub you_have_voted: bool,
+    /// The current ballot of the voter in a voting that allows re-voting. `None` when the
+    /// voter has not voted or cannot replace the ballot.
+    pub your_ballot: Option<Vec<CandidateId>>,
+}
+
+impl VotingForVoterTemplate {
+    /// Names of the candidates of a ballot in ranked order, e.g. "1. Alice, 2. Bob".
+    pub fn ranked_candidate_names(&self, ballot: &[CandidateId], locale: &Locale) -> String {
+        ballot
+            .iter()
+            .enumerate()
+            .map(|(index, id)| {
+                let name = self
+                    .candidates
+                    .iter()
+                    .find(|c| c.id == *id)
+                    .map_or(String::new(), |c| c.display_name(locale));
+                format!("{}. {}", index + 1, name)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 impl From<VotingForVoterTemplate> for Voting {
@@ -139,8 +606,14 @@ impl From<VotingForVoterTemplate> for Voting {
             state: value.state,
             created_at: value.created_at,
             hide_vote_counts: value.hide_vote_counts,
+            is_public_ballot: value.is_public_ballot,
+            allow_revote: value.allow_revote,
             candidates: value.candidates,
             number_of_winners: value.number_of_winners,
+            decimal_places: value.decimal_places,
+            agenda_position: value.agenda_position,
+            agenda_group: value.agenda_group,
+            eligible_groups: value.eligible_groups,
         }
     }
 }
@@ -159,13 +632,41 @@ impl PartialEq<VotingUpdate> for Voting {
                 .hide_vote_counts
                 .map(|h| self.hide_vote_counts == h)
                 .unwrap_or(true)
+            && other_clone
+                .is_public_ballot
+                .map(|p| self.is_public_ballot == p)
+                .unwrap_or(true)
+            && other_clone
+                .allow_revote
+                .map(|r| self.allow_revote == r)
+                .unwrap_or(true)
             && other_clone
                 .number_of_winners
                 .map(|h| self.number_of_winners == h)
                 .unwrap_or(true)
+            && other_clone
+                .decimal_places
+                .map(|d| self.decimal_places == d)
+                .unwrap_or(true)
             && other_clone
                 .candidates
-                .map(|c| self.candidates == c)
+                .map(|c| {
+                    let candidates = self.regular_candidates();
+                    candidates.len() == c.len()
+                        && candidates.iter().zip(c.iter()).all(|(a, b)| *a == b)
+                })
+                .unwrap_or(true)
+            && other_clone
+                .include_ron
+                .map(|r| self.has_ron() == r)
+                .unwrap_or(true)
+            && other_clone
+                .agenda_group
+                .map(|g| self.agenda_group == g)
+                .unwrap_or(true)
+            && other_clone
+                .eligible_groups
+                .map(|g| self.has_eligible_groups(&g))
                 .unwrap_or(true)
     }
 }
@@ -179,8 +680,28 @@ pub struct VotingCreate {
     pub description: String,
     pub state: Option<VotingStateWithoutResults>,
     pub hide_vote_counts: bool,
+    /// Stores the voter alias with each ballot, see [`Voting::is_public_ballot`].
+    #[serde(default)]
+    pub is_public_ballot: bool,
+    /// Lets voters replace their ballot, see [`Voting::allow_revote`].
+    #[serde(default)]
+    pub allow_revote: bool,
     pub number_of_winners: i32,
-    pub candidates: Option<Vec<CandidateId>>,
+    /// See [`Voting::decimal_places`].
+    #[validate(range(min = 0, max = 9))]
+    pub decimal_places: Option<i32>,
+    #[validate]
+    pub candidates: Option<Vec<CandidateInput>>,
+    /// Adds the re-open nominations (RON) pseudo-candidate to the ballot.
+    #[serde(default)]
+    pub include_ron: bool,
+    /// See [`Voting::agenda_group`].
+    #[serde(default)]
+    #[validate(length(min = 1, max = 128))]
+    pub agenda_group: Option<String>,
+    /// See [`Voting::eligible_groups`].
+    #[serde(default)]
+    pub eligible_groups: Vec<VoterGroupId>,
 }
 
 #[derive(Validate, Debug, Clone, Deserialize, Serialize)]
@@ -192,23 +713,139 @@ pub struct VotingUpdate {
     pub description: Option<String>,
     pub state: Option<VotingStateWithoutResults>,
     pub hide_vote_counts: Option<bool>,
+    pub is_public_ballot: Option<bool>,
+    pub allow_revote: Option<bool>,
     pub number_of_winners: Option<i32>,
-    pub candidates: Option<Vec<CandidateId>>,
+    /// `Some(None)` switches the voting back to floating point counting.
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    #[validate(range(min = 0, max = 9))]
+    pub decimal_places: Option<Option<i32>>,
+    /// The full candidate list in display order. Entries with an id update the existing
+    /// candidate, entries without one are added and missing candidates are removed.
+    #[validate]
+    pub candidates: Option<Vec<CandidateInput>>,
+    /// Adds or removes the re-open nominations (RON) pseudo-candidate.
+    pub include_ron: Option<bool>,
+    /// `Some(None)` removes the voting from its agenda group.
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    #[validate(length(min = 1, max = 128))]
+    pub agenda_group: Option<Option<String>>,
+    /// Replaces the eligible groups. Can only be changed before the voting opens.
+    pub eligible_groups: Option<Vec<VoterGroupId>>,
+    /// Confirms that cast votes may be deleted. Required when the update changes the ballot
+    /// of an open voting, see [`Voting::update_affects_ballots`].
+    #[serde(default)]
+    pub reset_votes: bool,
+}
+
+/// New agenda order of some votings. They swap places among the positions they already have, so
+/// the admin view can reorder the votings of one state without knowing about the others.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VotingReorder {
+    pub voting_ids: Vec<VotingId>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    pub id: CandidateId,
+    pub name: String,
+    pub bio: Option<String>,
+    pub display_order: i32,
+    pub has_image: bool,
+    /// Withdrawn candidates stay on cast ballots but are left out of the count.
+    pub withdrawn_at: Option<DateTime<Utc>>,
+    /// The re-open nominations pseudo-candidate, see [`Voting::has_ron`].
+    pub is_ron: bool,
+}
+
+impl Candidate {
+    /// RON is stored without a name or an introduction, so that its label follows the language
+    /// of the viewer.
+    pub fn display_name(&self, locale: &Locale) -> String {
+        if self.is_ron {
+            locale.t("candidate.ron_name").to_string()
+        } else {
+            self.name.clone()
+        }
+    }
+
+    pub fn display_bio(&self, locale: &Locale) -> Option<String> {
+        if self.is_ron {
+            Some(locale.t("candidate.ron_bio").to_string())
+        } else {
+            self.bio.clone()
+        }
+    }
 }
 
-#[derive(Debug)]
-pub struct HasVoted {
+/// Largest number of decimal places of a fixed-point count. Keeps the products of the transfer
+/// value calculation within `i128`.
+pub const MAX_DECIMAL_PLACES: i32 = 9;
+
+#[derive(Validate, Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateInput {
+    pub id: Option<CandidateId>,
+    #[validate(length(min = 1, max = 128))]
+    pub name: String,
+    #[validate(length(max = 4096))]
+    pub bio: Option<String>,
+}
+
+impl PartialEq<CandidateInput> for Candidate {
+    fn eq(&self, other: &CandidateInput) -> bool {
+        other.id == Some(self.id) && self.name == other.name && self.bio == other.bio
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "nomination_state", rename_all = "lowercase")]
+pub enum NominationState {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A candidate proposed by a voter while the nominations of a voting are open. Approved nominees
+/// become candidates when the voting opens.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Nomination {
+    pub id: NominationId,
     pub voting_id: VotingId,
-    pub token_id: TokenId,
-    pub has_voted: bool,
+    pub name: String,
+    pub bio: Option<String>,
+    /// Alias of the voter who made the nomination
+    pub nominated_by: String,
+    pub state: NominationState,
+    pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug)]
-pub struct Vote {
+impl Nomination {
+    pub fn is_pending(&self) -> bool {
+        self.state == NominationState::Pending
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.state == NominationState::Approved
+    }
+}
+
+#[derive(Validate, Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NominationCreate {
     pub voting_id: VotingId,
-    pub id: Uuid,
-    pub candidate: CandidateId,
-    pub rank: i32,
+    #[validate(length(min = 1, max = 128))]
+    pub name: String,
+    #[validate(length(max = 4096))]
+    pub bio: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NominationUpdate {
+    pub state: NominationState,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -224,24 +861,17 @@ pub struct TokenUpdate {
     pub state: TokenState,
 }
 
-#[derive(Debug)]
-pub struct VoteCastStatus {
-    pub voting: VotingId,
-    pub token: TokenId,
-    pub has_voted: bool,
-}
-
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CandidateResultData {
-    pub name: CandidateId,
+    pub candidate_id: CandidateId,
     pub vote_count: f64,
     pub is_draw: bool,
 }
 
 impl PartialEq for CandidateResultData {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name
+        self.candidate_id == other.candidate_id
             && approx_eq!(f64, self.vote_count, other.vote_count, epsilon = 0.000001)
     }
 }
@@ -261,4 +891,244 @@ pub struct VotingRoundResult {
     pub round: i32,
     pub candidate_results: Vec<PassingCandidateResult>,
     pub dropped_candidate: Option<CandidateResultData>,
+    /// Where the votes of the elected or dropped candidates moved at the end of the round
+    pub transfers: Vec<VoteTransfer>,
+    pub non_transferable: NonTransferableWeight,
+}
+
+/// Weight that left the count at the end of a round without moving to another candidate.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NonTransferableWeight {
+    /// Ballots without a further preference
+    pub exhausted: f64,
+    /// Weight that was neither kept by the elected candidates nor transferred, e.g. due to
+    /// rounding of the transfer values
+    pub lost: f64,
+}
+
+impl NonTransferableWeight {
+    pub fn has_exhausted(&self) -> bool {
+        !approx_eq!(f64, self.exhausted, 0.0, epsilon = 0.000001)
+    }
+
+    pub fn has_lost(&self) -> bool {
+        !approx_eq!(f64, self.lost, 0.0, epsilon = 0.000001)
+    }
+}
+
+impl PartialEq for NonTransferableWeight {
+    fn eq(&self, other: &Self) -> bool {
+        approx_eq!(f64, self.exhausted, other.exhausted, epsilon = 0.000001)
+            && approx_eq!(f64, self.lost, other.lost, epsilon = 0.000001)
+    }
+}
+
+impl Eq for NonTransferableWeight {}
+
+/// Weight moved from one candidate to the next preference of the ballots. Ballots without a
+/// further preference are exhausted and have no receiving candidate.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteTransfer {
+    pub from_candidate_id: CandidateId,
+    pub to_candidate_id: Option<CandidateId>,
+    pub weight: f64,
+}
+
+impl PartialEq for VoteTransfer {
+    fn eq(&self, other: &Self) -> bool {
+        self.from_candidate_id == other.from_candidate_id
+            && self.to_candidate_id == other.to_candidate_id
+            && approx_eq!(f64, self.weight, other.weight, epsilon = 0.000001)
+    }
+}
+
+impl Eq for VoteTransfer {}
+
+/// Saved settings for a voting that recurs at every meeting. Votings are created from a template
+/// in the admin view or by naming it in an imported agenda.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VotingTemplate {
+    pub id: VotingTemplateId,
+    pub name: String,
+    pub description: String,
+    pub hide_vote_counts: bool,
+    pub is_public_ballot: bool,
+    pub allow_revote: bool,
+    pub number_of_winners: i32,
+    pub decimal_places: Option<i32>,
+    pub include_ron: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Validate, Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VotingTemplateCreate {
+    #[validate(length(min = 1, max = 128))]
+    pub name: String,
+    #[serde(default)]
+    #[validate(length(min = 0, max = 128))]
+    pub description: String,
+    #[serde(default)]
+    pub hide_vote_counts: bool,
+    #[serde(default)]
+    pub is_public_ballot: bool,
+    #[serde(default)]
+    pub allow_revote: bool,
+    #[validate(range(min = 1))]
+    pub number_of_winners: i32,
+    #[validate(range(min = 0, max = 9))]
+    pub decimal_places: Option<i32>,
+    #[serde(default)]
+    pub include_ron: bool,
+}
+
+/// The votings of a meeting, imported as drafts in the order they are listed.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Agenda {
+    pub votings: Vec<AgendaItem>,
+}
+
+/// One voting of an [`Agenda`]. Settings that are left out are taken from the named template, or
+/// from the defaults of a new voting if there is none.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AgendaItem {
+    pub name: String,
+    pub description: Option<String>,
+    /// Name of a [`VotingTemplate`]
+    pub template: Option<String>,
+    pub hide_vote_counts: Option<bool>,
+    pub is_public_ballot: Option<bool>,
+    pub allow_revote: Option<bool>,
+    pub number_of_winners: Option<i32>,
+    pub decimal_places: Option<i32>,
+    pub include_ron: Option<bool>,
+    /// See [`Voting::agenda_group`].
+    pub group: Option<String>,
+    /// Names of the [`VoterGroup`]s that may vote
+    #[serde(default)]
+    pub eligible_groups: Vec<String>,
+    #[serde(default)]
+    pub candidates: Vec<AgendaCandidate>,
+}
+
+/// A candidate of an [`AgendaItem`], either just a name or a name with an introduction.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum AgendaCandidate {
+    Name(String),
+    Details { name: String, bio: Option<String> },
+}
+
+impl AgendaItem {
+    /// Combines the item with its template into a draft voting. Returns `None` if neither of
+    /// them sets the number of winners. The eligible groups are resolved by the caller.
+    pub fn into_voting_create(self, template: Option<&VotingTemplate>) -> Option<VotingCreate> {
+        let number_of_winners = self
+            .number_of_winners
+            .or(template.map(|t| t.number_of_winners))?;
+
+        Some(VotingCreate {
+            name: self.name,
+            description: self
+                .description
+                .or(template.map(|t| t.description.clone()))
+                .unwrap_or_default(),
+            state: Some(VotingStateWithoutResults::Draft),
+            hide_vote_counts: self
+                .hide_vote_counts
+                .or(template.map(|t| t.hide_vote_counts))
+                .unwrap_or_default(),
+            is_public_ballot: self
+                .is_public_ballot
+                .or(template.map(|t| t.is_public_ballot))
+                .unwrap_or_default(),
+            allow_revote: self
+                .allow_revote
+                .or(template.map(|t| t.allow_revote))
+                .unwrap_or_default(),
+            number_of_winners,
+            decimal_places: self
+                .decimal_places
+                .or(template.and_then(|t| t.decimal_places)),
+            candidates: Some(
+                self.candidates
+                    .into_iter()
+                    .map(|c| match c {
+                        AgendaCandidate::Name(name) => CandidateInput {
+                            id: None,
+                            name,
+                            bio: None,
+                        },
+                        AgendaCandidate::Details { name, bio } => CandidateInput {
+                            id: None,
+                            name,
+                            bio,
+                        },
+                    })
+                    .collect(),
+            ),
+            include_ron: self
+                .include_ron
+                .or(template.map(|t| t.include_ron))
+                .unwrap_or_default(),
+            agenda_group: self.group,
+            eligible_groups: vec![],
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoterGroup {
+    pub id: VoterGroupId,
+    pub name: String,
+}
+
+/// A voter group with its tokens, as listed on the tokens page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoterGroupTokens {
+    pub id: VoterGroupId,
+    pub name: String,
+    pub token_ids: Vec<TokenId>,
+}
+
+impl VoterGroupTokens {
+    /// Token numbers as ranges, e.g. "1-50, 73".
+    pub fn token_ranges(&self) -> String {
+        let mut ranges: Vec<(TokenId, TokenId)> = vec![];
+        for &id in &self.token_ids {
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == id => *end = id,
+                _ => ranges.push((id, id)),
+            }
+        }
+        ranges
+            .iter()
+            .map(|(start, end)| match start == end {
+                true => start.to_string(),
+                false => format!("{start}-{end}"),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Validate, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoterGroupCreate {
+    #[validate(length(min = 1, max = 128))]
+    pub name: String,
+}
+
+/// Replaces the tokens of a voter group.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoterGroupTokensUpdate {
+    pub token_ids: Vec<TokenId>,
 }
@@ -4,6 +4,16 @@ var currentVotingId = -1;
 var votesGiven = 0;
 
 // Helper functions
+function t(key, arg) {
+  const text = TRANSLATIONS[key] ?? key;
+  return arg === undefined ? text : text.replace("{}", arg);
+}
+
+function setLocale(code) {
+  document.cookie = `lang=${code}; path=/; max-age=31536000; SameSite=Lax`;
+  location.reload();
+}
+
 function getCookie(name) {
   let cookieValue = null;
   if (document.cookie && document.cookie !== "") {
@@ -19,6 +29,11 @@ function getCookie(name) {
   return cookieValue;
 }
 
+// HTMX requests carry the same CSRF header as callApi
+document.addEventListener("htmx:configRequest", (event) => {
+  event.detail.headers["X-CSRFToken"] = getCookie("csrftoken");
+});
+
 function callApi(url, method, body) {
   return fetch(url, {
     method: method,
@@ -116,7 +131,7 @@ function showVotingConfirmationModal(
     const closeModalButton = document.getElementById("voting-modal-close");
       
     const data = {
-      candidates: chosenCandidates.filter(c => c.position !== "-").map(c => c.id),
+      candidates: chosenCandidates.filter(c => c.position !== "-").map(c => parseInt(c.id)),
       voting_id: votingId,
     };
 
@@ -132,11 +147,11 @@ function showVotingConfirmationModal(
         if (!res.ok) {
           if (res.status == 403) {
             throw Error(
-              "Äänestäminen epäonnistui. Tarkista äänestyksen salasana."
+              t("vote_failed_password")
             );
           }
           throw Error(
-            "Äänestäminen epäonnistui. Päivitä sivu ja yritä uudelleen!"
+            t("vote_failed")
           );
         }
         return res.text();
@@ -144,7 +159,7 @@ function showVotingConfirmationModal(
       .then((html) => {
         showUserNotification(
           USER_NOTIFICATION.CONFIRMATION,
-          "Äänestäminen onnistui. Päivitetään äänestysluettelo."
+          t("vote_succeeded")
         );
         // Do not distract the user with things happening too fast
         window.setTimeout(() => {
@@ -158,15 +173,13 @@ function showVotingConfirmationModal(
         confirmationModalTextArea.innerHTML =
           error.message.length > 0
             ? error.message
-            : "Äänestäminen saattoi epäonnistua. Päivitä sivu ja tarkista,\
-            näkyykö äänestys vielä äänestämättömänä.";
+            : t("vote_maybe_failed");
         e.target.removeAttribute("disabled");
         closeModalButton.removeAttribute("disabled");
       });
 
     confirmationModalTextArea.innerHTML =
-      "Äänesi on lähetetty. Odotetaan vahvistusta äänestyspalvelimelta. \
-    Jos mitään ei tapahdu 10 sekunnin kuluessa, päivitä sivu.";
+      t("vote_sent");
   }
 
   // Initialize modal
@@ -191,10 +204,14 @@ function showVotingConfirmationModal(
     .map((c) => (isRankedChoice ? `${c.position}.${c.name}` : c.name))
     .join(", ");
   const singularOrPlural =
-    chosenCandidates.length > 1 ? "ehdokkaita:\n" : "ehdokasta:\n";
+    chosenCandidates.length > 1 ? t("candidates_plural") + "\n" : t("candidates_singular") + "\n";
+  const publicBallotWarning =
+    getVotingForm(votingId).dataset.publicBallot === "true"
+      ? "<br><br><strong>" + t("public_ballot_not_secret") + "</strong> " + t("public_ballot_warning")
+      : "";
   document.getElementById(
     "voting-modal-text"
-  ).innerHTML = `Olet äänestämässä ${singularOrPlural} ${candidatesString}`;
+  ).innerHTML = `${t("voting_for")} ${singularOrPlural} ${candidatesString}${publicBallotWarning}`;
 
   btnConfirmation.addEventListener("click", setVotingConfirmationEventListener);
   instance.open();
@@ -215,7 +232,7 @@ function vote(votingId) {
   if (chosenCandidates.length === 0) {
     showUserNotification(
       USER_NOTIFICATION.WARNING,
-      "Valitse ainakin yksi ehdokas."
+      t("choose_a_candidate")
     );
     return;
   }
@@ -243,9 +260,9 @@ async function refreshVotingList(admin = false) {
   const votingArea = document.getElementById("voting-list-area");
   const adminPath = admin ? "admin/" : "";
   const failMsg =
-    "Äänestysten haku ei onnistunut. Päivitä sivu. Jos koetit äänestää, katso, näkyykö äänestys jo äänestettynä.";
+    t("votings_fetch_failed");
 
-  votingListRefreshButton.innerHTML = "Päivitetään...";
+  votingListRefreshButton.innerHTML = t("refreshing");
   votingListRefreshButton.disabled = true;
 
   await callApi(`${SITE_ROOT_PATH}votings`, "GET")
@@ -256,7 +273,7 @@ async function refreshVotingList(admin = false) {
       votingArea.innerHTML = "<p>" + failMsg + "</p>";
     });
 
-  votingListRefreshButton.innerHTML = "Luettelo päivitetty!";
+  votingListRefreshButton.innerHTML = t("refreshed");
 
   // Prevent from clicking the button unnecessarily many times within a short time
   var timeoutDuration = 3000;
@@ -325,21 +342,23 @@ function logout() {
     .catch(() =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        "Uloskirjautuminen epäonnistui. Päivitä sivu."
+        t("logout_failed")
       )
     );
 }
 
 function adminLogin() {
-  const token = document.getElementById("admin-login-field").value;
+  const username = document.getElementById("admin-username-field").value;
+  const password = document.getElementById("admin-login-field").value;
   const notificationArea = document.getElementById("login-notification-area");
 
   notificationArea.classList.add("loading-token-notification");
   notificationArea.classList.remove("wrong-token-warning");
-  notificationArea.innerHTML = "Ladataan... &#129312";
+  notificationArea.innerHTML = `${t("loading")} &#129312`;
 
   callApi(`${SITE_ROOT_PATH}login`, "POST", {
-    token
+    username,
+    password,
   })
     .then(async res => {
       if (!res.ok) {
@@ -363,8 +382,7 @@ function submitToken() {
 
   notificationArea.classList.add("loading-token-notification");
   notificationArea.classList.remove("wrong-token-warning");
-  notificationArea.innerHTML = "Ladataan... &#129312";
-  document.cookie = csrftoken = jQuery("[name=csrfmiddlewaretoken]").val();
+  notificationArea.innerHTML = `${t("loading")} &#129312`;
 
   callApi(`${SITE_ROOT_PATH}user/login/`, "POST", {
     token: token,
@@ -372,8 +390,8 @@ function submitToken() {
   })
     .then(async res => {
       if (!res.ok) {
-        if (res.status === 400 ||res.status === 401) throw Error(await res.text())
-        else throw Error("Kirjautuminen epäonnistui")
+        if (res.status === 400 || res.status === 401 || res.status === 429) throw Error(await res.text())
+        else throw Error(t("login_failed"))
       }
       location.reload();
     })
@@ -389,11 +407,12 @@ function submitToken() {
 
 // Admin
 function makeEditable(votingId) {
-  if (!confirm("Haluatko varmasti muokata äänestystä? Samalla poistetaan kaikki olemassa olevat äänet.")) {
+  if (!confirm(t("confirm_make_editable"))) {
     return
   }
   const data = {
-    state: "Draft"
+    state: "Draft",
+    resetVotes: true,
   }
   callApi(`${SITE_ROOT_PATH}votings/${votingId}`, "PATCH", data)
     .then(async (res) => {
@@ -411,13 +430,46 @@ function makeEditable(votingId) {
     .catch((err) =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        "Jotain meni pieleen! Päivitä sivu!"
+        t("something_went_wrong")
+      )
+    );
+}
+
+// Name and description can be changed while the voting is open without touching cast votes
+function editVotingDetails(votingId, element) {
+  const name = prompt(t("voting_name"), element.dataset.votingName);
+  if (name === null || !name.trim()) {
+    return;
+  }
+  const description = prompt(t("voting_description"), element.dataset.votingDescription);
+  if (description === null) {
+    return;
+  }
+  const agendaGroup = prompt(t("agenda_group"), element.dataset.agendaGroup);
+  if (agendaGroup === null) {
+    return;
+  }
+  callApi(`${SITE_ROOT_PATH}votings/${votingId}`, "PATCH", {
+    name: name.trim(),
+    description: description.trim(),
+    agendaGroup: agendaGroup.trim() || null,
+  })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("voting_edit_failed")
       )
     );
 }
 
 function deleteVoting(votingId) {
-  if (!confirm("Haluatko varmasti poistaa äänestyksen?")) {
+  if (!confirm(t("confirm_delete_voting"))) {
     return
   }
 
@@ -437,7 +489,7 @@ function deleteVoting(votingId) {
     .catch((err) =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        "Jotain meni pieleen! Päivitä sivu!"
+        t("something_went_wrong")
       )
     );
 
@@ -457,7 +509,7 @@ function activateOrInvalidateToken(isActivate, code, number) {
 
   // Require two clicks to activate code
   if (clickedState == "0") {
-    button.innerHTML = isActivate ? "Aktivoi?" : "Mitätöi?";
+    button.innerHTML = isActivate ? t("activate_confirm") : t("void_confirm");
     button.classList.add("orange");
     button.classList.remove(isActivate ? "green" : "red");
     button.dataset["clicked"] = "1";
@@ -475,9 +527,7 @@ function activateOrInvalidateToken(isActivate, code, number) {
     .catch(() =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        `Koodin ${
-          isActivate ? "aktivointi" : "mitätöinti"
-        } epäonnistui. Tarkista koodi.`
+        t(isActivate ? "token_activate_failed" : "token_void_failed")
       )
     );
 }
@@ -493,59 +543,404 @@ function createVoting() {
   //const votingPassword = document.getElementById("voting-add-voting-password")
   //  .value;
   const numberOfWinners = document.getElementById("number-of-winners").value;
+  const decimalPlaces = document.getElementById("decimal-places").value;
   const hideVoteCounts = document.getElementById("hide-vote-counts").checked;
+  const includeRon = document.getElementById("include-ron").checked;
+  const isPublicBallot = document.getElementById("is-public-ballot").checked;
+  const allowRevote = document.getElementById("allow-revote").checked;
+  const agendaGroup = document.getElementById("agenda-group").value.trim();
+  const eligibleGroups = checkedGroupIds("new-voting-eligible-groups");
 
   const data = {
     name,
     description,
+    agendaGroup: agendaGroup || null,
+    eligibleGroups,
     hideVoteCounts,
+    includeRon,
+    isPublicBallot,
+    allowRevote,
     numberOfWinners: parseInt(numberOfWinners),
+    decimalPlaces: decimalPlaces === "" ? null : parseInt(decimalPlaces),
   };
   callApi(`${SITE_ROOT_PATH}votings`, "POST", data)
     .then(() => refreshVotingList(true))
     .catch(() =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        "Äänestyksen luominen ei ehkä onnistunut! Päivitä sivu!"
+        t("voting_create_failed")
       )
     );
 }
 
-function addCandidate(votingId) {
-  const new_candidate = document.getElementById(`voting-${votingId}-candidate-name`).value.trim();
-  const candidates = [ ... document.getElementsByName(`candidate-of-voting-${votingId}`)].map(htmlelem => htmlelem.innerHTML)
+// Votings are reordered on the agenda by dragging their handle in the admin view. A voting can
+// only be moved among the votings of the same list.
+let draggedVoting = null;
+
+document.addEventListener("dragstart", (event) => {
+  if (!event.target.classList?.contains("drag-handle")) {
+    return;
+  }
+  draggedVoting = event.target.closest("[data-sortable-list]");
+  draggedVoting.classList.add("dragging");
+  event.dataTransfer.effectAllowed = "move";
+  event.dataTransfer.setDragImage(draggedVoting, 0, 0);
+});
+
+document.addEventListener("dragover", (event) => {
+  const row = event.target.closest?.("[data-sortable-list]");
+  if (
+    !draggedVoting ||
+    !row ||
+    row === draggedVoting ||
+    row.dataset.sortableList !== draggedVoting.dataset.sortableList
+  ) {
+    return;
+  }
+  event.preventDefault();
+  const rect = row.getBoundingClientRect();
+  const isAfter = event.clientY > rect.top + rect.height / 2;
+  row.parentNode.insertBefore(draggedVoting, isAfter ? row.nextSibling : row);
+});
+
+document.addEventListener("drop", (event) => {
+  if (draggedVoting) {
+    event.preventDefault();
+  }
+});
+
+document.addEventListener("dragend", () => {
+  if (!draggedVoting) {
+    return;
+  }
+  const list = draggedVoting.dataset.sortableList;
+  draggedVoting.classList.remove("dragging");
+  draggedVoting = null;
+
+  const votingIds = [
+    ...document.querySelectorAll(`[data-sortable-list="${list}"]`),
+  ].map((elem) => parseInt(elem.dataset.votingId));
+  callApi(`${SITE_ROOT_PATH}votings/order`, "PUT", { votingIds })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("voting_order_failed")
+      )
+    );
+});
+
+// Fills the new voting form with the settings of the chosen template
+function applyVotingTemplate(select) {
+  const option = select.selectedOptions[0];
+  if (!option.value) {
+    return;
+  }
+  document.getElementById("voting-description").value = option.dataset.description;
+  document.getElementById("hide-vote-counts").checked = option.dataset.hideVoteCounts === "true";
+  document.getElementById("include-ron").checked = option.dataset.includeRon === "true";
+  document.getElementById("is-public-ballot").checked = option.dataset.isPublicBallot === "true";
+  document.getElementById("allow-revote").checked = option.dataset.allowRevote === "true";
+  document.getElementById("number-of-winners").value = option.dataset.numberOfWinners;
+  document.getElementById("decimal-places").value = option.dataset.decimalPlaces;
+  M.updateTextFields();
+}
+
+// Saves the settings of the new voting form as a template
+function saveVotingTemplate() {
+  const name = prompt(t("template_name"));
+  if (!name || !name.trim()) {
+    return;
+  }
+  const decimalPlaces = document.getElementById("decimal-places").value;
   const data = {
-    candidates: candidates.concat(new_candidate)
-  }
-  if (new_candidate) {
-    callApi(`${SITE_ROOT_PATH}votings/${votingId}`, 
-      "PATCH", 
-      data)
-      .then(() => refreshVotingList(true))
-      .catch(() =>
-        showUserNotification(
-          USER_NOTIFICATION.WARNING,
-          "Ehdokkaan lisääminen ei ehkä onnistunut! Päivitä sivu!"
-        )
+    name: name.trim(),
+    description: document.getElementById("voting-description").value,
+    hideVoteCounts: document.getElementById("hide-vote-counts").checked,
+    includeRon: document.getElementById("include-ron").checked,
+    isPublicBallot: document.getElementById("is-public-ballot").checked,
+    allowRevote: document.getElementById("allow-revote").checked,
+    numberOfWinners: parseInt(document.getElementById("number-of-winners").value),
+    decimalPlaces: decimalPlaces === "" ? null : parseInt(decimalPlaces),
+  };
+  callApi(`${SITE_ROOT_PATH}voting-templates`, "POST", data)
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("template_save_failed")
+      )
+    );
+}
+
+function deleteVotingTemplate() {
+  const templateId = document.getElementById("voting-template").value;
+  if (!templateId || !confirm(t("confirm_delete_template"))) {
+    return;
+  }
+  callApi(`${SITE_ROOT_PATH}voting-templates/${templateId}`, "DELETE")
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("template_delete_failed")
+      )
+    );
+}
+
+// Creates the votings of an agenda file as drafts
+function importAgenda(input) {
+  const file = input.files[0];
+  if (!file) {
+    return;
+  }
+  const isYaml = /\.ya?ml$/i.test(file.name);
+  fetch(`${SITE_ROOT_PATH}votings/import`, {
+    method: "POST",
+    headers: {
+      "Content-Type": isYaml ? "application/yaml" : "application/json",
+      "X-CSRFToken": getCookie("csrftoken"),
+      "X-Requested-With": "XMLHttpRequest",
+    },
+    mode: "same-origin",
+    body: file,
+  })
+    .then(async (res) => {
+      if (!res.ok) {
+        throw new Error(await res.text());
+      }
+      const votings = await res.json();
+      showUserNotification(
+        USER_NOTIFICATION.CONFIRMATION,
+        t("agenda_imported", votings.length)
       );
+      refreshVotingList(true);
+    })
+    .catch((err) =>
+      showUserNotification(USER_NOTIFICATION.WARNING, err.message)
+    )
+    .finally(() => (input.value = ""));
+}
+
+// Candidates of a draft voting as they are sent back to the server
+function getDraftCandidates(votingId) {
+  return [ ... document.getElementsByName(`candidate-of-voting-${votingId}`)].map(elem => ({
+    id: parseInt(elem.dataset.candidateId),
+    name: elem.dataset.candidateName,
+    bio: elem.dataset.candidateBio || null,
+  }));
+}
+
+function updateDraftCandidates(votingId, candidates, errorMessage) {
+  return callApi(`${SITE_ROOT_PATH}votings/${votingId}`, "PATCH", { candidates })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(USER_NOTIFICATION.WARNING, errorMessage)
+    );
+}
+
+function addCandidate(votingId) {
+  const name = document.getElementById(`voting-${votingId}-candidate-name`).value.trim();
+  const bio = document.getElementById(`voting-${votingId}-candidate-bio`).value.trim();
+  if (name) {
+    updateDraftCandidates(
+      votingId,
+      getDraftCandidates(votingId).concat({ id: null, name, bio: bio || null }),
+      t("candidate_add_failed")
+    );
   }
 }
 
-function removeCandidate(voting_id, candidate_to_remove) {
-  const candidates = [ ... document.getElementsByName(`candidate-of-voting-${voting_id}`)].map(htmlelem => htmlelem.innerHTML)
-  const data = {
-    candidates: candidates.filter(c => c !== candidate_to_remove)
+function setRon(votingId, includeRon) {
+  callApi(`${SITE_ROOT_PATH}votings/${votingId}`, "PATCH", { includeRon })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("ron_change_failed")
+      )
+    );
+}
+
+function setPublicBallot(votingId, isPublicBallot) {
+  callApi(`${SITE_ROOT_PATH}votings/${votingId}`, "PATCH", { isPublicBallot })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("voting_mode_change_failed")
+      )
+    );
+}
+
+function setAllowRevote(votingId, allowRevote) {
+  callApi(`${SITE_ROOT_PATH}votings/${votingId}`, "PATCH", { allowRevote })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("voting_mode_change_failed")
+      )
+    );
+}
+
+function checkedGroupIds(name) {
+  return Array.from(
+    document.querySelectorAll(`input[name="${name}"]:checked`)
+  ).map((input) => parseInt(input.value));
+}
+
+function setEligibleGroups(votingId) {
+  const eligibleGroups = checkedGroupIds(`eligible-groups-of-voting-${votingId}`);
+  callApi(`${SITE_ROOT_PATH}votings/${votingId}`, "PATCH", { eligibleGroups })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("eligible_groups_failed")
+      )
+    );
+}
+
+function removeCandidate(votingId, candidateId) {
+  updateDraftCandidates(
+    votingId,
+    getDraftCandidates(votingId).filter(c => c.id !== candidateId),
+    t("candidate_delete_failed")
+  );
+}
+
+function editCandidate(votingId, candidateId) {
+  const candidates = getDraftCandidates(votingId);
+  const candidate = candidates.find(c => c.id === candidateId);
+  const name = prompt(t("candidate_name"), candidate.name);
+  if (name === null || !name.trim()) {
+    return;
   }
-  callApi(
-    `${SITE_ROOT_PATH}votings/${voting_id}`,
-    "PATCH",
-    data
-  )
+  const bio = prompt(t("candidate_bio"), candidate.bio || "");
+  if (bio === null) {
+    return;
+  }
+  candidate.name = name.trim();
+  candidate.bio = bio.trim() || null;
+  updateDraftCandidates(
+    votingId,
+    candidates,
+    t("candidate_edit_failed")
+  );
+}
+
+function moveCandidate(votingId, candidateId, direction) {
+  const candidates = getDraftCandidates(votingId);
+  const index = candidates.findIndex(c => c.id === candidateId);
+  const target = index + direction;
+  if (index < 0 || target < 0 || target >= candidates.length) {
+    return;
+  }
+  [candidates[index], candidates[target]] = [candidates[target], candidates[index]];
+  updateDraftCandidates(
+    votingId,
+    candidates,
+    t("candidate_order_failed")
+  );
+}
+
+function withdrawCandidate(candidateId, element) {
+  const name = element.dataset.candidateName;
+  if (!confirm(t("confirm_withdraw_candidate", name))) {
+    return;
+  }
+  callApi(`${SITE_ROOT_PATH}candidates/${candidateId}/withdraw`, "POST", {})
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("candidate_withdraw_failed")
+      )
+    );
+}
+
+function uploadCandidateImage(candidateId, input) {
+  const file = input.files[0];
+  if (!file) {
+    return;
+  }
+  fetch(`${SITE_ROOT_PATH}candidates/${candidateId}/image`, {
+    method: "PUT",
+    headers: {
+      "Content-Type": file.type,
+      "X-CSRFToken": getCookie("csrftoken"),
+      "X-Requested-With": "XMLHttpRequest",
+    },
+    mode: "same-origin",
+    body: file,
+  })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("image_upload_failed")
+      )
+    );
+}
+
+function deleteCandidateImage(candidateId) {
+  callApi(`${SITE_ROOT_PATH}candidates/${candidateId}/image`, "DELETE")
     .then(() => refreshVotingList(true))
     .catch(() =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        "Äänestyksen luominen ei ehkä onnistunut! Päivitä sivu!"
+        t("image_delete_failed")
       )
     );
 }
@@ -574,7 +969,42 @@ function closeVoting(votingId) {
     .catch((err) =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        "Äänestyksen sulkeminen ei ehkä onnistunut! Päivitä sivu!"
+        t("voting_close_failed")
+      )
+    );
+}
+
+function recountVoting(votingId) {
+  const form = document.getElementById(`recount-form-${votingId}`);
+  const numberOfWinners = parseInt(
+    form.querySelector("#recount-number-of-winners").value
+  );
+  const decimalPlacesValue = form.querySelector("#recount-decimal-places").value;
+  const decimalPlaces =
+    decimalPlacesValue === "" ? null : parseInt(decimalPlacesValue);
+  const excludedCandidates = Array.from(
+    form.querySelectorAll("input[name=recount-excluded-candidate]:checked")
+  ).map((input) => parseInt(input.value));
+
+  if (!confirm(t("confirm_recount"))) {
+    return;
+  }
+
+  callApi(`${SITE_ROOT_PATH}votings/${votingId}/recount`, "POST", {
+    numberOfWinners,
+    excludedCandidates,
+    decimalPlaces,
+  })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      location.reload();
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("recount_failed")
       )
     );
 }
@@ -591,7 +1021,67 @@ function openVoting(votingId) {
     .catch(() =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        "Äänestyksen avaaminen ei ehkä onnistunut! Päivitä sivu!"
+        t("voting_open_failed")
+      )
+    );
+}
+
+function setNominating(votingId, nominating) {
+  callApi(`${SITE_ROOT_PATH}votings/${votingId}`, "PATCH", {
+    state: nominating ? "Nominating" : "Draft",
+  })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("nominating_change_failed")
+      )
+    );
+}
+
+function reviewNomination(nominationId, state) {
+  callApi(`${SITE_ROOT_PATH}nominations/${nominationId}`, "PATCH", { state })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      refreshVotingList(true);
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("nomination_review_failed")
+      )
+    );
+}
+
+function nominateCandidate(votingId) {
+  const name = document.getElementById(`nomination-${votingId}-name`).value.trim();
+  const bio = document.getElementById(`nomination-${votingId}-bio`).value.trim();
+  if (!name) {
+    return;
+  }
+  callApi(`${SITE_ROOT_PATH}nominations`, "POST", {
+    votingId,
+    name,
+    bio: bio || null,
+  })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      showUserNotification(USER_NOTIFICATION.CONFIRMATION, t("nomination_sent"));
+      refreshVotingList();
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("nomination_failed")
       )
     );
 }
@@ -620,7 +1110,7 @@ function searchFunction() {
 }
 
 function invalidateActiveTokens() {
-  const userConfirmed = confirm("Oletko varma, että haluat mitätöidä aktiiviset koodit?");
+  const userConfirmed = confirm(t("confirm_void_active_tokens"));
 
   if (!userConfirmed) return
 
@@ -629,13 +1119,100 @@ function invalidateActiveTokens() {
     .catch(() =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        "Koodien mitätöinti epäonnistui!"
+        t("tokens_void_failed")
+      )
+    );
+}
+
+// Voter groups
+
+function createVoterGroup() {
+  const name = document.getElementById("new-voter-group-name").value.trim();
+  if (!name) {
+    return;
+  }
+  callApi(`${SITE_ROOT_PATH}voter-groups`, "POST", { name })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      location.reload();
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("voter_group_create_failed")
+      )
+    );
+}
+
+function deleteVoterGroup(groupId) {
+  if (!confirm(t("confirm_delete_voter_group"))) {
+    return;
+  }
+  callApi(`${SITE_ROOT_PATH}voter-groups/${groupId}`, "DELETE")
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      location.reload();
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("voter_group_delete_failed")
+      )
+    );
+}
+
+// Parses code numbers such as "1-50, 73". Returns null if the text is not valid.
+function parseTokenRanges(text) {
+  const ids = [];
+  for (const part of text.split(",").map((p) => p.trim()).filter((p) => p)) {
+    const match = part.match(/^(\d+)(?:\s*-\s*(\d+))?$/);
+    if (!match) {
+      return null;
+    }
+    const start = parseInt(match[1]);
+    const end = match[2] === undefined ? start : parseInt(match[2]);
+    if (end < start) {
+      return null;
+    }
+    for (let id = start; id <= end; id++) {
+      ids.push(id);
+    }
+  }
+  return ids;
+}
+
+function saveVoterGroupTokens(groupId) {
+  const tokenIds = parseTokenRanges(
+    document.getElementById(`voter-group-${groupId}-tokens`).value
+  );
+  if (tokenIds === null) {
+    showUserNotification(
+      USER_NOTIFICATION.WARNING,
+      t("voter_group_tokens_failed")
+    );
+    return;
+  }
+  callApi(`${SITE_ROOT_PATH}voter-groups/${groupId}/tokens`, "PUT", { tokenIds })
+    .then((res) => {
+      if (!res.ok) {
+        throw new Error(res.statusText);
+      }
+      location.reload();
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("voter_group_tokens_failed")
       )
     );
 }
 
 function deleteAllData() {
-  const userConfirmed = confirm("Tämä poistaa kaikki äänestykset, koodit ja äänet pysyvästi. Jatketaanko?");
+  const userConfirmed = confirm(t("confirm_reset_data"));
 
   if (!userConfirmed) return
 
@@ -644,7 +1221,121 @@ function deleteAllData() {
     .catch(() =>
       showUserNotification(
         USER_NOTIFICATION.WARNING,
-        "Tietojen poisto epäonnistui!"
+        t("reset_data_failed")
+      )
+    );
+}
+
+// Admin accounts
+function callAdminAccountsApi(url, method, body, failMsg) {
+  callApi(url, method, body)
+    .then(async (res) => {
+      if (!res.ok) {
+        throw Error(await res.text());
+      }
+      location.reload();
+    })
+    .catch((error) =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        error.message.length > 0 ? error.message : failMsg
+      )
+    );
+}
+
+function createAdminAccount() {
+  const data = {
+    username: document.getElementById("new-admin-username").value.trim(),
+    password: document.getElementById("new-admin-password").value,
+    role: document.getElementById("new-admin-role").value,
+  };
+  callAdminAccountsApi(
+    `${SITE_ROOT_PATH}admins`,
+    "POST",
+    data,
+    t("admin_create_failed")
+  );
+}
+
+function updateAdminRole(adminId, role) {
+  callAdminAccountsApi(
+    `${SITE_ROOT_PATH}admins/${adminId}`,
+    "PATCH",
+    { role },
+    t("admin_role_failed")
+  );
+}
+
+function resetAdminPassword(adminId) {
+  const password = prompt(t("new_password"));
+
+  if (!password) return
+
+  callAdminAccountsApi(
+    `${SITE_ROOT_PATH}admins/${adminId}`,
+    "PATCH",
+    { password },
+    t("admin_password_failed")
+  );
+}
+
+function deleteAdminAccount(adminId) {
+  if (!confirm(t("confirm_delete_admin"))) {
+    return
+  }
+  callAdminAccountsApi(
+    `${SITE_ROOT_PATH}admins/${adminId}`,
+    "DELETE",
+    {},
+    t("admin_delete_failed")
+  );
+}
+
+function revokeAllAdminSessions() {
+  if (!confirm(t("confirm_logout_admins"))) {
+    return
+  }
+  callAdminAccountsApi(
+    `${SITE_ROOT_PATH}admins/sessions`,
+    "DELETE",
+    {},
+    t("logout_admins_failed")
+  );
+}
+
+function clearLoginFailures() {
+  if (!confirm(t("confirm_clear_lockouts"))) {
+    return
+  }
+  callAdminAccountsApi(
+    `${SITE_ROOT_PATH}security/login-failures`,
+    "DELETE",
+    {},
+    t("clear_lockouts_failed")
+  );
+}
+
+function unlockLoginFailures(button, scope) {
+  callAdminAccountsApi(
+    `${SITE_ROOT_PATH}security/login-failures/${scope}`,
+    "DELETE",
+    { kind: button.dataset.kind, key: button.dataset.key },
+    t("unlock_failed")
+  );
+}
+
+function adminLogout() {
+  callApi(`${SITE_ROOT_PATH}admin/logout`, "POST")
+    .then((res) => {
+      if (!res.ok) {
+        throw Error();
+      }
+      location.reload();
+    })
+    .catch(() =>
+      showUserNotification(
+        USER_NOTIFICATION.WARNING,
+        t("logout_failed")
       )
     );
 }
@@ -729,6 +1420,33 @@ function setupEventListeners() {
       }
     });
   });
+
+  setupPresentation();
+}
+
+// The projector view is re-rendered by the server whenever the voting changes
+function setupPresentation() {
+  const presentation = document.getElementById("voting-presentation");
+  if (!presentation) {
+    return;
+  }
+
+  const content = document.getElementById("presentation-content");
+  const events = new EventSource(presentation.dataset.eventsUrl);
+
+  events.addEventListener("update", (event) => {
+    const wasRevealed = content.querySelector(".presentation-elected") !== null;
+    content.innerHTML = event.data;
+
+    // Browsers only play sounds after the page has been interacted with, e.g. by going full screen
+    if (!wasRevealed && content.querySelector(".presentation-elected") !== null) {
+      document.getElementById("presentation-sound").play().catch(() => {});
+    }
+  });
+}
+
+function enterFullscreen() {
+  document.documentElement.requestFullscreen();
 }
 
 function validateAliasSyntax(aliasInput) {